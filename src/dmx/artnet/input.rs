@@ -0,0 +1,236 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    thread, time,
+};
+
+use artnet_protocol::ArtCommand;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::dmx::merge::{merge_input_universes, DmxInputUniverseData, DmxMergeMode};
+
+use super::{socket::ArtnetSocket, ARTNET_PORT};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct ArtnetInputUniverseConfig {
+    /// The Art-Net universe to listen on
+    pub universe: u16,
+
+    /// The output universe the received data is merged into
+    pub target_universe: u16,
+}
+
+fn default_source_timeout() -> f32 {
+    2.5
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct ArtnetInputConfig {
+    pub bind_ip: Option<String>,
+
+    #[serde(default)]
+    pub universes: Vec<ArtnetInputUniverseConfig>,
+
+    #[serde(default)]
+    pub merge_mode: DmxMergeMode,
+
+    /// Time in seconds after which a source, that didn't send
+    /// any data, is no longer merged into the output.
+    #[serde(default = "default_source_timeout")]
+    pub source_timeout: f32,
+}
+
+impl Default for ArtnetInputConfig {
+    fn default() -> Self {
+        Self {
+            bind_ip: None,
+            universes: Vec::new(),
+            merge_mode: DmxMergeMode::default(),
+            source_timeout: default_source_timeout(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ArtnetInput {
+    config: ArtnetInputConfig,
+
+    // addresses of the local interfaces, packets sent by this console come from
+    local_addresses: Vec<IpAddr>,
+
+    // keyed by the target universe and the source, so multiple
+    // sources sending the same universe are merged
+    universes: HashMap<(u16, SocketAddr), DmxInputUniverseData>,
+}
+
+impl ArtnetInput {
+    pub fn new(config: ArtnetInputConfig) -> Self {
+        Self {
+            config,
+            local_addresses: Vec::new(),
+            universes: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &ArtnetInputConfig {
+        &self.config
+    }
+
+    fn receive(&mut self, universe: u16, data: &[u8], sender: SocketAddr) {
+        // our own outputs (e.g. broadcasts) would otherwise be merged back into themselves
+        if sender.port() == ARTNET_PORT && self.local_addresses.contains(&sender.ip()) {
+            return;
+        }
+
+        let now = time::Instant::now();
+
+        for universe_config in self
+            .config
+            .universes
+            .iter()
+            .filter(|universe_config| universe_config.universe == universe)
+        {
+            self.universes
+                .entry((universe_config.target_universe, sender))
+                .or_insert_with(|| DmxInputUniverseData::new(now))
+                .update(data, now);
+        }
+    }
+
+    /// Merges the received input data into `output_data`, according to the configured merge mode.
+    /// Universes, whose merged data changed since the last call, are added to `dirty_universes`.
+    pub fn merge_into(
+        &mut self,
+        output_data: &mut HashMap<u16, [u8; 512]>,
+        output_changed_at: &HashMap<u16, [time::Instant; 512]>,
+        dirty_universes: &mut BTreeSet<u16>,
+    ) {
+        let source_timeout = time::Duration::from_secs_f32(self.config.source_timeout.max(0.0));
+
+        self.universes.retain(|(universe, sender), input_data| {
+            if input_data.last_received().elapsed() > source_timeout {
+                log::debug!(
+                    "Art-Net input for universe {} from {} timed out",
+                    universe,
+                    sender
+                );
+                dirty_universes.insert(*universe);
+                false
+            } else {
+                true
+            }
+        });
+
+        merge_input_universes(
+            self.universes
+                .iter_mut()
+                .map(|((universe, _), input_data)| (universe, input_data)),
+            self.config.merge_mode,
+            output_data,
+            output_changed_at,
+            dirty_universes,
+        );
+    }
+}
+
+pub fn start_artnet_input_thread(input: Arc<RwLock<ArtnetInput>>) {
+    thread::spawn(move || {
+        log::debug!("Starting ArtNet input thread..");
+
+        let bind_ip = input.read().config().bind_ip.clone();
+
+        let socket = match ArtnetSocket::shared(bind_ip.as_deref()) {
+            Ok(socket) => socket,
+            Err(err) => {
+                log::error!("Failed to bind ArtNet input socket: {}", err);
+                return;
+            }
+        };
+        let packets = socket.subscribe();
+        input.write().local_addresses = socket.local_addresses().to_vec();
+
+        // the input is no longer needed, once the fixture handler dropped its reference
+        while Arc::strong_count(&input) > 1 {
+            let Ok((data, sender)) = packets.recv_timeout(time::Duration::from_millis(500)) else {
+                continue;
+            };
+
+            if let Ok(ArtCommand::Output(output)) = ArtCommand::from_buffer(&data) {
+                let universe: u16 = output.port_address.into();
+                input
+                    .write()
+                    .receive(universe, output.data.as_ref(), sender);
+            }
+        }
+
+        log::debug!("Stopping ArtNet input thread..");
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeSet, HashMap},
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        time,
+    };
+
+    use crate::dmx::artnet::ARTNET_PORT;
+
+    use super::{ArtnetInput, ArtnetInputConfig, ArtnetInputUniverseConfig};
+
+    const LOCAL_IP: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10));
+
+    fn input() -> ArtnetInput {
+        let mut input = ArtnetInput::new(ArtnetInputConfig {
+            universes: vec![ArtnetInputUniverseConfig {
+                universe: 1,
+                target_universe: 0,
+            }],
+            ..Default::default()
+        });
+        input.local_addresses = vec![LOCAL_IP];
+
+        input
+    }
+
+    fn merged_output(input: &mut ArtnetInput, output: [u8; 512]) -> [u8; 512] {
+        let mut output_data = HashMap::from([(0, output)]);
+        let output_changed_at = HashMap::from([(0, [time::Instant::now(); 512])]);
+
+        input.merge_into(&mut output_data, &output_changed_at, &mut BTreeSet::new());
+
+        output_data[&0]
+    }
+
+    #[test]
+    fn test_ignores_own_packets() {
+        let mut input = input();
+
+        // the output broadcasting the universe, the input is listening on
+        input.receive(1, &[255; 512], SocketAddr::new(LOCAL_IP, ARTNET_PORT));
+
+        assert!(input.universes.is_empty());
+        assert_eq!(merged_output(&mut input, [0; 512])[0], 0);
+    }
+
+    #[test]
+    fn test_merges_sources() {
+        let mut input = input();
+
+        let mut data_a = [0; 512];
+        data_a[0] = 200;
+        let mut data_b = [0; 512];
+        data_b[1] = 100;
+
+        input.receive(1, &data_a, "192.168.1.20:6454".parse().unwrap());
+        input.receive(1, &data_b, "192.168.1.30:6454".parse().unwrap());
+
+        let output = merged_output(&mut input, [0; 512]);
+        assert_eq!(output[..2], [200, 100]);
+    }
+}
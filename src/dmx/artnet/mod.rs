@@ -1,16 +1,37 @@
 use std::{
     collections::HashMap,
     net::{self, SocketAddr},
-    sync::mpsc::{self, TryRecvError},
+    sync::{
+        mpsc::{self, TryRecvError},
+        Arc,
+    },
     thread, time,
 };
 
-use artnet_protocol::{ArtCommand, Output, Poll, PortAddress};
+use artnet_protocol::{ArtCommand, Output, Poll};
 use itertools::Itertools;
+use node::ArtnetNodes;
+use parking_lot::RwLock;
+use rdm::{packet::ArtnetRdmCommand, ArtnetRdm};
 use serde::{Deserialize, Serialize};
+use socket::ArtnetSocket;
 
 use super::DmxData;
 
+pub mod input;
+pub mod node;
+pub mod rdm;
+pub mod socket;
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct ArtnetUniverseDestination {
+    pub universe: u16,
+
+    /// IPv4 address of the node
+    pub address: String,
+}
+
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
 pub struct ArtnetOutputConfig {
@@ -23,35 +44,97 @@ pub struct ArtnetOutputConfig {
 
     #[serde(default)]
     pub universes: Vec<u16>,
+
+    /// Send an ArtSync after every output cycle, so nodes
+    /// output all universes at the same time
+    #[serde(default)]
+    pub sync: bool,
+
+    /// Universes, that are sent (unicast) to the given address, instead
+    /// of being broadcasted or sent to the discovered nodes
+    #[serde(default)]
+    pub destinations: Vec<ArtnetUniverseDestination>,
+}
+
+impl ArtnetOutputConfig {
+    fn destination_addresses(&self) -> HashMap<u16, Vec<SocketAddr>> {
+        let mut destination_addresses: HashMap<u16, Vec<SocketAddr>> = HashMap::new();
+
+        for destination in &self.destinations {
+            match destination.address.parse::<net::Ipv4Addr>() {
+                Ok(addr) => destination_addresses
+                    .entry(destination.universe)
+                    .or_default()
+                    .push((addr, ARTNET_PORT).into()),
+                Err(err) => log::warn!(
+                    "Invalid ArtNet destination address {:?} for universe {}: {}",
+                    destination.address,
+                    destination.universe,
+                    err
+                ),
+            }
+        }
+
+        destination_addresses
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct ArtNetOutputNode {
-    pub addr: SocketAddr,
+#[derive(Debug)]
+pub enum ArtnetOutputMessage {
+    Data(DmxData),
+
+    /// Sent after every output cycle, if sync is enabled
+    Sync,
+}
+
+pub(crate) const ARTNET_PORT: u16 = 6454;
+
+pub(crate) const ARTNET_ID: &[u8; 8] = b"Art-Net\0";
+const ARTNET_PROTOCOL_VERSION: u16 = 14;
+
+const ARTNET_OP_SYNC: u16 = 0x5200;
+const ARTNET_OP_ADDRESS: u16 = 0x6000;
+
+pub(crate) fn artnet_header(op_code: u16) -> Vec<u8> {
+    let mut data = ARTNET_ID.to_vec();
+    data.extend(op_code.to_le_bytes());
+    data.extend(ARTNET_PROTOCOL_VERSION.to_be_bytes());
+    data
 }
 
-const ARTNET_PORT: u16 = 6454;
+fn artnet_sync_packet() -> Vec<u8> {
+    let mut data = artnet_header(ARTNET_OP_SYNC);
+    // aux bytes
+    data.extend([0; 2]);
+    data
+}
+
+// ArtSync is broadcasted, but nodes with a unicast destination
+// might not be reachable via the broadcast addresses
+fn sync_addresses(
+    broadcast_addresses: &[SocketAddr],
+    destination_addresses: &HashMap<u16, Vec<SocketAddr>>,
+) -> Vec<SocketAddr> {
+    broadcast_addresses
+        .iter()
+        .chain(destination_addresses.values().flatten())
+        .unique()
+        .copied()
+        .collect()
+}
 
 pub fn start_broadcast_artnet_output_thread(
-    rx: mpsc::Receiver<DmxData>,
+    rx: mpsc::Receiver<ArtnetOutputMessage>,
     config: ArtnetOutputConfig,
 ) {
     thread::spawn(move || {
-        let socket = net::UdpSocket::bind((
-            config.bind_ip.unwrap_or_else(|| "0.0.0.0".to_owned()),
-            ARTNET_PORT,
-        ))
-        .unwrap();
-        socket
-            .set_read_timeout(Some(time::Duration::from_secs(3)))
-            .unwrap();
-
-        #[cfg(unix)]
-        {
-            log::debug!("Setting SO_REUSEADDR (Unix only)");
-            nix::sys::socket::setsockopt(&socket, nix::sys::socket::sockopt::ReuseAddr, &true)
-                .unwrap();
-        }
+        let socket = match ArtnetSocket::shared(config.bind_ip.as_deref()) {
+            Ok(socket) => socket,
+            Err(err) => {
+                log::error!("Failed to bind ArtNet output socket: {}", err);
+                return;
+            }
+        };
 
         let broadcast_addresses = config
             .broadcast_addresses
@@ -59,48 +142,60 @@ pub fn start_broadcast_artnet_output_thread(
             .map(|addr| net::SocketAddr::new(net::IpAddr::V4(addr.parse().unwrap()), ARTNET_PORT))
             .collect::<Vec<_>>();
 
-        socket.set_broadcast(true).unwrap();
+        let destination_addresses = config.destination_addresses();
+        let sync_addresses = sync_addresses(&broadcast_addresses, &destination_addresses);
+        let sync_buff = artnet_sync_packet();
 
         loop {
-            let recv_result = rx.try_recv();
-
-            if let Ok((send_universe, send_universe_data)) = recv_result {
-                let output_command = ArtCommand::Output(Output {
-                    data: Vec::from(send_universe_data).into(),
-                    port_address: send_universe.try_into().unwrap(),
-                    ..Output::default()
-                });
-
-                let command_bytes = output_command.write_to_buffer().unwrap();
-                for addr in &broadcast_addresses {
-                    socket.send_to(&command_bytes, addr).unwrap();
+            match rx.try_recv() {
+                Ok(ArtnetOutputMessage::Data((send_universe, send_universe_data))) => {
+                    let output_command = ArtCommand::Output(Output {
+                        data: Vec::from(send_universe_data).into(),
+                        port_address: send_universe.try_into().unwrap(),
+                        ..Output::default()
+                    });
+
+                    let command_bytes = output_command.write_to_buffer().unwrap();
+                    for addr in destination_addresses
+                        .get(&send_universe)
+                        .unwrap_or(&broadcast_addresses)
+                    {
+                        if let Err(err) = socket.send_to(&command_bytes, *addr) {
+                            log::debug!("Failed to send to {}: {}", addr, err);
+                        }
+                    }
                 }
-            } else if recv_result.err().unwrap() == TryRecvError::Disconnected {
-                break;
+                Ok(ArtnetOutputMessage::Sync) => {
+                    for addr in &sync_addresses {
+                        if let Err(err) = socket.send_to(&sync_buff, *addr) {
+                            log::debug!("Failed to send ArtNet Sync to {}: {}", addr, err);
+                        }
+                    }
+                }
+                Err(TryRecvError::Disconnected) => break,
+                Err(TryRecvError::Empty) => {}
             }
         }
     });
 }
 
-pub fn start_artnet_output_thread(rx: mpsc::Receiver<DmxData>, config: ArtnetOutputConfig) {
+pub fn start_artnet_output_thread(
+    rx: mpsc::Receiver<ArtnetOutputMessage>,
+    config: ArtnetOutputConfig,
+    artnet_nodes: Arc<RwLock<ArtnetNodes>>,
+    rdm: Arc<RwLock<ArtnetRdm>>,
+) {
     thread::spawn(move || {
         log::debug!("Starting ArtNet thread..");
 
-        let socket = net::UdpSocket::bind((
-            config.bind_ip.unwrap_or_else(|| "0.0.0.0".to_owned()),
-            ARTNET_PORT,
-        ))
-        .unwrap();
-        socket
-            .set_read_timeout(Some(time::Duration::from_secs(3)))
-            .unwrap();
-
-        #[cfg(unix)]
-        {
-            log::debug!("Setting SO_REUSEADDR (Unix only)");
-            nix::sys::socket::setsockopt(&socket, nix::sys::socket::sockopt::ReuseAddr, &true)
-                .unwrap();
-        }
+        let socket = match ArtnetSocket::shared(config.bind_ip.as_deref()) {
+            Ok(socket) => socket,
+            Err(err) => {
+                log::error!("Failed to bind ArtNet output socket: {}", err);
+                return;
+            }
+        };
+        let packets = socket.subscribe();
 
         let broadcast_addresses = config
             .broadcast_addresses
@@ -108,46 +203,52 @@ pub fn start_artnet_output_thread(rx: mpsc::Receiver<DmxData>, config: ArtnetOut
             .map(|addr| net::SocketAddr::new(net::IpAddr::V4(addr.parse().unwrap()), ARTNET_PORT))
             .collect::<Vec<_>>();
 
-        socket.set_broadcast(true).unwrap();
-        socket.set_nonblocking(true).unwrap();
+        let destination_addresses = config.destination_addresses();
+        let sync_addresses = sync_addresses(&broadcast_addresses, &destination_addresses);
+        let sync_buff = artnet_sync_packet();
 
-        let mut nodes: HashMap<PortAddress, Vec<ArtNetOutputNode>> = HashMap::new();
+        // the addresses of the discovered nodes, keyed by the universe
+        let mut node_addresses: HashMap<u16, Vec<SocketAddr>> = HashMap::new();
 
         let poll_buff = ArtCommand::Poll(Poll::default()).write_to_buffer().unwrap();
         let mut last_poll_sent: Option<time::Instant> = None;
-        let mut input_buffer = [0u8; 512];
-
         let mut last_socket_in_update = time::Instant::now();
 
         loop {
-            let recv_result = rx.try_recv();
-
-            if let Ok((send_universe, send_universe_data)) = recv_result {
-                let send_universe = send_universe.try_into().unwrap();
-
-                let output_command = ArtCommand::Output(Output {
-                    data: Vec::from(send_universe_data).into(),
-                    port_address: send_universe,
-                    ..Output::default()
-                });
-
-                let command_bytes = output_command.write_to_buffer().unwrap();
-
-                if let Some(nodes) = nodes.get_mut(&send_universe) {
-                    nodes.retain(|node| {
-                        if socket.send_to(&command_bytes, node.addr).is_ok() {
-                            true
-                        } else {
-                            // If we fail to send, the node is probably disconnected and we should remove it
-                            // from the list of nodes.
-                            // If anything else hapended, it should added back to the list with the next poll.
-                            log::debug!("Failed to send to node {:?}, removing it..", node);
-                            false
-                        }
+            match rx.try_recv() {
+                Ok(ArtnetOutputMessage::Data((send_universe, send_universe_data))) => {
+                    let output_command = ArtCommand::Output(Output {
+                        data: Vec::from(send_universe_data).into(),
+                        port_address: send_universe.try_into().unwrap(),
+                        ..Output::default()
                     });
+
+                    let command_bytes = output_command.write_to_buffer().unwrap();
+
+                    if let Some(destination_addresses) = destination_addresses.get(&send_universe) {
+                        for addr in destination_addresses {
+                            if let Err(err) = socket.send_to(&command_bytes, *addr) {
+                                log::debug!("Failed to send to destination {}: {}", addr, err);
+                            }
+                        }
+                    } else if let Some(node_addresses) = node_addresses.get(&send_universe) {
+                        // nodes, that are disconnected, expire once they stop answering our polls
+                        for addr in node_addresses {
+                            if let Err(err) = socket.send_to(&command_bytes, *addr) {
+                                log::debug!("Failed to send to node {}: {}", addr, err);
+                            }
+                        }
+                    }
+                }
+                Ok(ArtnetOutputMessage::Sync) => {
+                    for addr in &sync_addresses {
+                        if let Err(err) = socket.send_to(&sync_buff, *addr) {
+                            log::debug!("Failed to send ArtNet Sync to {}: {}", addr, err);
+                        }
+                    }
                 }
-            } else if recv_result.err().unwrap() == TryRecvError::Disconnected {
-                break;
+                Err(TryRecvError::Disconnected) => break,
+                Err(TryRecvError::Empty) => {}
             }
 
             // handle incoming artnet commands
@@ -157,37 +258,43 @@ pub fn start_artnet_output_thread(rx: mpsc::Receiver<DmxData>, config: ArtnetOut
                 {
                     for addr in &broadcast_addresses {
                         log::debug!("Sending ArtNet Poll to broadcast address {}..", addr);
-                        socket.send_to(&poll_buff, addr).unwrap();
+                        if let Err(err) = socket.send_to(&poll_buff, *addr) {
+                            log::debug!("Failed to send ArtNet Poll to {}: {}", addr, err);
+                        }
                     }
 
                     last_poll_sent = Some(time::Instant::now());
                 }
 
+                // poll again, so the changes made by ArtAddress show up
+                if artnet_nodes.write().send_requests(socket.socket()) {
+                    last_poll_sent = None;
+                }
+
+                rdm.write()
+                    .send_requests(socket.socket(), &broadcast_addresses, &config.universes);
+
+                let mut nodes_changed = artnet_nodes.write().expire();
+
                 // check for incoming data
-                if let Ok((length, _)) = socket.recv_from(&mut input_buffer) {
-                    let command = ArtCommand::from_buffer(&input_buffer[..length]).unwrap();
-
-                    match command {
-                        ArtCommand::PollReply(poll_reply) => {
-                            let net_and_subnet: u16 = ((poll_reply.port_address[0] as u16) << 8)
-                                | ((poll_reply.port_address[1] as u16) << 4);
-
-                            for uni in poll_reply.swout.iter().unique() {
-                                let universe: PortAddress =
-                                    (net_and_subnet | *uni as u16).try_into().unwrap();
-
-                                let node = ArtNetOutputNode {
-                                    addr: (poll_reply.address, ARTNET_PORT).into(),
-                                };
-
-                                let universe_nodes = nodes.entry(universe).or_default();
-                                if !universe_nodes.contains(&node) {
-                                    universe_nodes.push(node);
-                                }
-                            }
-                        }
-                        _ => {}
+                while let Ok((data, sender)) = packets.try_recv() {
+                    if let Some(rdm_command) = ArtnetRdmCommand::from_buffer(&data) {
+                        rdm.write()
+                            .handle_command(rdm_command, sender, socket.socket());
+                        continue;
                     }
+
+                    if let Ok(ArtCommand::PollReply(poll_reply)) = ArtCommand::from_buffer(&data) {
+                        artnet_nodes.write().handle_poll_reply(
+                            (poll_reply.address, ARTNET_PORT).into(),
+                            &poll_reply,
+                        );
+                        nodes_changed = true;
+                    }
+                }
+
+                if nodes_changed {
+                    node_addresses = artnet_nodes.read().universe_addresses();
                 }
 
                 last_socket_in_update = time::Instant::now();
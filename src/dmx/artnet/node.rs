@@ -0,0 +1,309 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::{SocketAddr, UdpSocket},
+    time,
+};
+
+use artnet_protocol::PollReply;
+use itertools::Itertools;
+
+use crate::utils::clock;
+
+use super::{artnet_header, ARTNET_OP_ADDRESS};
+
+const ARTNET_SHORT_NAME_LENGTH: usize = 18;
+const ARTNET_LONG_NAME_LENGTH: usize = 64;
+
+// tells the node to keep the current value of a switch
+const ARTNET_ADDRESS_NO_CHANGE: u8 = 0x7F;
+const ARTNET_ADDRESS_PROGRAM: u8 = 0x80;
+const ARTNET_ADDRESS_COMMAND_NONE: u8 = 0x00;
+
+// nodes, that didn't answer the last few polls, are considered disconnected
+const ARTNET_NODE_TIMEOUT: time::Duration = time::Duration::from_secs(10);
+
+/// Changes, that are programmed into a node using `ArtAddress`.
+/// Values that are `None` are left unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArtnetNodeAddress {
+    pub short_name: Option<String>,
+    pub long_name: Option<String>,
+
+    /// The port address of the first output port of the node
+    pub port_address: Option<u16>,
+}
+
+impl ArtnetNodeAddress {
+    pub fn write_to_buffer(&self, bind_index: u8) -> Vec<u8> {
+        let mut data = artnet_header(ARTNET_OP_ADDRESS);
+
+        data.push(
+            self.port_address
+                .map(|port_address| ARTNET_ADDRESS_PROGRAM | ((port_address >> 8) as u8 & 0x7F))
+                .unwrap_or(ARTNET_ADDRESS_NO_CHANGE),
+        );
+        data.push(bind_index);
+
+        data.extend(artnet_name(
+            self.short_name.as_deref(),
+            ARTNET_SHORT_NAME_LENGTH,
+        ));
+        data.extend(artnet_name(
+            self.long_name.as_deref(),
+            ARTNET_LONG_NAME_LENGTH,
+        ));
+
+        // input ports
+        data.extend([ARTNET_ADDRESS_NO_CHANGE; 4]);
+
+        // output ports, only the first one is programmed
+        data.push(
+            self.port_address
+                .map(|port_address| ARTNET_ADDRESS_PROGRAM | (port_address as u8 & 0x0F))
+                .unwrap_or(ARTNET_ADDRESS_NO_CHANGE),
+        );
+        data.extend([ARTNET_ADDRESS_NO_CHANGE; 3]);
+
+        // sub switch
+        data.push(
+            self.port_address
+                .map(|port_address| ARTNET_ADDRESS_PROGRAM | ((port_address >> 4) as u8 & 0x0F))
+                .unwrap_or(ARTNET_ADDRESS_NO_CHANGE),
+        );
+
+        // acn priority
+        data.push(0xFF);
+        data.push(ARTNET_ADDRESS_COMMAND_NONE);
+
+        data
+    }
+}
+
+// An empty name (all zeros) tells the node to keep its current name
+fn artnet_name(name: Option<&str>, length: usize) -> Vec<u8> {
+    let mut data: Vec<u8> = name
+        .map(|name| name.as_bytes().iter().take(length - 1).copied().collect())
+        .unwrap_or_default();
+    data.resize(length, 0);
+    data
+}
+
+fn artnet_name_from_bytes(data: &[u8]) -> String {
+    String::from_utf8_lossy(data)
+        .trim_end_matches('\0')
+        .trim()
+        .to_owned()
+}
+
+/// Identifies a node. Nodes with more than four ports answer with one
+/// `ArtPollReply` per group of ports, each with its own bind index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArtnetNodeId {
+    pub addr: SocketAddr,
+    pub bind_index: u8,
+}
+
+/// A node, that answered our `ArtPoll`.
+#[derive(Debug, Clone)]
+pub struct ArtnetNode {
+    id: ArtnetNodeId,
+    short_name: String,
+    long_name: String,
+    port_addresses: Vec<u16>,
+    last_seen: time::Instant,
+}
+
+impl ArtnetNode {
+    pub fn id(&self) -> ArtnetNodeId {
+        self.id
+    }
+
+    pub fn short_name(&self) -> &str {
+        &self.short_name
+    }
+
+    pub fn long_name(&self) -> &str {
+        &self.long_name
+    }
+
+    /// The port addresses of the output ports of the node.
+    pub fn port_addresses(&self) -> &[u16] {
+        &self.port_addresses
+    }
+
+    pub fn last_seen(&self) -> time::Instant {
+        self.last_seen
+    }
+}
+
+/// The nodes discovered by an Art-Net output.
+#[derive(Debug, Default)]
+pub struct ArtnetNodes {
+    nodes: BTreeMap<ArtnetNodeId, ArtnetNode>,
+    requests: Vec<(ArtnetNodeId, ArtnetNodeAddress)>,
+}
+
+impl ArtnetNodes {
+    pub fn nodes(&self) -> impl Iterator<Item = &ArtnetNode> {
+        self.nodes.values()
+    }
+
+    /// Renames and/or re-universes the node `id` using `ArtAddress`.
+    pub fn set_address(&mut self, id: ArtnetNodeId, address: ArtnetNodeAddress) {
+        self.requests.push((id, address));
+    }
+
+    pub(super) fn handle_poll_reply(&mut self, addr: SocketAddr, poll_reply: &PollReply) {
+        let net_and_subnet: u16 =
+            ((poll_reply.port_address[0] as u16) << 8) | ((poll_reply.port_address[1] as u16) << 4);
+
+        self.insert(ArtnetNode {
+            id: ArtnetNodeId {
+                addr,
+                bind_index: poll_reply.bind_index,
+            },
+            short_name: artnet_name_from_bytes(&poll_reply.short_name),
+            long_name: artnet_name_from_bytes(&poll_reply.long_name),
+            port_addresses: poll_reply
+                .swout
+                .iter()
+                .unique()
+                .map(|universe| net_and_subnet | *universe as u16)
+                .collect(),
+            last_seen: clock::now(),
+        });
+    }
+
+    // A reply only replaces the ports of its own bind index, so
+    // the universes of the other binds of the node are kept
+    fn insert(&mut self, node: ArtnetNode) {
+        self.nodes.insert(node.id, node);
+    }
+
+    /// Removes the nodes, that stopped answering our polls, and returns, whether any node was removed.
+    pub(super) fn expire(&mut self) -> bool {
+        let num_nodes = self.nodes.len();
+
+        self.nodes.retain(|id, node| {
+            let is_alive = clock::elapsed(node.last_seen) <= ARTNET_NODE_TIMEOUT;
+
+            if !is_alive {
+                log::debug!("ArtNet node {:?} stopped answering, removing it..", id);
+            }
+
+            is_alive
+        });
+
+        self.nodes.len() != num_nodes
+    }
+
+    /// The addresses of the nodes, that output a universe, keyed by the universe.
+    pub(super) fn universe_addresses(&self) -> HashMap<u16, Vec<SocketAddr>> {
+        let mut universe_addresses: HashMap<u16, Vec<SocketAddr>> = HashMap::new();
+
+        for node in self.nodes.values() {
+            for port_address in &node.port_addresses {
+                let addresses = universe_addresses.entry(*port_address).or_default();
+                if !addresses.contains(&node.id.addr) {
+                    addresses.push(node.id.addr);
+                }
+            }
+        }
+
+        universe_addresses
+    }
+
+    /// Sends the pending `ArtAddress` requests and returns, whether a request was sent.
+    pub(super) fn send_requests(&mut self, socket: &UdpSocket) -> bool {
+        let requests = std::mem::take(&mut self.requests);
+
+        for (id, address) in &requests {
+            log::debug!("Sending ArtNet Address to {:?}: {:?}", id, address);
+
+            if let Err(err) = socket.send_to(&address.write_to_buffer(id.bind_index), id.addr) {
+                log::warn!("Failed to send ArtNet Address to {:?}: {}", id, err);
+            }
+        }
+
+        !requests.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, time};
+
+    use crate::utils::clock;
+
+    use super::{ArtnetNode, ArtnetNodeAddress, ArtnetNodeId, ArtnetNodes};
+
+    #[test]
+    fn test_artnet_address_packet() {
+        let data = ArtnetNodeAddress {
+            short_name: Some("Truss Left".to_owned()),
+            long_name: None,
+            port_address: Some(0x0123),
+        }
+        .write_to_buffer(0);
+
+        assert_eq!(data.len(), 107);
+        assert_eq!(&data[..8], b"Art-Net\0");
+
+        // net switch
+        assert_eq!(data[12], 0x81);
+        assert_eq!(&data[14..24], b"Truss Left");
+        assert_eq!(data[24], 0);
+
+        // long name is unchanged
+        assert!(data[32..96].iter().all(|byte| *byte == 0));
+
+        // first output port and sub switch
+        assert_eq!(data[100], 0x83);
+        assert_eq!(data[101], 0x7F);
+        assert_eq!(data[104], 0x82);
+    }
+
+    fn node(addr: SocketAddr, bind_index: u8, port_addresses: Vec<u16>) -> ArtnetNode {
+        ArtnetNode {
+            id: ArtnetNodeId { addr, bind_index },
+            short_name: String::new(),
+            long_name: String::new(),
+            port_addresses,
+            last_seen: clock::now(),
+        }
+    }
+
+    #[test]
+    fn test_artnet_nodes_bind_indices() {
+        clock::start_virtual_clock();
+
+        let addr: SocketAddr = "10.0.0.5:6454".parse().unwrap();
+        let mut nodes = ArtnetNodes::default();
+
+        nodes.insert(node(addr, 1, vec![0, 1, 2, 3]));
+        nodes.insert(node(addr, 2, vec![4, 5]));
+
+        let universe_addresses = nodes.universe_addresses();
+        assert_eq!(universe_addresses.len(), 6);
+        assert_eq!(universe_addresses[&0], vec![addr]);
+        assert_eq!(universe_addresses[&5], vec![addr]);
+
+        // re-universing the second bind keeps the universes of the first one
+        nodes.insert(node(addr, 2, vec![6]));
+
+        let universe_addresses = nodes.universe_addresses();
+        assert!(universe_addresses.contains_key(&3));
+        assert!(universe_addresses.contains_key(&6));
+        assert!(!universe_addresses.contains_key(&4));
+
+        clock::set_virtual_time(time::Duration::from_secs(8));
+        nodes.insert(node(addr, 1, vec![0]));
+
+        clock::set_virtual_time(time::Duration::from_secs(12));
+        assert!(nodes.expire());
+        assert_eq!(
+            nodes.universe_addresses().keys().collect::<Vec<_>>(),
+            vec![&0]
+        );
+    }
+}
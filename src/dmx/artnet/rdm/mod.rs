@@ -0,0 +1,333 @@
+use std::{
+    collections::BTreeMap,
+    net::{SocketAddr, UdpSocket},
+};
+
+use itertools::Itertools;
+use packet::{
+    ArtnetRdmCommand, RdmCommandClass, RdmDeviceInfo, RdmPacket, RdmParameter, RdmUid,
+    RDM_RESPONSE_TYPE_ACK,
+};
+
+use crate::fixture::{
+    gdtf::{dmx_mode_address_footprint, modifier::FixtureOutputModifiers, GdtfFixturePatch},
+    handler::FixtureTypeList,
+};
+
+pub mod packet;
+
+// Parameters, that are requested for every discovered device
+const RDM_DEVICE_PARAMETERS: [RdmParameter; 5] = [
+    RdmParameter::DeviceInfo,
+    RdmParameter::DmxStartAddress,
+    RdmParameter::DmxPersonality,
+    RdmParameter::ManufacturerLabel,
+    RdmParameter::DeviceModelDescription,
+];
+
+#[derive(Debug, Clone)]
+pub struct ArtnetRdmDevice {
+    uid: RdmUid,
+    universe: u16,
+
+    // the Art-Net node, the device is connected to
+    node: SocketAddr,
+
+    device_info: Option<RdmDeviceInfo>,
+    start_address: Option<u16>,
+    personality: Option<(u8, u8)>,
+    manufacturer_label: Option<String>,
+    model_description: Option<String>,
+}
+
+impl ArtnetRdmDevice {
+    fn new(uid: RdmUid, universe: u16, node: SocketAddr) -> Self {
+        Self {
+            uid,
+            universe,
+            node,
+            device_info: None,
+            start_address: None,
+            personality: None,
+            manufacturer_label: None,
+            model_description: None,
+        }
+    }
+
+    pub fn uid(&self) -> RdmUid {
+        self.uid
+    }
+
+    pub fn universe(&self) -> u16 {
+        self.universe
+    }
+
+    pub fn device_info(&self) -> Option<&RdmDeviceInfo> {
+        self.device_info.as_ref()
+    }
+
+    pub fn start_address(&self) -> Option<u16> {
+        self.start_address
+    }
+
+    /// The current personality and the number of personalities of the device.
+    pub fn personality(&self) -> Option<(u8, u8)> {
+        self.personality
+    }
+
+    pub fn manufacturer_label(&self) -> Option<&str> {
+        self.manufacturer_label.as_deref()
+    }
+
+    pub fn model_description(&self) -> Option<&str> {
+        self.model_description.as_deref()
+    }
+
+    /// Proposes a patch entry for the device, if a fixture type matches the manufacturer label
+    /// and model description of the device and has a dmx mode with the footprint of the device.
+    /// The dmx mode at the index of the current personality is preferred.
+    pub fn propose_patch(
+        &self,
+        fixture_types: &FixtureTypeList,
+        fixture_id: u32,
+    ) -> Option<GdtfFixturePatch> {
+        let device_info = self.device_info.as_ref()?;
+        let start_address = self.start_address?;
+        let manufacturer_label = self.manufacturer_label.as_deref()?.trim().to_lowercase();
+        let model_description = self.model_description.as_deref()?.trim().to_lowercase();
+
+        let (fixture_type, dmx_mode) = fixture_types
+            .iter()
+            .filter(|fixture_type| {
+                fixture_type.manufacturer.to_string().trim().to_lowercase() == manufacturer_label
+            })
+            .filter(|fixture_type| {
+                let long_name = fixture_type.long_name.to_string().trim().to_lowercase();
+                long_name.contains(&model_description) || model_description.contains(&long_name)
+            })
+            .find_map(|fixture_type| {
+                let personality_idx = self
+                    .personality
+                    .map(|(current_personality, _)| current_personality as usize);
+
+                fixture_type
+                    .dmx_modes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, dmx_mode)| {
+                        dmx_mode_address_footprint(dmx_mode)
+                            .is_ok_and(|footprint| footprint == device_info.footprint)
+                    })
+                    // personalities start at 1
+                    .sorted_by_key(|(idx, _)| Some(idx + 1) != personality_idx)
+                    .map(|(_, dmx_mode)| (fixture_type, dmx_mode))
+                    .next()
+            })?;
+
+        let dmx_mode_name: &str = dmx_mode.name.as_ref();
+
+        Some(GdtfFixturePatch {
+            id: fixture_id,
+            name: format!("{} {}", fixture_type.long_name, fixture_id),
+            fixture_type_id: fixture_type.fixture_type_id,
+            fixture_type_dmx_mode: dmx_mode_name.to_owned(),
+            universe: self.universe,
+            start_address,
+            modifiers: FixtureOutputModifiers::default(),
+        })
+    }
+
+    fn handle_response(&mut self, packet: &RdmPacket) {
+        match packet.parameter {
+            RdmParameter::DeviceInfo => {
+                if let Some(device_info) = RdmDeviceInfo::decode(&packet.parameter_data) {
+                    self.start_address = Some(device_info.start_address);
+                    self.personality = Some((
+                        device_info.current_personality,
+                        device_info.personality_count,
+                    ));
+                    self.device_info = Some(device_info);
+                }
+            }
+            RdmParameter::DmxStartAddress => {
+                if let [high, low] = packet.parameter_data[..] {
+                    self.start_address = Some(u16::from_be_bytes([high, low]));
+                }
+            }
+            RdmParameter::DmxPersonality => {
+                if let [current_personality, personality_count] = packet.parameter_data[..] {
+                    self.personality = Some((current_personality, personality_count));
+                }
+            }
+            RdmParameter::ManufacturerLabel => {
+                self.manufacturer_label = Some(rdm_label(&packet.parameter_data));
+            }
+            RdmParameter::DeviceModelDescription => {
+                self.model_description = Some(rdm_label(&packet.parameter_data));
+            }
+        }
+    }
+}
+
+fn rdm_label(parameter_data: &[u8]) -> String {
+    String::from_utf8_lossy(parameter_data)
+        .trim_end_matches('\0')
+        .trim()
+        .to_owned()
+}
+
+#[derive(Debug)]
+enum ArtnetRdmRequest {
+    Discovery,
+    SetStartAddress(RdmUid, u16),
+}
+
+/// RDM devices, that were discovered by an Art-Net output.
+#[derive(Debug, Default)]
+pub struct ArtnetRdm {
+    devices: BTreeMap<RdmUid, ArtnetRdmDevice>,
+    requests: Vec<ArtnetRdmRequest>,
+    transaction_number: u8,
+}
+
+impl ArtnetRdm {
+    pub fn devices(&self) -> impl Iterator<Item = &ArtnetRdmDevice> {
+        self.devices.values()
+    }
+
+    /// Requests the table of devices of every universe of the output.
+    pub fn discover(&mut self) {
+        self.requests.push(ArtnetRdmRequest::Discovery);
+    }
+
+    pub fn set_start_address(&mut self, uid: RdmUid, start_address: u16) {
+        self.requests
+            .push(ArtnetRdmRequest::SetStartAddress(uid, start_address));
+    }
+
+    fn send_request(
+        &mut self,
+        socket: &UdpSocket,
+        uid: RdmUid,
+        command_class: RdmCommandClass,
+        parameter: RdmParameter,
+        parameter_data: Vec<u8>,
+    ) {
+        let Some(device) = self.devices.get(&uid) else {
+            return;
+        };
+
+        let command = ArtnetRdmCommand::Rdm {
+            port_address: device.universe,
+            packet: RdmPacket::request(
+                uid,
+                self.transaction_number,
+                command_class,
+                parameter,
+                parameter_data,
+            ),
+        };
+
+        self.transaction_number = self.transaction_number.wrapping_add(1);
+
+        if let Err(err) = socket.send_to(&command.write_to_buffer(), device.node) {
+            log::warn!("Failed to send RDM request to {}: {}", uid, err);
+        }
+    }
+
+    /// Sends the pending requests. Discovery requests are broadcasted for every universe of the output.
+    pub(super) fn send_requests(
+        &mut self,
+        socket: &UdpSocket,
+        broadcast_addresses: &[SocketAddr],
+        universes: &[u16],
+    ) {
+        for request in std::mem::take(&mut self.requests) {
+            match request {
+                ArtnetRdmRequest::Discovery => {
+                    self.devices.clear();
+
+                    for (net, universes) in &universes
+                        .iter()
+                        .sorted()
+                        .chunk_by(|universe| (*universe >> 8) as u8)
+                    {
+                        let command = ArtnetRdmCommand::TodRequest {
+                            net,
+                            addresses: universes.map(|universe| *universe as u8).collect(),
+                        };
+
+                        for addr in broadcast_addresses {
+                            log::debug!("Sending ArtNet TodRequest to {}..", addr);
+
+                            if let Err(err) = socket.send_to(&command.write_to_buffer(), addr) {
+                                log::warn!("Failed to send ArtNet TodRequest: {}", err);
+                            }
+                        }
+                    }
+                }
+                ArtnetRdmRequest::SetStartAddress(uid, start_address) => self.send_request(
+                    socket,
+                    uid,
+                    RdmCommandClass::Set,
+                    RdmParameter::DmxStartAddress,
+                    start_address.to_be_bytes().to_vec(),
+                ),
+            }
+        }
+    }
+
+    pub(super) fn handle_command(
+        &mut self,
+        command: ArtnetRdmCommand,
+        sender: SocketAddr,
+        socket: &UdpSocket,
+    ) {
+        match command {
+            ArtnetRdmCommand::TodData { port_address, uids } => {
+                for uid in uids {
+                    if self.devices.contains_key(&uid) {
+                        continue;
+                    }
+
+                    log::debug!("Discovered RDM device {} on universe {}", uid, port_address);
+                    self.devices
+                        .insert(uid, ArtnetRdmDevice::new(uid, port_address, sender));
+
+                    for parameter in RDM_DEVICE_PARAMETERS {
+                        self.send_request(socket, uid, RdmCommandClass::Get, parameter, Vec::new());
+                    }
+                }
+            }
+            ArtnetRdmCommand::Rdm { packet, .. } => {
+                if packet.port_or_response_type != RDM_RESPONSE_TYPE_ACK {
+                    log::debug!(
+                        "RDM device {} didn't acknowledge {:?}",
+                        packet.source,
+                        packet.parameter
+                    );
+                    return;
+                }
+
+                match packet.command_class {
+                    RdmCommandClass::GetResponse => {
+                        if let Some(device) = self.devices.get_mut(&packet.source) {
+                            device.handle_response(&packet);
+                        }
+                    }
+                    // read back the changed value
+                    RdmCommandClass::SetResponse => self.send_request(
+                        socket,
+                        packet.source,
+                        RdmCommandClass::Get,
+                        packet.parameter,
+                        Vec::new(),
+                    ),
+                    RdmCommandClass::Get | RdmCommandClass::Set => {}
+                }
+            }
+            // sent by other controllers (or ourselves)
+            ArtnetRdmCommand::TodRequest { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use std::fmt;
+
+use crate::dmx::artnet::{artnet_header, ARTNET_ID};
+
+const ARTNET_OP_TOD_REQUEST: u16 = 0x8000;
+const ARTNET_OP_TOD_DATA: u16 = 0x8100;
+const ARTNET_OP_RDM: u16 = 0x8300;
+
+const ARTNET_RDM_VERSION: u8 = 0x01;
+const ARTNET_TOD_FULL: u8 = 0x00;
+const ARTNET_RDM_PROCESS: u8 = 0x00;
+
+const RDM_START_CODE: u8 = 0xCC;
+const RDM_SUB_START_CODE: u8 = 0x01;
+
+// start code, sub start code, message length, uids, transaction number,
+// port id, message count, sub device, command class, parameter id and parameter data length
+const RDM_HEADER_LENGTH: usize = 24;
+
+pub const RDM_RESPONSE_TYPE_ACK: u8 = 0x00;
+
+/// Unique id of an RDM device (manufacturer id and device id).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RdmUid {
+    pub manufacturer_id: u16,
+    pub device_id: u32,
+}
+
+impl RdmUid {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            manufacturer_id: u16::from_be_bytes([bytes[0], bytes[1]]),
+            device_id: u32::from_be_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]),
+        }
+    }
+
+    fn to_bytes(self) -> [u8; 6] {
+        let manufacturer_id = self.manufacturer_id.to_be_bytes();
+        let device_id = self.device_id.to_be_bytes();
+
+        [
+            manufacturer_id[0],
+            manufacturer_id[1],
+            device_id[0],
+            device_id[1],
+            device_id[2],
+            device_id[3],
+        ]
+    }
+}
+
+impl fmt::Display for RdmUid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04X}:{:08X}", self.manufacturer_id, self.device_id)
+    }
+}
+
+/// UID used by demex as the source of RDM requests (taken from the prototyping range of manufacturer ids).
+pub const DEMEX_RDM_UID: RdmUid = RdmUid {
+    manufacturer_id: 0x7FF0,
+    device_id: 0x0000_0001,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RdmCommandClass {
+    Get = 0x20,
+    GetResponse = 0x21,
+    Set = 0x30,
+    SetResponse = 0x31,
+}
+
+impl TryFrom<u8> for RdmCommandClass {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x20 => Ok(Self::Get),
+            0x21 => Ok(Self::GetResponse),
+            0x30 => Ok(Self::Set),
+            0x31 => Ok(Self::SetResponse),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RdmParameter {
+    DeviceInfo = 0x0060,
+    DeviceModelDescription = 0x0080,
+    ManufacturerLabel = 0x0081,
+    DmxPersonality = 0x00E0,
+    DmxStartAddress = 0x00F0,
+}
+
+impl TryFrom<u16> for RdmParameter {
+    type Error = ();
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0x0060 => Ok(Self::DeviceInfo),
+            0x0080 => Ok(Self::DeviceModelDescription),
+            0x0081 => Ok(Self::ManufacturerLabel),
+            0x00E0 => Ok(Self::DmxPersonality),
+            0x00F0 => Ok(Self::DmxStartAddress),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RdmPacket {
+    pub destination: RdmUid,
+    pub source: RdmUid,
+    pub transaction_number: u8,
+
+    /// Port id for requests, response type for responses
+    pub port_or_response_type: u8,
+
+    pub sub_device: u16,
+    pub command_class: RdmCommandClass,
+    pub parameter: RdmParameter,
+    pub parameter_data: Vec<u8>,
+}
+
+impl RdmPacket {
+    pub fn request(
+        destination: RdmUid,
+        transaction_number: u8,
+        command_class: RdmCommandClass,
+        parameter: RdmParameter,
+        parameter_data: Vec<u8>,
+    ) -> Self {
+        Self {
+            destination,
+            source: DEMEX_RDM_UID,
+            transaction_number,
+            port_or_response_type: 0x01,
+            sub_device: 0,
+            command_class,
+            parameter,
+            parameter_data,
+        }
+    }
+
+    /// Encodes the packet, including the start code and the checksum.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(RDM_HEADER_LENGTH + self.parameter_data.len() + 2);
+
+        data.push(RDM_START_CODE);
+        data.push(RDM_SUB_START_CODE);
+        data.push((RDM_HEADER_LENGTH + self.parameter_data.len()) as u8);
+        data.extend(self.destination.to_bytes());
+        data.extend(self.source.to_bytes());
+        data.push(self.transaction_number);
+        data.push(self.port_or_response_type);
+        // message count
+        data.push(0);
+        data.extend(self.sub_device.to_be_bytes());
+        data.push(self.command_class as u8);
+        data.extend((self.parameter as u16).to_be_bytes());
+        data.push(self.parameter_data.len() as u8);
+        data.extend(&self.parameter_data);
+
+        let checksum = rdm_checksum(&data);
+        data.extend(checksum.to_be_bytes());
+
+        data
+    }
+
+    /// Decodes a packet, including the start code. Returns `None`, if the packet is invalid
+    /// or uses an unsupported command class or parameter.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < RDM_HEADER_LENGTH + 2
+            || data[0] != RDM_START_CODE
+            || data[1] != RDM_SUB_START_CODE
+        {
+            return None;
+        }
+
+        let message_length = data[2] as usize;
+        let parameter_data_length = data[23] as usize;
+
+        if message_length != RDM_HEADER_LENGTH + parameter_data_length
+            || data.len() < message_length + 2
+        {
+            return None;
+        }
+
+        let checksum = u16::from_be_bytes([data[message_length], data[message_length + 1]]);
+        if checksum != rdm_checksum(&data[..message_length]) {
+            return None;
+        }
+
+        Some(Self {
+            destination: RdmUid::from_bytes(&data[3..9]),
+            source: RdmUid::from_bytes(&data[9..15]),
+            transaction_number: data[15],
+            port_or_response_type: data[16],
+            sub_device: u16::from_be_bytes([data[18], data[19]]),
+            command_class: RdmCommandClass::try_from(data[20]).ok()?,
+            parameter: RdmParameter::try_from(u16::from_be_bytes([data[21], data[22]])).ok()?,
+            parameter_data: data[RDM_HEADER_LENGTH..message_length].to_vec(),
+        })
+    }
+}
+
+fn rdm_checksum(data: &[u8]) -> u16 {
+    data.iter()
+        .fold(0u16, |checksum, byte| checksum.wrapping_add(*byte as u16))
+}
+
+/// The parts of the `DEVICE_INFO` parameter data, that are used by demex.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RdmDeviceInfo {
+    pub device_model_id: u16,
+    pub footprint: u16,
+    pub current_personality: u8,
+    pub personality_count: u8,
+    pub start_address: u16,
+}
+
+impl RdmDeviceInfo {
+    pub fn decode(parameter_data: &[u8]) -> Option<Self> {
+        if parameter_data.len() < 19 {
+            return None;
+        }
+
+        Some(Self {
+            device_model_id: u16::from_be_bytes([parameter_data[2], parameter_data[3]]),
+            footprint: u16::from_be_bytes([parameter_data[10], parameter_data[11]]),
+            current_personality: parameter_data[12],
+            personality_count: parameter_data[13],
+            start_address: u16::from_be_bytes([parameter_data[14], parameter_data[15]]),
+        })
+    }
+}
+
+/// The Art-Net packets used for RDM, which are encoded by demex itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArtnetRdmCommand {
+    TodRequest {
+        net: u8,
+        addresses: Vec<u8>,
+    },
+    TodData {
+        port_address: u16,
+        uids: Vec<RdmUid>,
+    },
+    Rdm {
+        port_address: u16,
+        packet: RdmPacket,
+    },
+}
+
+fn port_address(net: u8, address: u8) -> u16 {
+    ((net as u16 & 0x7F) << 8) | address as u16
+}
+
+impl ArtnetRdmCommand {
+    pub fn write_to_buffer(&self) -> Vec<u8> {
+        match self {
+            Self::TodRequest { net, addresses } => {
+                let mut data = artnet_header(ARTNET_OP_TOD_REQUEST);
+                // filler and spare bytes
+                data.extend([0; 9]);
+                data.push(*net);
+                data.push(ARTNET_TOD_FULL);
+                data.push(addresses.len().min(32) as u8);
+                data.extend(addresses.iter().take(32));
+                data
+            }
+            Self::TodData { port_address, uids } => {
+                let mut data = artnet_header(ARTNET_OP_TOD_DATA);
+                data.push(ARTNET_RDM_VERSION);
+                // port
+                data.push(1);
+                // spare bytes and bind index
+                data.extend([0; 7]);
+                data.push((port_address >> 8) as u8);
+                data.push(ARTNET_TOD_FULL);
+                data.push(*port_address as u8);
+                data.extend((uids.len() as u16).to_be_bytes());
+                // block count
+                data.push(0);
+                data.push(uids.len() as u8);
+                data.extend(uids.iter().flat_map(|uid| uid.to_bytes()));
+                data
+            }
+            Self::Rdm {
+                port_address,
+                packet,
+            } => {
+                let mut data = artnet_header(ARTNET_OP_RDM);
+                data.push(ARTNET_RDM_VERSION);
+                // filler and spare bytes
+                data.extend([0; 8]);
+                data.push((port_address >> 8) as u8);
+                data.push(ARTNET_RDM_PROCESS);
+                data.push(*port_address as u8);
+                // Art-Net transports the RDM packet without the start code
+                data.extend(&packet.encode()[1..]);
+                data
+            }
+        }
+    }
+
+    /// Decodes an Art-Net RDM packet. Returns `None` for every other
+    /// Art-Net packet (e.g. `ArtPollReply`) or if the packet is invalid.
+    pub fn from_buffer(buffer: &[u8]) -> Option<Self> {
+        if buffer.len() < 24 || &buffer[..8] != ARTNET_ID {
+            return None;
+        }
+
+        match u16::from_le_bytes([buffer[8], buffer[9]]) {
+            ARTNET_OP_TOD_REQUEST => {
+                let address_count = (buffer[23] as usize).min(buffer.len() - 24);
+
+                Some(Self::TodRequest {
+                    net: buffer[21],
+                    addresses: buffer[24..24 + address_count].to_vec(),
+                })
+            }
+            ARTNET_OP_TOD_DATA => {
+                let uid_count = *buffer.get(27)? as usize;
+                let uids = buffer.get(28..28 + uid_count * 6)?;
+
+                Some(Self::TodData {
+                    port_address: port_address(buffer[21], buffer[23]),
+                    uids: uids.chunks_exact(6).map(RdmUid::from_bytes).collect(),
+                })
+            }
+            ARTNET_OP_RDM => {
+                let mut rdm_data = vec![RDM_START_CODE];
+                rdm_data.extend(&buffer[24..]);
+
+                Some(Self::Rdm {
+                    port_address: port_address(buffer[21], buffer[23]),
+                    packet: RdmPacket::decode(&rdm_data)?,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ArtnetRdmCommand, RdmCommandClass, RdmPacket, RdmParameter, RdmUid, DEMEX_RDM_UID,
+    };
+
+    #[test]
+    fn test_rdm_packet_roundtrip() {
+        let packet = RdmPacket::request(
+            RdmUid {
+                manufacturer_id: 0x4D41,
+                device_id: 0x1234_5678,
+            },
+            7,
+            RdmCommandClass::Set,
+            RdmParameter::DmxStartAddress,
+            42u16.to_be_bytes().to_vec(),
+        );
+
+        let data = packet.encode();
+        assert_eq!(data.len(), 24 + 2 + 2);
+        assert_eq!(RdmPacket::decode(&data), Some(packet));
+
+        let mut corrupted = data.clone();
+        corrupted[10] ^= 0xFF;
+        assert_eq!(RdmPacket::decode(&corrupted), None);
+    }
+
+    #[test]
+    fn test_artnet_rdm_command_roundtrip() {
+        let commands = [
+            ArtnetRdmCommand::TodRequest {
+                net: 0,
+                addresses: vec![0, 1],
+            },
+            ArtnetRdmCommand::TodData {
+                port_address: 0x0102,
+                uids: vec![DEMEX_RDM_UID],
+            },
+            ArtnetRdmCommand::Rdm {
+                port_address: 0x0001,
+                packet: RdmPacket::request(
+                    DEMEX_RDM_UID,
+                    0,
+                    RdmCommandClass::Get,
+                    RdmParameter::DeviceInfo,
+                    Vec::new(),
+                ),
+            },
+        ];
+
+        for command in commands {
+            assert_eq!(
+                ArtnetRdmCommand::from_buffer(&command.write_to_buffer()),
+                Some(command)
+            );
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, SocketAddr, UdpSocket},
+    sync::{mpsc, Arc, Weak},
+    thread, time,
+};
+
+use parking_lot::Mutex;
+
+use super::ARTNET_PORT;
+
+lazy_static::lazy_static! {
+    // keyed by the bind ip
+    static ref ARTNET_SOCKETS: Mutex<HashMap<String, Weak<ArtnetSocket>>> = Mutex::new(HashMap::new());
+}
+
+pub type ArtnetPacket = (Vec<u8>, SocketAddr);
+
+/// The addresses of the local interfaces, the socket sends from.
+fn local_addresses(bind_ip: &str) -> Vec<IpAddr> {
+    let bind_ip = bind_ip.parse::<IpAddr>().ok();
+
+    if let Some(bind_ip) = bind_ip.filter(|bind_ip| !bind_ip.is_unspecified()) {
+        return vec![bind_ip];
+    }
+
+    match nix::ifaddrs::getifaddrs() {
+        Ok(interfaces) => interfaces
+            .filter_map(|interface| interface.address)
+            .filter_map(|address| address.as_sockaddr_in().map(|address| address.ip().into()))
+            .collect(),
+        Err(err) => {
+            log::warn!("Failed to get the local interface addresses: {}", err);
+            Vec::new()
+        }
+    }
+}
+
+/// A UDP socket bound to the Art-Net port.
+///
+/// The port can only be bound once per address, so all Art-Net inputs and outputs
+/// with the same bind ip share one socket. Incoming packets are read by a single
+/// thread and handed to every subscriber.
+#[derive(Debug)]
+pub struct ArtnetSocket {
+    socket: UdpSocket,
+    local_addresses: Vec<IpAddr>,
+    subscribers: Mutex<Vec<mpsc::Sender<ArtnetPacket>>>,
+}
+
+impl ArtnetSocket {
+    /// Returns the socket for `bind_ip`, binding it, if no input or output is using it yet.
+    pub fn shared(bind_ip: Option<&str>) -> io::Result<Arc<Self>> {
+        let bind_ip = bind_ip.unwrap_or("0.0.0.0").to_owned();
+
+        let mut sockets = ARTNET_SOCKETS.lock();
+
+        if let Some(socket) = sockets.get(&bind_ip).and_then(Weak::upgrade) {
+            return Ok(socket);
+        }
+
+        log::debug!("Binding ArtNet socket to {}:{}..", bind_ip, ARTNET_PORT);
+
+        let socket = UdpSocket::bind((bind_ip.as_str(), ARTNET_PORT))?;
+        socket.set_broadcast(true)?;
+        socket.set_read_timeout(Some(time::Duration::from_millis(500)))?;
+
+        let socket = Arc::new(Self {
+            socket,
+            local_addresses: local_addresses(&bind_ip),
+            subscribers: Mutex::new(Vec::new()),
+        });
+
+        start_artnet_receive_thread(Arc::downgrade(&socket));
+        sockets.insert(bind_ip, Arc::downgrade(&socket));
+
+        Ok(socket)
+    }
+
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Packets sent by this socket (or any other socket of this console) are
+    /// received from one of these addresses, e.g. when broadcasting.
+    pub fn local_addresses(&self) -> &[IpAddr] {
+        &self.local_addresses
+    }
+
+    pub fn send_to(&self, data: &[u8], addr: SocketAddr) -> io::Result<usize> {
+        self.socket.send_to(data, addr)
+    }
+
+    /// Returns a receiver for all packets, that arrive at the socket from now on.
+    pub fn subscribe(&self) -> mpsc::Receiver<ArtnetPacket> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().push(tx);
+        rx
+    }
+}
+
+fn start_artnet_receive_thread(socket: Weak<ArtnetSocket>) {
+    thread::spawn(move || {
+        let mut input_buffer = [0u8; 1500];
+
+        // the socket is closed, once every input and output dropped it
+        while let Some(socket) = socket.upgrade() {
+            let Ok((length, sender)) = socket.socket.recv_from(&mut input_buffer) else {
+                continue;
+            };
+
+            socket.subscribers.lock().retain(|subscriber| {
+                subscriber
+                    .send((input_buffer[..length].to_vec(), sender))
+                    .is_ok()
+            });
+        }
+
+        log::debug!("Stopping ArtNet receive thread..");
+    });
+}
@@ -1,11 +1,96 @@
+use std::{fs, io::BufWriter};
+
 use serde::{Deserialize, Serialize};
 
+use recording::{DmxRecordingError, DmxRecordingWriter, DMX_RECORDING_EXTENSION};
+
+use crate::storage;
+
+pub mod recording;
+pub mod replay;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
 pub enum DebugOutputVerbosity {
+    /// Logs and records every sent universe
     Verbose,
+
+    /// Records every sent universe
     Quiet,
 
     #[default]
     Silent,
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct DebugOutputConfig {
+    pub verbosity: DebugOutputVerbosity,
+
+    /// The universes, that are output to the debug output
+    #[serde(default)]
+    pub universes: Vec<u16>,
+}
+
+impl DebugOutputConfig {
+    /// Whether the output is recorded. Without universes, there is nothing to record.
+    pub fn is_recording(&self) -> bool {
+        self.verbosity != DebugOutputVerbosity::Silent && !self.universes.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub struct DebugOutput {
+    verbosity: DebugOutputVerbosity,
+    recorder: Option<DmxRecordingWriter<BufWriter<fs::File>>>,
+}
+
+impl DebugOutput {
+    pub fn new(config: &DebugOutputConfig) -> Self {
+        let recorder = if !config.is_recording() {
+            None
+        } else {
+            let path = storage::dmx_recordings(crate::APP_ID).join(format!(
+                "{}.{}",
+                chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"),
+                DMX_RECORDING_EXTENSION
+            ));
+
+            match DmxRecordingWriter::create(&path) {
+                Ok(recorder) => {
+                    log::info!("Recording DMX output to {}", path.display());
+                    Some(recorder)
+                }
+                Err(err) => {
+                    log::error!("Failed to create DMX recording {}: {}", path.display(), err);
+                    None
+                }
+            }
+        };
+
+        Self {
+            verbosity: config.verbosity,
+            recorder,
+        }
+    }
+
+    pub fn send(&mut self, universe: u16, data: &[u8; 512]) -> Result<(), DmxRecordingError> {
+        if self.verbosity == DebugOutputVerbosity::Verbose {
+            log::info!("Universe {}: {:?}", universe, data);
+        }
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.write_frame(universe, data)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn shutdown(&mut self) {
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(err) = recorder.flush() {
+                log::warn!("Failed to flush DMX recording: {}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    fmt, fs,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    time,
+};
+
+const DMX_RECORDING_MAGIC: &[u8; 6] = b"DMXREC";
+const DMX_RECORDING_VERSION: u8 = 1;
+
+pub const DMX_RECORDING_EXTENSION: &str = "dmxrec";
+
+#[derive(Debug)]
+pub enum DmxRecordingError {
+    IoError(io::Error),
+    InvalidHeader,
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for DmxRecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IoError(err) => write!(f, "IO error: {}", err),
+            Self::InvalidHeader => write!(f, "Not a DMX recording"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported DMX recording version: {}", version)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DmxRecordingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+impl From<io::Error> for DmxRecordingError {
+    fn from(err: io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
+/// A single recorded universe.
+///
+/// On disk, a frame is stored as `frame (u32) | delta (u32, micros) | universe (u16) | data (512 bytes)`,
+/// all integers in little endian, after a header consisting of the magic bytes and the format version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmxRecordingFrame {
+    /// Running number of the frame in the recording
+    pub frame: u32,
+
+    /// Time elapsed since the previous frame
+    pub delta: time::Duration,
+
+    pub universe: u16,
+    pub data: [u8; 512],
+}
+
+#[derive(Debug)]
+pub struct DmxRecordingWriter<W: Write> {
+    writer: W,
+    frame: u32,
+    last_frame_at: Option<time::Instant>,
+}
+
+impl DmxRecordingWriter<BufWriter<fs::File>> {
+    pub fn create(path: &Path) -> Result<Self, DmxRecordingError> {
+        Self::new(BufWriter::new(fs::File::create(path)?))
+    }
+}
+
+impl<W: Write> DmxRecordingWriter<W> {
+    pub fn new(mut writer: W) -> Result<Self, DmxRecordingError> {
+        writer.write_all(DMX_RECORDING_MAGIC)?;
+        writer.write_all(&[DMX_RECORDING_VERSION])?;
+
+        Ok(Self {
+            writer,
+            frame: 0,
+            last_frame_at: None,
+        })
+    }
+
+    pub fn write_frame(
+        &mut self,
+        universe: u16,
+        data: &[u8; 512],
+    ) -> Result<(), DmxRecordingError> {
+        let now = time::Instant::now();
+        let delta = self
+            .last_frame_at
+            .map(|last_frame_at| now.duration_since(last_frame_at))
+            .unwrap_or_default();
+
+        self.write_frame_with_delta(universe, data, delta)?;
+        self.last_frame_at = Some(now);
+
+        Ok(())
+    }
+
+    fn write_frame_with_delta(
+        &mut self,
+        universe: u16,
+        data: &[u8; 512],
+        delta: time::Duration,
+    ) -> Result<(), DmxRecordingError> {
+        let delta_micros = u32::try_from(delta.as_micros()).unwrap_or(u32::MAX);
+
+        self.writer.write_all(&self.frame.to_le_bytes())?;
+        self.writer.write_all(&delta_micros.to_le_bytes())?;
+        self.writer.write_all(&universe.to_le_bytes())?;
+        self.writer.write_all(data)?;
+
+        self.frame = self.frame.wrapping_add(1);
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), DmxRecordingError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct DmxRecordingReader<R: Read> {
+    reader: R,
+}
+
+impl DmxRecordingReader<BufReader<fs::File>> {
+    pub fn open(path: &Path) -> Result<Self, DmxRecordingError> {
+        Self::new(BufReader::new(fs::File::open(path)?))
+    }
+}
+
+impl<R: Read> DmxRecordingReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, DmxRecordingError> {
+        let mut magic = [0u8; DMX_RECORDING_MAGIC.len()];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|_| DmxRecordingError::InvalidHeader)?;
+
+        if &magic != DMX_RECORDING_MAGIC {
+            return Err(DmxRecordingError::InvalidHeader);
+        }
+
+        let mut version = [0u8; 1];
+        reader
+            .read_exact(&mut version)
+            .map_err(|_| DmxRecordingError::InvalidHeader)?;
+
+        if version[0] != DMX_RECORDING_VERSION {
+            return Err(DmxRecordingError::UnsupportedVersion(version[0]));
+        }
+
+        Ok(Self { reader })
+    }
+
+    /// Reads the next frame of the recording or `None`, if the end of the recording was reached.
+    pub fn next_frame(&mut self) -> Result<Option<DmxRecordingFrame>, DmxRecordingError> {
+        let mut frame = [0u8; 4];
+
+        match self.reader.read_exact(&mut frame) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        }
+
+        let mut delta_micros = [0u8; 4];
+        let mut universe = [0u8; 2];
+        let mut data = [0u8; 512];
+
+        self.reader.read_exact(&mut delta_micros)?;
+        self.reader.read_exact(&mut universe)?;
+        self.reader.read_exact(&mut data)?;
+
+        Ok(Some(DmxRecordingFrame {
+            frame: u32::from_le_bytes(frame),
+            delta: time::Duration::from_micros(u32::from_le_bytes(delta_micros) as u64),
+            universe: u16::from_le_bytes(universe),
+            data,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time;
+
+    use super::{DmxRecordingError, DmxRecordingReader, DmxRecordingWriter};
+
+    #[test]
+    fn test_dmx_recording_roundtrip() {
+        let mut buf = Vec::new();
+
+        let mut writer = DmxRecordingWriter::new(&mut buf).unwrap();
+        writer
+            .write_frame_with_delta(1, &[255; 512], time::Duration::ZERO)
+            .unwrap();
+        writer
+            .write_frame_with_delta(2, &[42; 512], time::Duration::from_millis(25))
+            .unwrap();
+
+        let mut reader = DmxRecordingReader::new(&buf[..]).unwrap();
+
+        let first = reader.next_frame().unwrap().unwrap();
+        assert_eq!(first.frame, 0);
+        assert_eq!(first.universe, 1);
+        assert_eq!(first.data, [255; 512]);
+
+        let second = reader.next_frame().unwrap().unwrap();
+        assert_eq!(second.frame, 1);
+        assert_eq!(second.delta, time::Duration::from_millis(25));
+        assert_eq!(second.universe, 2);
+        assert_eq!(second.data, [42; 512]);
+
+        assert!(reader.next_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_dmx_recording_invalid_header() {
+        assert!(matches!(
+            DmxRecordingReader::new(&b"NOTDMX"[..]),
+            Err(DmxRecordingError::InvalidHeader)
+        ));
+    }
+}
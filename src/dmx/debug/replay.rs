@@ -0,0 +1,132 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread, time,
+};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::dmx::merge::{merge_input_universes, DmxInputUniverseData, DmxMergeMode};
+
+use super::recording::{DmxRecordingError, DmxRecordingReader};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct DmxReplayConfig {
+    /// Path of the DMX recording to replay
+    pub file: String,
+
+    /// Start over, once the end of the recording is reached
+    #[serde(default)]
+    pub looped: bool,
+
+    #[serde(default)]
+    pub merge_mode: DmxMergeMode,
+}
+
+#[derive(Debug)]
+pub struct DmxReplay {
+    config: DmxReplayConfig,
+
+    universes: HashMap<u16, DmxInputUniverseData>,
+    is_finished: bool,
+}
+
+impl DmxReplay {
+    pub fn new(config: DmxReplayConfig) -> Self {
+        Self {
+            config,
+            universes: HashMap::new(),
+            is_finished: false,
+        }
+    }
+
+    pub fn config(&self) -> &DmxReplayConfig {
+        &self.config
+    }
+
+    fn receive(&mut self, universe: u16, data: &[u8; 512]) {
+        let now = time::Instant::now();
+
+        self.universes
+            .entry(universe)
+            .or_insert_with(|| DmxInputUniverseData::new(now))
+            .update(data, now);
+    }
+
+    /// Merges the replayed data into `output_data`, according to the configured merge mode.
+    /// Once the replay finished, the replayed universes are released.
+    pub fn merge_into(
+        &mut self,
+        output_data: &mut HashMap<u16, [u8; 512]>,
+        output_changed_at: &HashMap<u16, [time::Instant; 512]>,
+        dirty_universes: &mut BTreeSet<u16>,
+    ) {
+        if self.is_finished {
+            dirty_universes.extend(self.universes.drain().map(|(universe, _)| universe));
+            return;
+        }
+
+        merge_input_universes(
+            self.universes.iter_mut(),
+            self.config.merge_mode,
+            output_data,
+            output_changed_at,
+            dirty_universes,
+        );
+    }
+}
+
+// Returns the number of replayed frames
+fn replay_recording(
+    replay: &Arc<RwLock<DmxReplay>>,
+    path: &Path,
+) -> Result<usize, DmxRecordingError> {
+    let mut reader = DmxRecordingReader::open(path)?;
+    let mut num_frames = 0;
+
+    while Arc::strong_count(replay) > 1 {
+        let Some(frame) = reader.next_frame()? else {
+            break;
+        };
+
+        thread::sleep(frame.delta);
+
+        log::trace!(
+            "Replaying frame {} of universe {}",
+            frame.frame,
+            frame.universe
+        );
+        replay.write().receive(frame.universe, &frame.data);
+
+        num_frames += 1;
+    }
+
+    Ok(num_frames)
+}
+
+pub fn start_dmx_replay_thread(replay: Arc<RwLock<DmxReplay>>) {
+    thread::spawn(move || {
+        log::debug!("Starting DMX replay thread..");
+
+        let path = PathBuf::from(&replay.read().config().file);
+
+        // the replay is no longer needed, once the fixture handler dropped its reference
+        while Arc::strong_count(&replay) > 1 {
+            match replay_recording(&replay, &path) {
+                Ok(num_frames) if num_frames > 0 && replay.read().config().looped => {}
+                Ok(_) => break,
+                Err(err) => {
+                    log::error!("Failed to replay DMX recording {}: {}", path.display(), err);
+                    break;
+                }
+            }
+        }
+
+        replay.write().is_finished = true;
+
+        log::debug!("Stopping DMX replay thread..");
+    });
+}
@@ -0,0 +1,99 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    time,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub enum DmxMergeMode {
+    /// Highest takes precedence
+    #[default]
+    Htp,
+
+    /// Latest takes precedence
+    Ltp,
+}
+
+/// Data of a single universe received by an input (e.g. Art-Net input or a replay).
+#[derive(Debug)]
+pub struct DmxInputUniverseData {
+    data: [u8; 512],
+    changed_at: [time::Instant; 512],
+    last_received: time::Instant,
+    is_dirty: bool,
+}
+
+impl DmxInputUniverseData {
+    pub fn new(now: time::Instant) -> Self {
+        Self {
+            data: [0; 512],
+            changed_at: [now; 512],
+            last_received: now,
+            is_dirty: true,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8], now: time::Instant) {
+        for (i, d) in data.iter().take(512).enumerate() {
+            if self.data[i] != *d {
+                self.data[i] = *d;
+                self.changed_at[i] = now;
+                self.is_dirty = true;
+            }
+        }
+
+        self.last_received = now;
+    }
+
+    pub fn last_received(&self) -> time::Instant {
+        self.last_received
+    }
+}
+
+/// Merges the input universes (keyed by the target universe) into `output_data`, according to `merge_mode`.
+/// A target universe may be sent by multiple sources, which are merged with each other as well.
+/// Universes, whose merged data changed since the last call, are added to `dirty_universes`.
+pub fn merge_input_universes<'a>(
+    input_universes: impl Iterator<Item = (&'a u16, &'a mut DmxInputUniverseData)>,
+    merge_mode: DmxMergeMode,
+    output_data: &mut HashMap<u16, [u8; 512]>,
+    output_changed_at: &HashMap<u16, [time::Instant; 512]>,
+    dirty_universes: &mut BTreeSet<u16>,
+) {
+    // with LTP, the latest change of all sources and the output takes precedence
+    let mut merged_changed_at = output_changed_at.clone();
+
+    for (universe, input_data) in input_universes {
+        let Some(universe_data) = output_data.get_mut(universe) else {
+            continue;
+        };
+
+        if input_data.is_dirty {
+            dirty_universes.insert(*universe);
+            input_data.is_dirty = false;
+        }
+
+        match merge_mode {
+            DmxMergeMode::Htp => {
+                for (value, input_value) in universe_data.iter_mut().zip(input_data.data) {
+                    *value = (*value).max(input_value);
+                }
+            }
+            DmxMergeMode::Ltp => {
+                for (i, value) in universe_data.iter_mut().enumerate() {
+                    if merged_changed_at
+                        .get(universe)
+                        .is_none_or(|changed_at| input_data.changed_at[i] > changed_at[i])
+                    {
+                        *value = input_data.data[i];
+                        merged_changed_at
+                            .entry(*universe)
+                            .or_insert(input_data.changed_at)[i] = input_data.changed_at[i];
+                    }
+                }
+            }
+        }
+    }
+}
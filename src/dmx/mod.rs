@@ -1,19 +1,39 @@
 use std::{
+    collections::{BTreeSet, HashMap},
     fmt,
-    sync::mpsc::{self},
+    sync::{
+        mpsc::{self},
+        Arc,
+    },
+    thread, time,
 };
 
 use artnet::{
+    input::{start_artnet_input_thread, ArtnetInput, ArtnetInputConfig},
+    node::ArtnetNodes,
+    rdm::ArtnetRdm,
     start_artnet_output_thread, start_broadcast_artnet_output_thread, ArtnetOutputConfig,
+    ArtnetOutputMessage,
+};
+use debug::{
+    replay::{start_dmx_replay_thread, DmxReplay, DmxReplayConfig},
+    DebugOutput, DebugOutputConfig,
 };
-use debug::DebugOutputVerbosity;
+use parking_lot::RwLock;
+use sacn::{start_sacn_output_thread, SacnOutputConfig};
 use serde::{Deserialize, Serialize};
-use serial::SerialOutputConfig;
+use serial::{
+    enttec::{start_enttec_pro_output_thread, EnttecPro, EnttecProOutputConfig},
+    SerialOutputConfig,
+};
 
 use crate::headless::id::DemexProtoDeviceId;
 
 pub mod artnet;
 pub mod debug;
+pub mod merge;
+pub mod routing;
+pub mod sacn;
 pub mod serial;
 
 pub trait DemexDmxOutputTrait: fmt::Debug {
@@ -25,15 +45,25 @@ pub type DmxData = (u16, [u8; 512]);
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
 pub enum DemexDmxOutputConfigData {
-    Debug(DebugOutputVerbosity),
+    Debug(DebugOutputConfig),
 
     Serial(SerialOutputConfig),
+
+    /// Serial output using the Enttec DMX USB Pro protocol
+    EnttecPro(EnttecProOutputConfig),
+
     Artnet(ArtnetOutputConfig),
+    Sacn(SacnOutputConfig),
+
+    ArtnetInput(ArtnetInputConfig),
+
+    /// Replays a DMX recording of the debug output
+    Replay(DmxReplayConfig),
 }
 
 impl Default for DemexDmxOutputConfigData {
     fn default() -> Self {
-        Self::Debug(DebugOutputVerbosity::Quiet)
+        Self::Debug(DebugOutputConfig::default())
     }
 }
 
@@ -45,11 +75,19 @@ pub struct DemexDmxOutputConfig {
 }
 
 impl DemexDmxOutputConfig {
+    pub fn new(data: DemexDmxOutputConfigData, device_id: DemexProtoDeviceId) -> Self {
+        Self { data, device_id }
+    }
+
     pub fn universes(&self) -> Option<Vec<u16>> {
         match &self.data {
-            DemexDmxOutputConfigData::Debug(_) => None,
+            DemexDmxOutputConfigData::Debug(config) => Some(config.universes.clone()),
             DemexDmxOutputConfigData::Artnet(config) => Some(config.universes.clone()),
             DemexDmxOutputConfigData::Serial(config) => Some(vec![config.universe]),
+            DemexDmxOutputConfigData::EnttecPro(config) => Some(vec![config.universe]),
+            DemexDmxOutputConfigData::Sacn(config) => Some(config.universes()),
+            DemexDmxOutputConfigData::ArtnetInput(_) => None,
+            DemexDmxOutputConfigData::Replay(_) => None,
         }
     }
 
@@ -57,7 +95,11 @@ impl DemexDmxOutputConfig {
         match &self.data {
             DemexDmxOutputConfigData::Debug(_) => 0,
             DemexDmxOutputConfigData::Serial(_) => 1,
+            DemexDmxOutputConfigData::EnttecPro(_) => 2,
             DemexDmxOutputConfigData::Artnet(_) => 1,
+            DemexDmxOutputConfigData::Sacn(_) => 1,
+            DemexDmxOutputConfigData::ArtnetInput(_) => 1,
+            DemexDmxOutputConfigData::Replay(_) => 1,
         }
     }
 }
@@ -74,22 +116,41 @@ impl Default for DemexDmxOutputConfig {
 #[derive(Debug)]
 pub enum DemexDmxOutputData {
     Artnet {
-        tx: mpsc::Sender<DmxData>,
+        tx: mpsc::Sender<ArtnetOutputMessage>,
         config: ArtnetOutputConfig,
+
+        // not available for broadcast outputs, as no nodes are tracked
+        nodes: Option<Arc<RwLock<ArtnetNodes>>>,
+        rdm: Option<Arc<RwLock<ArtnetRdm>>>,
     },
     Serial {
         tx: mpsc::Sender<DmxData>,
         config: SerialOutputConfig,
     },
-    Debug(DebugOutputVerbosity),
+    EnttecPro {
+        tx: mpsc::Sender<DmxData>,
+        enttec_pro: Arc<RwLock<EnttecPro>>,
+    },
+    Sacn {
+        tx: mpsc::Sender<DmxData>,
+        config: SacnOutputConfig,
+        handle: thread::JoinHandle<()>,
+    },
+    ArtnetInput(Arc<RwLock<ArtnetInput>>),
+    Replay(Arc<RwLock<DmxReplay>>),
+    Debug(DebugOutput),
     None,
 }
 
 impl DemexDmxOutputTrait for DemexDmxOutputData {
     fn send(&mut self, universe: u16, data: &[u8; 512]) -> Result<(), Box<dyn std::error::Error>> {
         match self {
-            Self::Artnet { tx, .. } | Self::Serial { tx, .. } => tx.send((universe, *data))?,
-            Self::Debug(_) => (),
+            Self::Artnet { tx, .. } => tx.send(ArtnetOutputMessage::Data((universe, *data)))?,
+            Self::Serial { tx, .. } | Self::EnttecPro { tx, .. } | Self::Sacn { tx, .. } => {
+                tx.send((universe, *data))?
+            }
+            Self::ArtnetInput(_) | Self::Replay(_) => (),
+            Self::Debug(output) => output.send(universe, data)?,
             Self::None => (),
         }
 
@@ -115,18 +176,26 @@ impl DemexDmxOutput {
         let data = match &config.data {
             DemexDmxOutputConfigData::Artnet(config) => {
                 let (tx, rx) = mpsc::channel();
-                if config.broadcast {
+                let (nodes, rdm) = if config.broadcast {
                     start_broadcast_artnet_output_thread(rx, config.clone());
+                    (None, None)
                 } else {
-                    start_artnet_output_thread(rx, config.clone());
-                }
+                    let nodes = Arc::new(RwLock::new(ArtnetNodes::default()));
+                    let rdm = Arc::new(RwLock::new(ArtnetRdm::default()));
+                    start_artnet_output_thread(rx, config.clone(), nodes.clone(), rdm.clone());
+                    (Some(nodes), Some(rdm))
+                };
 
                 DemexDmxOutputData::Artnet {
                     tx,
                     config: config.clone(),
+                    nodes,
+                    rdm,
                 }
             }
-            DemexDmxOutputConfigData::Debug(verbosity) => DemexDmxOutputData::Debug(*verbosity),
+            DemexDmxOutputConfigData::Debug(config) => {
+                DemexDmxOutputData::Debug(DebugOutput::new(config))
+            }
             DemexDmxOutputConfigData::Serial(config) => {
                 let (tx, rx) = mpsc::channel();
                 serial::start_serial_output_thread(rx, config.clone());
@@ -136,12 +205,53 @@ impl DemexDmxOutput {
                     config: config.clone(),
                 }
             }
+            DemexDmxOutputConfigData::EnttecPro(config) => {
+                let (tx, rx) = mpsc::channel();
+                let enttec_pro = Arc::new(RwLock::new(EnttecPro::new(config.clone())));
+                start_enttec_pro_output_thread(rx, enttec_pro.clone());
+
+                DemexDmxOutputData::EnttecPro { tx, enttec_pro }
+            }
+            DemexDmxOutputConfigData::Sacn(config) => {
+                let (tx, rx) = mpsc::channel();
+                let handle = start_sacn_output_thread(rx, config.clone());
+
+                DemexDmxOutputData::Sacn {
+                    tx,
+                    config: config.clone(),
+                    handle,
+                }
+            }
+            DemexDmxOutputConfigData::ArtnetInput(config) => {
+                let input = Arc::new(RwLock::new(ArtnetInput::new(config.clone())));
+                start_artnet_input_thread(input.clone());
+
+                DemexDmxOutputData::ArtnetInput(input)
+            }
+            DemexDmxOutputConfigData::Replay(config) => {
+                let replay = Arc::new(RwLock::new(DmxReplay::new(config.clone())));
+                start_dmx_replay_thread(replay.clone());
+
+                DemexDmxOutputData::Replay(replay)
+            }
         };
 
         Self { data, config }
     }
 }
 
+impl DemexDmxOutputData {
+    fn sync(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Self::Artnet { tx, config, .. } = self {
+            if config.sync {
+                tx.send(ArtnetOutputMessage::Sync)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl DemexDmxOutputTrait for DemexDmxOutput {
     fn send(&mut self, universe: u16, data: &[u8; 512]) -> Result<(), Box<dyn std::error::Error>> {
         if let Err(err) = self.data.send(universe, data) {
@@ -157,6 +267,79 @@ impl DemexDmxOutput {
         !matches!(self.data, DemexDmxOutputData::None)
     }
 
+    /// Merges data received by this output (if it is an input) into `output_data`.
+    pub fn merge_input_into(
+        &self,
+        output_data: &mut HashMap<u16, [u8; 512]>,
+        output_changed_at: &HashMap<u16, [time::Instant; 512]>,
+        dirty_universes: &mut BTreeSet<u16>,
+    ) {
+        match &self.data {
+            DemexDmxOutputData::ArtnetInput(input) => {
+                input
+                    .write()
+                    .merge_into(output_data, output_changed_at, dirty_universes)
+            }
+            DemexDmxOutputData::Replay(replay) => {
+                replay
+                    .write()
+                    .merge_into(output_data, output_changed_at, dirty_universes)
+            }
+            DemexDmxOutputData::EnttecPro { enttec_pro, .. } => {
+                enttec_pro
+                    .write()
+                    .merge_into(output_data, output_changed_at, dirty_universes)
+            }
+            _ => {}
+        }
+    }
+
+    /// Stops outputting data and waits for output threads, that
+    /// need to notify receivers about the shutdown (e.g. sACN stream termination).
+    pub fn shutdown(&mut self) {
+        let data = std::mem::replace(&mut self.data, DemexDmxOutputData::None);
+
+        match data {
+            DemexDmxOutputData::Sacn { tx, handle, .. } => {
+                drop(tx);
+
+                if handle.join().is_err() {
+                    log::warn!("sACN output thread panicked during shutdown");
+                }
+            }
+            DemexDmxOutputData::Debug(mut output) => output.shutdown(),
+            _ => {}
+        }
+    }
+
+    /// Signals the end of an output cycle, after the data of every dirty universe was sent.
+    pub fn sync(&mut self) {
+        if let Err(err) = self.data.sync() {
+            log::warn!("Error syncing {:?}: {}", self, err);
+        }
+    }
+
+    pub fn enttec_pro(&self) -> Option<&Arc<RwLock<EnttecPro>>> {
+        match &self.data {
+            DemexDmxOutputData::EnttecPro { enttec_pro, .. } => Some(enttec_pro),
+            _ => None,
+        }
+    }
+
+    pub fn artnet_nodes(&self) -> Option<&Arc<RwLock<ArtnetNodes>>> {
+        match &self.data {
+            DemexDmxOutputData::Artnet { nodes, .. } => nodes.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn artnet_rdm(&self) -> Option<&Arc<RwLock<ArtnetRdm>>> {
+        match &self.data {
+            DemexDmxOutputData::Artnet { rdm, .. } => rdm.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn config(&self) -> &DemexDmxOutputConfig {
         &self.config
     }
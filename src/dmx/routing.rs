@@ -0,0 +1,157 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Routes a logical universe (the universe fixtures are patched to)
+/// to one or more physical universes of an output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct DmxRoute {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// The logical universe
+    pub universe: u16,
+
+    /// Index of the output (in the output config), the universe is sent to
+    pub output: usize,
+
+    /// Physical universes of the output, the logical universe is mirrored to.
+    /// If empty, the logical universe is used.
+    #[serde(default)]
+    pub destinations: Vec<u16>,
+
+    /// Added to every physical universe
+    #[serde(default)]
+    pub offset: i32,
+}
+
+impl Default for DmxRoute {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            universe: 1,
+            output: 0,
+            destinations: Vec::new(),
+            offset: 0,
+        }
+    }
+}
+
+impl DmxRoute {
+    /// The physical universes of the route or `None` for every
+    /// universe, that is out of range after applying the offset.
+    pub fn physical_universes(&self) -> Vec<Option<u16>> {
+        let destinations = if self.destinations.is_empty() {
+            vec![self.universe]
+        } else {
+            self.destinations.clone()
+        };
+
+        destinations
+            .into_iter()
+            .map(|destination| u16::try_from(destination as i32 + self.offset).ok())
+            .collect()
+    }
+}
+
+/// Maps logical universes onto the physical universes of the outputs.
+/// Logical universes without a route are sent unchanged to every output.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DmxRouting {
+    #[serde(default)]
+    routes: Vec<DmxRoute>,
+}
+
+impl DmxRouting {
+    pub fn routes(&self) -> &[DmxRoute] {
+        &self.routes
+    }
+
+    pub fn routes_mut(&mut self) -> &mut Vec<DmxRoute> {
+        &mut self.routes
+    }
+
+    /// The logical universes, that are sent by at least one enabled route.
+    pub fn routed_universes(&self) -> impl Iterator<Item = u16> + '_ {
+        self.routes
+            .iter()
+            .filter(|route| route.enabled)
+            .map(|route| route.universe)
+            .unique()
+    }
+
+    /// Whether `universe` is a physical universe of a route to the output at `output_idx`.
+    pub fn is_destination(&self, output_idx: usize, universe: u16) -> bool {
+        self.routes
+            .iter()
+            .filter(|route| route.output == output_idx)
+            .flat_map(|route| route.physical_universes())
+            .any(|physical_universe| physical_universe == Some(universe))
+    }
+
+    /// The physical universes, the logical `universe` is sent to by the output at `output_idx`.
+    /// Disabled routes still claim their logical universe, so it isn't sent unchanged.
+    pub fn physical_universes(&self, output_idx: usize, universe: u16) -> Vec<u16> {
+        if !self.routes.iter().any(|route| route.universe == universe) {
+            return vec![universe];
+        }
+
+        self.routes
+            .iter()
+            .filter(|route| {
+                route.enabled && route.universe == universe && route.output == output_idx
+            })
+            .flat_map(|route| route.physical_universes())
+            .flatten()
+            .unique()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DmxRoute, DmxRouting};
+
+    #[test]
+    fn test_dmx_routing() {
+        let routing = DmxRouting {
+            routes: vec![
+                DmxRoute {
+                    universe: 1,
+                    output: 0,
+                    destinations: vec![3, 4],
+                    ..Default::default()
+                },
+                DmxRoute {
+                    universe: 1,
+                    output: 1,
+                    offset: 10,
+                    ..Default::default()
+                },
+                DmxRoute {
+                    enabled: false,
+                    universe: 2,
+                    output: 0,
+                    ..Default::default()
+                },
+            ],
+        };
+
+        // mirrored
+        assert_eq!(routing.physical_universes(0, 1), vec![3, 4]);
+        // offset
+        assert_eq!(routing.physical_universes(1, 1), vec![11]);
+        // disabled
+        assert!(routing.physical_universes(0, 2).is_empty());
+        // not routed
+        assert_eq!(routing.physical_universes(0, 5), vec![5]);
+
+        assert_eq!(routing.routed_universes().collect::<Vec<_>>(), vec![1]);
+        assert!(routing.is_destination(0, 4));
+        assert!(!routing.is_destination(1, 4));
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    collections::HashMap,
+    net::{self, Ipv4Addr, SocketAddr},
+    sync::mpsc,
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::DmxData;
+
+const SACN_PORT: u16 = 5568;
+
+const SACN_PACKET_LENGTH: usize = 638;
+const SACN_SOURCE_NAME_LENGTH: usize = 64;
+
+const SACN_ACN_PACKET_IDENTIFIER: [u8; 12] = *b"ASC-E1.17\0\0\0";
+const SACN_VECTOR_ROOT_E131_DATA: u32 = 0x0000_0004;
+const SACN_VECTOR_E131_DATA_PACKET: u32 = 0x0000_0002;
+const SACN_VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+
+const SACN_OPTION_STREAM_TERMINATED: u8 = 0x40;
+
+/// The standard recommends sending three stream terminated packets per universe.
+const SACN_STREAM_TERMINATED_PACKETS: usize = 3;
+
+pub const SACN_DEFAULT_PRIORITY: u8 = 100;
+
+fn default_source_name() -> String {
+    "demex".to_owned()
+}
+
+fn default_cid() -> String {
+    uuid::Uuid::from_bytes(rand::random()).to_string()
+}
+
+fn default_multicast() -> bool {
+    true
+}
+
+fn default_priority() -> u8 {
+    SACN_DEFAULT_PRIORITY
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct SacnUniverseConfig {
+    pub universe: u16,
+
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+}
+
+impl Default for SacnUniverseConfig {
+    fn default() -> Self {
+        Self {
+            universe: 1,
+            priority: SACN_DEFAULT_PRIORITY,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct SacnOutputConfig {
+    #[serde(default = "default_source_name")]
+    pub source_name: String,
+
+    /// The component identifier (UUID) of this source. This is stored
+    /// inside the show file, so receivers recognize us across restarts.
+    #[serde(default = "default_cid")]
+    pub cid: String,
+
+    pub bind_ip: Option<String>,
+
+    /// Send to the standard multicast group of each universe (239.255.x.y)
+    #[serde(default = "default_multicast")]
+    pub multicast: bool,
+
+    #[serde(default)]
+    pub unicast_destinations: Vec<String>,
+
+    #[serde(default)]
+    pub universes: Vec<SacnUniverseConfig>,
+}
+
+impl Default for SacnOutputConfig {
+    fn default() -> Self {
+        Self {
+            source_name: default_source_name(),
+            cid: default_cid(),
+            bind_ip: None,
+            multicast: default_multicast(),
+            unicast_destinations: Vec::new(),
+            universes: Vec::new(),
+        }
+    }
+}
+
+impl SacnOutputConfig {
+    pub fn universes(&self) -> Vec<u16> {
+        self.universes.iter().map(|u| u.universe).collect()
+    }
+
+    pub fn priority(&self, universe: u16) -> u8 {
+        self.universes
+            .iter()
+            .find(|u| u.universe == universe)
+            .map(|u| u.priority)
+            .unwrap_or(SACN_DEFAULT_PRIORITY)
+    }
+}
+
+fn sacn_multicast_address(universe: u16) -> SocketAddr {
+    SocketAddr::new(
+        net::IpAddr::V4(Ipv4Addr::new(
+            239,
+            255,
+            (universe >> 8) as u8,
+            (universe & 0xff) as u8,
+        )),
+        SACN_PORT,
+    )
+}
+
+fn sacn_flags_and_length(length: usize) -> [u8; 2] {
+    (0x7000 | (length as u16 & 0x0fff)).to_be_bytes()
+}
+
+#[derive(Debug)]
+struct SacnSource {
+    cid: [u8; 16],
+    source_name: [u8; SACN_SOURCE_NAME_LENGTH],
+    sequence_numbers: HashMap<u16, u8>,
+}
+
+impl SacnSource {
+    fn new(config: &SacnOutputConfig) -> Self {
+        let cid = uuid::Uuid::parse_str(&config.cid)
+            .map(|cid| *cid.as_bytes())
+            .unwrap_or_else(|_| {
+                log::warn!(
+                    "Invalid sACN CID {:?}, using a random one for this session",
+                    config.cid
+                );
+                rand::random()
+            });
+
+        // the source name is a null-terminated UTF-8 string of at most 64 bytes
+        let mut source_name = [0u8; SACN_SOURCE_NAME_LENGTH];
+        let name_bytes = config.source_name.as_bytes();
+        let name_len = name_bytes.len().min(SACN_SOURCE_NAME_LENGTH - 1);
+        source_name[..name_len].copy_from_slice(&name_bytes[..name_len]);
+
+        Self {
+            cid,
+            source_name,
+            sequence_numbers: HashMap::new(),
+        }
+    }
+
+    fn next_sequence_number(&mut self, universe: u16) -> u8 {
+        let sequence_number = self.sequence_numbers.entry(universe).or_insert(0);
+        let current = *sequence_number;
+        *sequence_number = sequence_number.wrapping_add(1);
+        current
+    }
+
+    fn data_packet(
+        &mut self,
+        universe: u16,
+        priority: u8,
+        data: &[u8; 512],
+        options: u8,
+    ) -> [u8; SACN_PACKET_LENGTH] {
+        let mut packet = [0u8; SACN_PACKET_LENGTH];
+
+        // Root layer
+        packet[0..2].copy_from_slice(&0x0010u16.to_be_bytes());
+        packet[2..4].copy_from_slice(&0x0000u16.to_be_bytes());
+        packet[4..16].copy_from_slice(&SACN_ACN_PACKET_IDENTIFIER);
+        packet[16..18].copy_from_slice(&sacn_flags_and_length(SACN_PACKET_LENGTH - 16));
+        packet[18..22].copy_from_slice(&SACN_VECTOR_ROOT_E131_DATA.to_be_bytes());
+        packet[22..38].copy_from_slice(&self.cid);
+
+        // Framing layer
+        packet[38..40].copy_from_slice(&sacn_flags_and_length(SACN_PACKET_LENGTH - 38));
+        packet[40..44].copy_from_slice(&SACN_VECTOR_E131_DATA_PACKET.to_be_bytes());
+        packet[44..108].copy_from_slice(&self.source_name);
+        packet[108] = priority.min(200);
+        packet[109..111].copy_from_slice(&0u16.to_be_bytes());
+        packet[111] = self.next_sequence_number(universe);
+        packet[112] = options;
+        packet[113..115].copy_from_slice(&universe.to_be_bytes());
+
+        // DMP layer
+        packet[115..117].copy_from_slice(&sacn_flags_and_length(SACN_PACKET_LENGTH - 115));
+        packet[117] = SACN_VECTOR_DMP_SET_PROPERTY;
+        packet[118] = 0xa1;
+        packet[119..121].copy_from_slice(&0x0000u16.to_be_bytes());
+        packet[121..123].copy_from_slice(&0x0001u16.to_be_bytes());
+        packet[123..125].copy_from_slice(&513u16.to_be_bytes());
+        packet[125] = 0x00;
+        packet[126..].copy_from_slice(data);
+
+        packet
+    }
+}
+
+pub fn start_sacn_output_thread(
+    rx: mpsc::Receiver<DmxData>,
+    config: SacnOutputConfig,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        log::debug!("Starting sACN thread..");
+
+        let socket = net::UdpSocket::bind((
+            config
+                .bind_ip
+                .clone()
+                .unwrap_or_else(|| "0.0.0.0".to_owned()),
+            0,
+        ))
+        .unwrap();
+
+        let unicast_destinations = config
+            .unicast_destinations
+            .iter()
+            .map(|addr| net::SocketAddr::new(net::IpAddr::V4(addr.parse().unwrap()), SACN_PORT))
+            .collect::<Vec<_>>();
+
+        let mut source = SacnSource::new(&config);
+        let mut last_universe_data: HashMap<u16, [u8; 512]> = HashMap::new();
+
+        let send_packet = |socket: &net::UdpSocket, universe: u16, packet: &[u8]| {
+            if config.multicast {
+                if let Err(err) = socket.send_to(packet, sacn_multicast_address(universe)) {
+                    log::warn!("Failed to send sACN packet via multicast: {}", err);
+                }
+            }
+
+            for addr in &unicast_destinations {
+                if let Err(err) = socket.send_to(packet, addr) {
+                    log::warn!("Failed to send sACN packet to {}: {}", addr, err);
+                }
+            }
+        };
+
+        // the output is shut down, once the sending half of the channel is dropped
+        while let Ok((send_universe, send_universe_data)) = rx.recv() {
+            if !config.universes.iter().any(|u| u.universe == send_universe) {
+                continue;
+            }
+
+            let packet = source.data_packet(
+                send_universe,
+                config.priority(send_universe),
+                &send_universe_data,
+                0,
+            );
+            send_packet(&socket, send_universe, &packet);
+
+            last_universe_data.insert(send_universe, send_universe_data);
+        }
+
+        log::debug!("sACN output disconnected, sending stream terminated packets..");
+
+        for (universe, data) in &last_universe_data {
+            for _ in 0..SACN_STREAM_TERMINATED_PACKETS {
+                let packet = source.data_packet(
+                    *universe,
+                    config.priority(*universe),
+                    data,
+                    SACN_OPTION_STREAM_TERMINATED,
+                );
+                send_packet(&socket, *universe, &packet);
+            }
+        }
+    })
+}
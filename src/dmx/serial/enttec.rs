@@ -0,0 +1,582 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt, fs,
+    io::{self, Read, Write},
+    sync::{mpsc, Arc},
+    thread, time,
+};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::dmx::{
+    merge::{merge_input_universes, DmxInputUniverseData, DmxMergeMode},
+    DmxData,
+};
+
+const ENTTEC_PRO_START_OF_MESSAGE: u8 = 0x7E;
+const ENTTEC_PRO_END_OF_MESSAGE: u8 = 0xE7;
+
+// the largest message is a received dmx packet (status, start code and 512 channels)
+const ENTTEC_PRO_MAX_DATA_LENGTH: usize = 600;
+
+const ENTTEC_PRO_LABEL_GET_PARAMETERS: u8 = 3;
+const ENTTEC_PRO_LABEL_RECEIVED_DMX: u8 = 5;
+const ENTTEC_PRO_LABEL_SEND_DMX: u8 = 6;
+const ENTTEC_PRO_LABEL_RECEIVE_DMX_ON_CHANGE: u8 = 8;
+const ENTTEC_PRO_LABEL_GET_SERIAL_NUMBER: u8 = 10;
+
+const DMX_START_CODE: u8 = 0x00;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct EnttecProOutputConfig {
+    /// Path of the tty of the widget (e.g. /dev/ttyUSB0)
+    pub serial_port: String,
+    pub universe: u16,
+
+    /// Receive DMX with the widget and merge it into this universe
+    #[serde(default)]
+    pub input_universe: Option<u16>,
+
+    #[serde(default)]
+    pub merge_mode: DmxMergeMode,
+}
+
+#[derive(Debug)]
+pub enum EnttecProError {
+    IoError(io::Error),
+    MessageTooLong(usize),
+}
+
+impl fmt::Display for EnttecProError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IoError(err) => write!(f, "IO error: {}", err),
+            Self::MessageTooLong(length) => {
+                write!(f, "DMX USB Pro message is too long ({} bytes)", length)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnttecProError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+impl From<io::Error> for EnttecProError {
+    fn from(err: io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
+/// A message of the DMX USB Pro widget API.
+///
+/// On the wire, a message is framed as `0x7E | label | length (u16, little endian) | data | 0xE7`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnttecProMessage {
+    pub label: u8,
+    pub data: Vec<u8>,
+}
+
+impl EnttecProMessage {
+    pub fn new(label: u8, data: Vec<u8>) -> Self {
+        Self { label, data }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, EnttecProError> {
+        if self.data.len() > ENTTEC_PRO_MAX_DATA_LENGTH {
+            return Err(EnttecProError::MessageTooLong(self.data.len()));
+        }
+
+        let mut message = Vec::with_capacity(self.data.len() + 5);
+        message.push(ENTTEC_PRO_START_OF_MESSAGE);
+        message.push(self.label);
+        message.extend((self.data.len() as u16).to_le_bytes());
+        message.extend(&self.data);
+        message.push(ENTTEC_PRO_END_OF_MESSAGE);
+
+        Ok(message)
+    }
+}
+
+/// Decodes messages from the byte stream of the widget. Bytes, that
+/// aren't part of a valid message, are skipped.
+#[derive(Debug, Default)]
+pub struct EnttecProDecoder {
+    buffer: Vec<u8>,
+}
+
+impl EnttecProDecoder {
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend(data);
+    }
+
+    /// Returns the next complete message or `None`, if more data is needed.
+    pub fn next_message(&mut self) -> Option<EnttecProMessage> {
+        loop {
+            let Some(start) = self
+                .buffer
+                .iter()
+                .position(|byte| *byte == ENTTEC_PRO_START_OF_MESSAGE)
+            else {
+                self.buffer.clear();
+                return None;
+            };
+
+            self.buffer.drain(..start);
+
+            if self.buffer.len() < 4 {
+                return None;
+            }
+
+            let length = u16::from_le_bytes([self.buffer[2], self.buffer[3]]) as usize;
+
+            if length > ENTTEC_PRO_MAX_DATA_LENGTH {
+                // not a valid header, search for the next start byte
+                self.buffer.remove(0);
+                continue;
+            }
+
+            if self.buffer.len() < length + 5 {
+                return None;
+            }
+
+            if self.buffer[length + 4] != ENTTEC_PRO_END_OF_MESSAGE {
+                self.buffer.remove(0);
+                continue;
+            }
+
+            let message =
+                EnttecProMessage::new(self.buffer[1], self.buffer[4..length + 4].to_vec());
+            self.buffer.drain(..length + 5);
+
+            return Some(message);
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EnttecProParameters {
+    pub firmware_version: u16,
+
+    /// In units of 10.67 microseconds
+    pub break_time: u8,
+
+    /// In units of 10.67 microseconds
+    pub mark_after_break_time: u8,
+
+    /// Packets per second
+    pub refresh_rate: u8,
+}
+
+/// Replies of the widget, that are handled by demex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnttecProReply {
+    Parameters(EnttecProParameters),
+    SerialNumber(u32),
+    ReceivedDmx {
+        // bit 0 is set on a receive queue overflow, bit 1 on a receive overrun
+        status: u8,
+        data: Vec<u8>,
+    },
+}
+
+impl EnttecProReply {
+    pub fn from_message(message: &EnttecProMessage) -> Option<Self> {
+        let data = &message.data;
+
+        match message.label {
+            ENTTEC_PRO_LABEL_GET_PARAMETERS if data.len() >= 5 => {
+                Some(Self::Parameters(EnttecProParameters {
+                    firmware_version: u16::from_le_bytes([data[0], data[1]]),
+                    break_time: data[2],
+                    mark_after_break_time: data[3],
+                    refresh_rate: data[4],
+                }))
+            }
+            ENTTEC_PRO_LABEL_GET_SERIAL_NUMBER if data.len() >= 4 => {
+                Some(Self::SerialNumber(u32::from_le_bytes([
+                    data[0], data[1], data[2], data[3],
+                ])))
+            }
+            // only the default start code carries channel data
+            ENTTEC_PRO_LABEL_RECEIVED_DMX if data.len() >= 2 && data[1] == DMX_START_CODE => {
+                Some(Self::ReceivedDmx {
+                    status: data[0],
+                    data: data[2..].to_vec(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A DMX USB Pro compatible widget, connected via `port` (e.g. a tty).
+#[derive(Debug)]
+pub struct EnttecProWidget<P: Read + Write> {
+    port: P,
+    decoder: EnttecProDecoder,
+}
+
+impl<P: Read + Write> EnttecProWidget<P> {
+    pub fn new(port: P) -> Self {
+        Self {
+            port,
+            decoder: EnttecProDecoder::default(),
+        }
+    }
+
+    fn send_message(&mut self, label: u8, data: Vec<u8>) -> Result<(), EnttecProError> {
+        self.port
+            .write_all(&EnttecProMessage::new(label, data).encode()?)?;
+        self.port.flush()?;
+        Ok(())
+    }
+
+    pub fn send_dmx(&mut self, data: &[u8; 512]) -> Result<(), EnttecProError> {
+        let mut message_data = Vec::with_capacity(513);
+        message_data.push(DMX_START_CODE);
+        message_data.extend(data);
+
+        self.send_message(ENTTEC_PRO_LABEL_SEND_DMX, message_data)
+    }
+
+    pub fn request_parameters(&mut self) -> Result<(), EnttecProError> {
+        // the size of the user configuration, we don't need
+        self.send_message(ENTTEC_PRO_LABEL_GET_PARAMETERS, vec![0, 0])
+    }
+
+    pub fn request_serial_number(&mut self) -> Result<(), EnttecProError> {
+        self.send_message(ENTTEC_PRO_LABEL_GET_SERIAL_NUMBER, Vec::new())
+    }
+
+    /// Makes the widget send every received DMX packet (label 5).
+    pub fn enable_dmx_input(&mut self) -> Result<(), EnttecProError> {
+        self.send_message(ENTTEC_PRO_LABEL_RECEIVE_DMX_ON_CHANGE, vec![0])
+    }
+
+    /// Reads the data, that is currently available, and returns the decoded replies.
+    pub fn read_replies(&mut self) -> Result<Vec<EnttecProReply>, EnttecProError> {
+        let mut buffer = [0u8; 1024];
+
+        let length = match self.port.read(&mut buffer) {
+            Ok(length) => length,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock
+                        | io::ErrorKind::TimedOut
+                        | io::ErrorKind::Interrupted
+                ) =>
+            {
+                0
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        self.decoder.push(&buffer[..length]);
+
+        let mut replies = Vec::new();
+        while let Some(message) = self.decoder.next_message() {
+            match EnttecProReply::from_message(&message) {
+                Some(reply) => replies.push(reply),
+                None => log::trace!("Ignoring DMX USB Pro message with label {}", message.label),
+            }
+        }
+
+        Ok(replies)
+    }
+}
+
+// Opens the tty in raw mode, so the line discipline doesn't touch the binary data.
+// Reads return after at most 100ms, even if no data was received.
+fn open_serial_port(path: &str) -> io::Result<fs::File> {
+    let file = fs::OpenOptions::new().read(true).write(true).open(path)?;
+
+    #[cfg(unix)]
+    {
+        use nix::sys::termios;
+
+        match termios::tcgetattr(&file) {
+            Ok(mut attributes) => {
+                termios::cfmakeraw(&mut attributes);
+                attributes.control_chars[termios::SpecialCharacterIndices::VMIN as usize] = 0;
+                attributes.control_chars[termios::SpecialCharacterIndices::VTIME as usize] = 1;
+
+                termios::tcsetattr(&file, termios::SetArg::TCSANOW, &attributes)
+                    .map_err(io::Error::from)?;
+            }
+            Err(err) => log::warn!("{} is not a tty, using it as is: {}", path, err),
+        }
+    }
+
+    Ok(file)
+}
+
+/// The state of a DMX USB Pro output, that is shared with the output thread.
+#[derive(Debug)]
+pub struct EnttecPro {
+    config: EnttecProOutputConfig,
+
+    parameters: Option<EnttecProParameters>,
+    serial_number: Option<u32>,
+
+    // keyed by the input universe
+    input: HashMap<u16, DmxInputUniverseData>,
+}
+
+impl EnttecPro {
+    pub fn new(config: EnttecProOutputConfig) -> Self {
+        Self {
+            config,
+            parameters: None,
+            serial_number: None,
+            input: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &EnttecProOutputConfig {
+        &self.config
+    }
+
+    pub fn parameters(&self) -> Option<&EnttecProParameters> {
+        self.parameters.as_ref()
+    }
+
+    /// The serial number of the widget, which is BCD encoded
+    /// and therefore displayed correctly in hex.
+    pub fn serial_number(&self) -> Option<u32> {
+        self.serial_number
+    }
+
+    fn handle_reply(&mut self, reply: EnttecProReply) {
+        match reply {
+            EnttecProReply::Parameters(parameters) => {
+                log::debug!("DMX USB Pro parameters: {:?}", parameters);
+                self.parameters = Some(parameters);
+            }
+            EnttecProReply::SerialNumber(serial_number) => {
+                log::debug!("DMX USB Pro serial number: {:08X}", serial_number);
+                self.serial_number = Some(serial_number);
+            }
+            EnttecProReply::ReceivedDmx { status, data } => {
+                if status != 0 {
+                    log::debug!("DMX USB Pro input status: {:#04X}", status);
+                }
+
+                let Some(input_universe) = self.config.input_universe else {
+                    return;
+                };
+
+                let now = time::Instant::now();
+                self.input
+                    .entry(input_universe)
+                    .or_insert_with(|| DmxInputUniverseData::new(now))
+                    .update(&data, now);
+            }
+        }
+    }
+
+    /// Merges the received DMX input into `output_data`, according to the configured merge mode.
+    pub fn merge_into(
+        &mut self,
+        output_data: &mut HashMap<u16, [u8; 512]>,
+        output_changed_at: &HashMap<u16, [time::Instant; 512]>,
+        dirty_universes: &mut BTreeSet<u16>,
+    ) {
+        merge_input_universes(
+            self.input.iter_mut(),
+            self.config.merge_mode,
+            output_data,
+            output_changed_at,
+            dirty_universes,
+        );
+    }
+}
+
+fn run_enttec_pro_reader(
+    mut widget: EnttecProWidget<fs::File>,
+    enttec_pro: Arc<RwLock<EnttecPro>>,
+) -> Result<(), EnttecProError> {
+    // the state is no longer needed, once the fixture handler dropped its reference
+    while Arc::strong_count(&enttec_pro) > 1 {
+        for reply in widget.read_replies()? {
+            enttec_pro.write().handle_reply(reply);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn start_enttec_pro_output_thread(
+    rx: mpsc::Receiver<DmxData>,
+    enttec_pro: Arc<RwLock<EnttecPro>>,
+) {
+    thread::spawn(move || {
+        log::debug!("Starting DMX USB Pro thread..");
+
+        let config = enttec_pro.read().config().clone();
+
+        let port = match open_serial_port(&config.serial_port) {
+            Ok(port) => port,
+            Err(err) => {
+                log::error!(
+                    "Failed to open DMX USB Pro serial port {}: {}",
+                    config.serial_port,
+                    err
+                );
+                return;
+            }
+        };
+
+        // replies of the widget are read by a separate thread, so
+        // sending DMX isn't blocked by the read timeout
+        match port.try_clone() {
+            Ok(reader_port) => {
+                let reader = EnttecProWidget::new(reader_port);
+
+                thread::spawn(move || {
+                    if let Err(err) = run_enttec_pro_reader(reader, enttec_pro) {
+                        log::error!("Failed to read from DMX USB Pro: {}", err);
+                    }
+                });
+            }
+            Err(err) => {
+                log::error!("Failed to clone DMX USB Pro serial port: {}", err);
+                return;
+            }
+        }
+
+        let mut widget = EnttecProWidget::new(port);
+
+        let setup_result = widget
+            .request_parameters()
+            .and_then(|_| widget.request_serial_number())
+            .and_then(|_| {
+                if config.input_universe.is_some() {
+                    widget.enable_dmx_input()
+                } else {
+                    Ok(())
+                }
+            });
+
+        if let Err(err) = setup_result {
+            log::warn!("Failed to set up DMX USB Pro: {}", err);
+        }
+
+        while let Ok((send_universe, send_universe_data)) = rx.recv() {
+            if config.universe != send_universe {
+                continue;
+            }
+
+            if let Err(err) = widget.send_dmx(&send_universe_data) {
+                log::error!("Failed to send DMX to DMX USB Pro: {}", err);
+            }
+        }
+
+        log::debug!("Stopping DMX USB Pro thread..");
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read, Write};
+
+    use super::{EnttecProDecoder, EnttecProMessage, EnttecProReply, EnttecProWidget};
+
+    // Stand-in for the tty of a widget
+    #[derive(Default)]
+    struct FakePort {
+        written: Vec<u8>,
+        to_read: io::Cursor<Vec<u8>>,
+    }
+
+    impl Read for FakePort {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.to_read.read(buf)
+        }
+    }
+
+    impl Write for FakePort {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.written.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_enttec_pro_send_dmx_framing() {
+        let mut widget = EnttecProWidget::new(FakePort::default());
+        widget.send_dmx(&[255; 512]).unwrap();
+
+        let written = &widget.port.written;
+        assert_eq!(written.len(), 513 + 5);
+        assert_eq!(&written[..5], &[0x7E, 6, 0x01, 0x02, 0x00]);
+        assert_eq!(written[5..517], [255; 512]);
+        assert_eq!(written[517], 0xE7);
+    }
+
+    #[test]
+    fn test_enttec_pro_read_replies() {
+        let mut stream = vec![0x00, 0xE7, 0x42];
+        stream.extend(
+            EnttecProMessage::new(3, vec![0x44, 0x01, 9, 1, 40])
+                .encode()
+                .unwrap(),
+        );
+        stream.extend(
+            EnttecProMessage::new(10, vec![0x78, 0x56, 0x34, 0x12])
+                .encode()
+                .unwrap(),
+        );
+        stream.extend(
+            EnttecProMessage::new(5, vec![0, 0, 1, 2, 3])
+                .encode()
+                .unwrap(),
+        );
+
+        let mut widget = EnttecProWidget::new(FakePort {
+            to_read: io::Cursor::new(stream),
+            ..Default::default()
+        });
+
+        let replies = widget.read_replies().unwrap();
+        assert_eq!(replies.len(), 3);
+
+        assert!(matches!(
+            replies[0],
+            EnttecProReply::Parameters(parameters) if parameters.firmware_version == 0x0144 && parameters.refresh_rate == 40
+        ));
+        assert_eq!(replies[1], EnttecProReply::SerialNumber(0x12345678));
+        assert_eq!(
+            replies[2],
+            EnttecProReply::ReceivedDmx {
+                status: 0,
+                data: vec![1, 2, 3]
+            }
+        );
+    }
+
+    #[test]
+    fn test_enttec_pro_decoder_partial_message() {
+        let encoded = EnttecProMessage::new(10, vec![1, 2, 3, 4])
+            .encode()
+            .unwrap();
+
+        let mut decoder = EnttecProDecoder::default();
+        decoder.push(&encoded[..5]);
+        assert!(decoder.next_message().is_none());
+
+        decoder.push(&encoded[5..]);
+        assert_eq!(
+            decoder.next_message(),
+            Some(EnttecProMessage::new(10, vec![1, 2, 3, 4]))
+        );
+    }
+}
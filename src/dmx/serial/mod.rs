@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 
 use super::DmxData;
 
+pub mod enttec;
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
 pub struct SerialOutputConfig {
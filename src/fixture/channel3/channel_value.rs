@@ -15,7 +15,7 @@ use super::utils::{
     dmx_value_to_f32, max_value, mix_dmx_value, multiply_dmx_value, multiply_dmx_value_f32,
 };
 
-use crate::utils::serde::approx_instant;
+use crate::utils::{clock, serde::approx_instant};
 
 #[derive(Debug, Clone)]
 pub enum FixtureChannelValue3Discrete {
@@ -56,7 +56,7 @@ impl FixtureChannelValue2PresetState {
 
     pub fn now(selection: FixtureSelection) -> Self {
         Self {
-            started: time::Instant::now(),
+            started: clock::now(),
             with_selection: selection,
         }
     }
@@ -133,6 +133,18 @@ impl FixtureChannelValue3 {
         matches!(self, Self::Home)
     }
 
+    /// Replaces the ids of referenced presets, e.g. after they were moved.
+    pub fn map_preset_ids(&mut self, map: &impl Fn(FixturePresetId) -> FixturePresetId) {
+        match self {
+            Self::Preset { id, .. } => *id = map(*id),
+            Self::Mix { a, b, .. } => {
+                a.map_preset_ids(map);
+                b.map_preset_ids(map);
+            }
+            Self::Discrete { .. } | Self::DiscreteSet { .. } | Self::Home => {}
+        }
+    }
+
     pub fn with_preset_state(self, preset_state: Option<FixtureChannelValue2PresetState>) -> Self {
         match self {
             Self::Discrete { .. } | Self::DiscreteSet { .. } | Self::Home => self,
@@ -15,7 +15,7 @@ use crate::{
         timing::TimingHandler,
         updatables::runtime::RuntimePhase,
     },
-    utils::math::instant_diff_secs,
+    utils::{clock, math::instant_diff_secs},
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -64,19 +64,19 @@ impl FeatureEffectRuntime {
     }
 
     pub fn start(&mut self, time_offset: f32) {
-        self.effect_started =
-            Some(time::Instant::now() - time::Duration::from_secs_f32(time_offset));
+        self.effect_started = Some(clock::now() - time::Duration::from_secs_f32(time_offset));
     }
 
     pub fn stop(&mut self) {
         self.effect_started = None;
     }
 
+    /// `channel_offset` returns the offset of a channel of the fixture in the selection.
     pub fn get_values_with_started(
         &self,
         fixture: &GdtfFixture,
         fixture_types: &FixtureTypeList,
-        fixture_offset: f32,
+        channel_offset: impl Fn(&str) -> f32,
         timing_handler: &TimingHandler,
         started: Option<time::Instant>,
     ) -> Vec<(String, FixtureChannelValue3)> {
@@ -92,7 +92,7 @@ impl FeatureEffectRuntime {
                     channel.name().as_ref(),
                     fixture,
                     fixture_types,
-                    fixture_offset,
+                    channel_offset(channel.name().as_ref()),
                     timing_handler,
                     started,
                 )
@@ -133,7 +133,7 @@ impl FeatureEffectRuntime {
             .ok_or(EffectError::EffectNotStarted)
             .and_then(|effect_started| {
                 let phase_offset = self.phase.phase(fixture_offset);
-                let mut started_elapsed = effect_started.elapsed().as_secs_f64();
+                let mut started_elapsed = clock::elapsed(effect_started).as_secs_f64();
 
                 let effective_bpm = match &self.speed {
                     EffectSpeed::SpeedMaster { id, scale, sync } => {
@@ -269,7 +269,7 @@ impl FeatureEffectRuntime {
             .ok_or(EffectError::EffectNotStarted)
             .and_then(|effect_started| {
                 let phase_offset = self.phase.phase(fixture_offset);
-                let mut started_elapsed = effect_started.elapsed().as_secs_f64();
+                let mut started_elapsed = clock::elapsed(effect_started).as_secs_f64();
 
                 let effective_bpm = match &self.speed {
                     EffectSpeed::SpeedMaster { id, scale, sync } => {
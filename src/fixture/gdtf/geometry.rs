@@ -0,0 +1,81 @@
+use gdtf::geometry::{Geometry, GeometryType};
+
+fn find_geometry<'a>(geometries: &'a [Geometry], name: &str) -> Option<&'a Geometry> {
+    geometries.iter().find_map(|geometry| {
+        if geometry.name.as_ref() == name {
+            Some(geometry)
+        } else {
+            find_geometry(&geometry.children, name)
+        }
+    })
+}
+
+/// Collects all geometry references of the geometry tree, in tree order.
+pub fn collect_geometry_references<'a>(
+    geometries: &'a [Geometry],
+    references: &mut Vec<&'a Geometry>,
+) {
+    for geometry in geometries {
+        if let GeometryType::GeometryReference { .. } = geometry.t {
+            references.push(geometry);
+        }
+
+        collect_geometry_references(&geometry.children, references);
+    }
+}
+
+/// DMX modes may define the channels of a geometry once (e.g. the beam of a pixel) and
+/// reuse them for every geometry reference to it, shifted by the DMX offset of the reference.
+/// This replaces such template channels with a channel for every reference, so the channels
+/// of every pixel can be addressed on their own.
+pub fn instantiate_template_channels(fixture_type: &mut gdtf::fixture_type::FixtureType) {
+    for dmx_mode in &mut fixture_type.dmx_modes {
+        // only the references below the geometry of the mode belong to it
+        let mut references = Vec::new();
+        if let Some(mode_geometry) =
+            find_geometry(&fixture_type.geometries, dmx_mode.geometry.as_ref())
+        {
+            collect_geometry_references(&mode_geometry.children, &mut references);
+        }
+
+        if references.is_empty() {
+            continue;
+        }
+
+        dmx_mode.dmx_channels = dmx_mode
+            .dmx_channels
+            .drain(..)
+            .flat_map(|dmx_channel| {
+                let instances = references
+                    .iter()
+                    .filter_map(|reference| match &reference.t {
+                        GeometryType::GeometryReference { geometry, breaks }
+                            if geometry.as_ref() == dmx_channel.geometry.as_ref() =>
+                        {
+                            let dmx_offset = breaks
+                                .first()
+                                .map(|dmx_break| dmx_break.dmx_offset as i64)
+                                .unwrap_or(1);
+
+                            let mut instance = dmx_channel.clone();
+                            instance.geometry = reference.name.clone();
+
+                            for offset in instance.offset.iter_mut().flatten() {
+                                *offset = (*offset as i64 + dmx_offset - 1) as _;
+                            }
+
+                            Some(instance)
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                if instances.is_empty() {
+                    vec![dmx_channel]
+                } else {
+                    instances
+                }
+            })
+            .collect();
+    }
+}
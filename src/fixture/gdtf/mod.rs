@@ -12,15 +12,20 @@ use super::{
         utils::dmx_value_to_f32,
     },
     error::FixtureError,
-    handler::FixtureTypeList,
-    presets::PresetHandler,
+    handler::{park::ParkHandler, FixtureTypeList},
+    presets::{preset::FixturePresetId, PresetHandler},
+    selection::FixtureSelection,
     timing::TimingHandler,
     updatables::UpdatableHandler,
     value_source::{FixtureChannelValueSource, FixtureChannelValueSourceTrait},
 };
 use std::collections::HashMap;
 
+use self::modifier::FixtureOutputModifiers;
+
 pub mod error;
+pub mod geometry;
+pub mod modifier;
 pub mod sync;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,9 +38,107 @@ pub struct GdtfFixturePatch {
 
     pub universe: u16,
     pub start_address: u16,
+
+    #[serde(default)]
+    pub modifiers: FixtureOutputModifiers,
+}
+
+pub fn dmx_mode_address_footprint(dmx_mode: &gdtf::dmx_mode::DmxMode) -> Result<u16, FixtureError> {
+    dmx_mode
+        .dmx_channels
+        .iter()
+        .flat_map(|dmx_channel| &dmx_channel.offset)
+        .flatten()
+        .max()
+        .map(|max_offset| *max_offset as u16)
+        .ok_or(FixtureError::GdtfMaxDmxOffsetNotFound)
+}
+
+/// Maps the channels, that belong to a cell, to their (1-based) cell.
+/// Cells are the geometry references of the fixture type (e.g. the pixels of a LED bar),
+/// that have channels in the DMX mode, numbered in the order of the geometry tree.
+/// Template channels have to be instantiated for every reference beforehand
+/// (see [`geometry::instantiate_template_channels`]).
+fn dmx_mode_channel_cells(
+    fixture_type: &gdtf::fixture_type::FixtureType,
+    dmx_mode: &gdtf::dmx_mode::DmxMode,
+) -> HashMap<String, u16> {
+    let mut geometry_references = Vec::new();
+    geometry::collect_geometry_references(&fixture_type.geometries, &mut geometry_references);
+
+    // a DMX mode may only use some of the references (e.g. a 4 pixel mode of a 8 pixel bar)
+    let cell_geometries = geometry_references
+        .into_iter()
+        .map(|reference| reference.name.as_ref())
+        .filter(|reference| {
+            dmx_mode
+                .dmx_channels
+                .iter()
+                .any(|dmx_channel| dmx_channel.geometry.as_ref() == *reference)
+        })
+        .collect::<Vec<_>>();
+
+    dmx_mode
+        .dmx_channels
+        .iter()
+        .filter_map(|dmx_channel| {
+            let cell = cell_geometries
+                .iter()
+                .position(|geometry| *geometry == dmx_channel.geometry.as_ref())?;
+
+            Some((dmx_channel.name().as_ref().to_owned(), cell as u16 + 1))
+        })
+        .collect()
+}
+
+/// The value of the first channel function of the channel, that controls a
+/// component of an additive or subtractive RGB color, or `None`, if there is none.
+pub fn rgb_channel_value(
+    channel: &gdtf::dmx_mode::DmxChannel,
+    [r, g, b]: [f32; 3],
+) -> Option<FixtureChannelValue3> {
+    channel.logical_channels[0]
+        .channel_functions
+        .iter()
+        .enumerate()
+        .find_map(|(channel_function_idx, channel_function)| {
+            let function_val = match channel_function.attribute.first().unwrap().as_ref() {
+                "ColorAdd_R" => r,
+                "ColorAdd_G" => g,
+                "ColorAdd_B" => b,
+                "ColorSub_C" => 1.0 - r,
+                "ColorSub_M" => 1.0 - g,
+                "ColorSub_Y" => 1.0 - b,
+                _ => return None,
+            };
+
+            Some(FixtureChannelValue3::Discrete {
+                channel_function_idx,
+                value: function_val,
+            })
+        })
+}
+
+fn max_dmx_value(num_bytes: usize) -> f64 {
+    ((1u64 << (8 * num_bytes)) - 1) as f64
 }
 
 impl GdtfFixturePatch {
+    pub fn address_footprint(&self, fixture_types: &FixtureTypeList) -> Result<u16, FixtureError> {
+        let fixture_type = fixture_types
+            .iter()
+            .find(|ft| ft.fixture_type_id == self.fixture_type_id)
+            .ok_or(FixtureError::GdtfFixtureTypeNotFound(self.fixture_type_id))?;
+
+        let dmx_mode = fixture_type
+            .dmx_mode(&self.fixture_type_dmx_mode)
+            .ok_or_else(|| {
+                FixtureError::GdtfFixtureDmxModeNotFound(self.fixture_type_dmx_mode.clone())
+            })?;
+
+        dmx_mode_address_footprint(dmx_mode)
+    }
+
     pub fn into_fixture(
         self,
         fixture_types: &[gdtf::fixture_type::FixtureType],
@@ -52,6 +155,7 @@ impl GdtfFixturePatch {
             self.fixture_type_dmx_mode,
             self.universe,
             self.start_address,
+            self.modifiers,
         )
     }
 }
@@ -68,6 +172,10 @@ pub struct GdtfFixture {
     start_address: u16,
     address_footprint: u16,
 
+    output_modifiers: FixtureOutputModifiers,
+
+    channel_cells: HashMap<String, u16>,
+
     programmer_values: HashMap<String, FixtureChannelValue3>,
     outputs_values: HashMap<String, FixtureChannelValue3>,
 
@@ -82,6 +190,7 @@ impl GdtfFixture {
         dmx_mode_name: String,
         universe: u16,
         start_address: u16,
+        output_modifiers: FixtureOutputModifiers,
     ) -> Result<Self, FixtureError> {
         let dmx_mode = fixture_type.dmx_mode(&dmx_mode_name).ok_or(
             FixtureError::GdtfFixtureDmxModeNotFound(dmx_mode_name.clone()),
@@ -98,14 +207,8 @@ impl GdtfFixture {
             })
             .collect();
 
-        let address_footprint = (dmx_mode
-            .dmx_channels
-            .iter()
-            .flat_map(|dmx_channel| &dmx_channel.offset)
-            .flatten()
-            .max()
-            .copied()
-            .ok_or(FixtureError::GdtfMaxDmxOffsetNotFound)?) as u16;
+        let address_footprint = dmx_mode_address_footprint(dmx_mode)?;
+        let channel_cells = dmx_mode_channel_cells(fixture_type, dmx_mode);
 
         Ok(Self {
             id,
@@ -115,6 +218,8 @@ impl GdtfFixture {
             universe,
             start_address,
             address_footprint,
+            output_modifiers,
+            channel_cells,
             programmer_values: values.clone(),
             outputs_values: values,
             sources: vec![FixtureChannelValueSource::Programmer],
@@ -145,6 +250,34 @@ impl GdtfFixture {
         self.address_footprint
     }
 
+    pub fn output_modifiers(&self) -> &FixtureOutputModifiers {
+        &self.output_modifiers
+    }
+
+    pub fn set_output_modifiers(&mut self, output_modifiers: FixtureOutputModifiers) {
+        self.output_modifiers = output_modifiers;
+    }
+
+    pub fn num_cells(&self) -> u16 {
+        self.channel_cells.values().max().copied().unwrap_or(0)
+    }
+
+    /// The cell, the channel belongs to or `None`, if it doesn't belong
+    /// to a cell (e.g. the master dimmer of a LED bar).
+    pub fn channel_cell(&self, channel_name: &str) -> Option<u16> {
+        self.channel_cells.get(channel_name).copied()
+    }
+
+    /// If only some cells of the fixture are selected, only their channels are selected.
+    pub fn is_channel_selected(&self, channel_name: &str, selection: &FixtureSelection) -> bool {
+        match selection.cells(self.id) {
+            Some(cells) => self
+                .channel_cell(channel_name)
+                .is_some_and(|cell| cells.contains(&cell)),
+            None => selection.has_fixture(self.id),
+        }
+    }
+
     pub fn fixture_type_id(&self) -> uuid::Uuid {
         self.fixture_type_id
     }
@@ -166,6 +299,26 @@ impl GdtfFixture {
         &self.sources
     }
 
+    /// Restores the programmer values and whether the programmer is a value source.
+    /// The other sources are kept, as they belong to the playback state.
+    pub fn restore_programmer_state(
+        &mut self,
+        programmer_values: HashMap<String, FixtureChannelValue3>,
+        programmer_active: bool,
+    ) {
+        self.programmer_values = programmer_values;
+
+        let is_active = self
+            .sources
+            .contains(&FixtureChannelValueSource::Programmer);
+
+        if programmer_active && !is_active {
+            self.sources.push(FixtureChannelValueSource::Programmer);
+        } else if !programmer_active {
+            self.remove_value_source(FixtureChannelValueSource::Programmer);
+        }
+    }
+
     pub fn fixture_type_and_dmx_mode<'a>(
         &self,
         fixture_types: &'a FixtureTypeList,
@@ -204,6 +357,12 @@ impl GdtfFixture {
         Ok(())
     }
 
+    pub fn map_programmer_preset_ids(&mut self, map: &impl Fn(FixturePresetId) -> FixturePresetId) {
+        for value in self.programmer_values.values_mut() {
+            value.map_preset_ids(map);
+        }
+    }
+
     pub fn push_value_source(&mut self, value_source: FixtureChannelValueSource) {
         self.sources.retain(|source| !source.eq(&value_source));
         self.sources.push(value_source);
@@ -238,6 +397,29 @@ impl GdtfFixture {
             .to_owned())
     }
 
+    pub fn get_channel_feature_group(
+        &self,
+        fixture_types: &FixtureTypeList,
+        channel_name: &str,
+    ) -> Result<FixtureChannel3FeatureGroup, FixtureError> {
+        let (fixture_type, dmx_mode) = self.fixture_type_and_dmx_mode(fixture_types)?;
+
+        let dmx_channel = dmx_mode
+            .dmx_channel(channel_name)
+            .ok_or_else(|| FixtureError::GdtfChannelNotFound(channel_name.to_owned()))?;
+
+        let attribute = dmx_channel.logical_channels[0]
+            .attribute(fixture_type)
+            .ok_or_else(|| FixtureError::GdtfChannelHasNoAttribute(channel_name.to_owned()))?;
+
+        attribute
+            .feature
+            .as_ref()
+            .and_then(|feature| feature.split_first())
+            .and_then(|(group_name, _)| group_name.as_ref().parse().ok())
+            .ok_or_else(|| FixtureError::GdtfChannelHasNoAttribute(channel_name.to_owned()))
+    }
+
     pub fn get_channels_in_feature_group(
         &self,
         fixture_types: &FixtureTypeList,
@@ -560,34 +742,8 @@ impl GdtfFixture {
         let (_, dmx_mode) = self.fixture_type_and_dmx_mode(fixture_types)?;
 
         for channel in &dmx_mode.dmx_channels {
-            for (channel_function_idx, channel_function) in channel.logical_channels[0]
-                .channel_functions
-                .iter()
-                .enumerate()
-            {
-                let function_val = match channel_function.attribute.first().unwrap().as_ref() {
-                    "ColorAdd_R" => Some(r),
-                    "ColorAdd_G" => Some(g),
-                    "ColorAdd_B" => Some(b),
-                    "ColorSub_C" => Some(1.0 - r),
-                    "ColorSub_M" => Some(1.0 - g),
-                    "ColorSub_Y" => Some(1.0 - b),
-                    _ => None,
-                };
-
-                if let Some(function_val) = function_val {
-                    self.set_programmer_value(
-                        fixture_types,
-                        channel.name().as_ref(),
-                        FixtureChannelValue3::Discrete {
-                            channel_function_idx,
-                            value: function_val,
-                        },
-                    )?;
-
-                    // Skip the rest of the channel functions
-                    break;
-                }
+            if let Some(value) = rgb_channel_value(channel, [r, g, b]) {
+                self.set_programmer_value(fixture_types, channel.name().as_ref(), value)?;
             }
         }
 
@@ -698,10 +854,18 @@ impl GdtfFixture {
     pub fn update_programmer_attribute_matches_value(
         &mut self,
         fixture_types: &FixtureTypeList,
+        selection: &FixtureSelection,
         filter: impl Fn(&str) -> bool,
         slider_val: FixtureChannelValue3Discrete,
     ) -> Result<(), FixtureError> {
-        for (channel, _, _) in self.channels_for_attribute_matches(fixture_types, filter)? {
+        let channels = self
+            .channels_for_attribute_matches(fixture_types, filter)?
+            .into_iter()
+            .map(|(channel, _, _)| channel)
+            .filter(|channel| self.is_channel_selected(channel.name().as_ref(), selection))
+            .collect::<Vec<_>>();
+
+        for channel in channels {
             self.update_programmer_value(
                 fixture_types,
                 channel.name().as_ref(),
@@ -831,6 +995,7 @@ impl GdtfFixture {
         preset_handler: &PresetHandler,
         timing_handler: &TimingHandler,
         grand_master: f32,
+        park_handler: &ParkHandler,
     ) -> Result<Vec<u8>, FixtureError> {
         let (_, dmx_mode) = self.fixture_type_and_dmx_mode(fixture_types)?;
 
@@ -844,10 +1009,33 @@ impl GdtfFixture {
                 None => continue,
             };
 
-            let value = self
-                .outputs_values
-                .get(dmx_channel.name().as_ref())
-                .unwrap();
+            let logical_channel = &dmx_channel.logical_channels[0];
+            let attribute = logical_channel.attribute.first().unwrap().as_ref();
+
+            // parked values replace the merged value of the sources
+            // and aren't affected by the grand master
+            let parked_value = park_handler
+                .fixture_attribute_value(self.id, attribute)
+                .map(|value| FixtureChannelValue3::Discrete {
+                    channel_function_idx: logical_channel
+                        .channel_functions
+                        .iter()
+                        .position(|function| {
+                            function.attribute.first().unwrap().as_ref() == attribute
+                        })
+                        .unwrap_or_default(),
+                    value,
+                });
+
+            let (value, grand_master) = match &parked_value {
+                Some(parked_value) => (parked_value, 1.0),
+                None => (
+                    self.outputs_values
+                        .get(dmx_channel.name().as_ref())
+                        .unwrap(),
+                    grand_master,
+                ),
+            };
 
             let dmx_value = value
                 .to_dmx(
@@ -865,9 +1053,40 @@ impl GdtfFixture {
 
             dynamic_data.insert(dmx_channel.name().as_ref().to_string(), dmx_value);
 
-            let mut real_dmx_value = dmx_value.to(offsets.len() as u8);
+            let (output_offsets, mut real_dmx_value) =
+                if self.output_modifiers.is_modified(attribute) {
+                    // swapped values are output on the channel of the other attribute
+                    let output_attribute = self.output_modifiers.output_attribute(attribute);
+                    let output_offsets = dmx_mode
+                        .dmx_channels
+                        .iter()
+                        .find(|other_channel| {
+                            other_channel.logical_channels[0]
+                                .attribute
+                                .first()
+                                .unwrap()
+                                .as_ref()
+                                == output_attribute
+                        })
+                        .and_then(|other_channel| other_channel.offset.as_ref())
+                        .unwrap_or(offsets);
+
+                    let value =
+                        dmx_value.to(offsets.len() as u8) as f64 / max_dmx_value(offsets.len());
+                    let value = self
+                        .output_modifiers
+                        .attribute_modifier(attribute)
+                        .map_or(value, |modifier| modifier.apply(value as f32) as f64);
+
+                    (
+                        output_offsets,
+                        (value * max_dmx_value(output_offsets.len())).round() as u64,
+                    )
+                } else {
+                    (offsets, dmx_value.to(offsets.len() as u8) as u64)
+                };
 
-            for offset in offsets.iter().rev() {
+            for offset in output_offsets.iter().rev() {
                 data[*offset as usize - 1] = (real_dmx_value & 0xFF) as u8;
                 real_dmx_value >>= 8;
             }
@@ -876,3 +1095,61 @@ impl GdtfFixture {
         Ok(data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{geometry::instantiate_template_channels, GdtfFixture};
+
+    const PIXEL_FIXTURE_TYPE_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_data/fixtures/Briteq@POWERPIXEL8-RGB@20240710_2_Misdat.gdtf"
+    );
+
+    #[test]
+    fn test_geometry_reference_cells() {
+        let mut fixture_types =
+            gdtf::GdtfFile::new(fs::File::open(PIXEL_FIXTURE_TYPE_FILE).unwrap())
+                .unwrap()
+                .description
+                .fixture_types;
+        fixture_types
+            .iter_mut()
+            .for_each(instantiate_template_channels);
+
+        let fixture = GdtfFixture::new(
+            1,
+            "Pixel".to_owned(),
+            &fixture_types[0],
+            "24Ch - pixel".to_owned(),
+            0,
+            1,
+            Default::default(),
+        )
+        .unwrap();
+
+        assert_eq!(fixture.num_cells(), 8);
+        assert_eq!(fixture.address_footprint(), 24);
+
+        // every RGB triple of the mode belongs to its own pixel
+        let mut num_offset_channels = 0;
+        for (dmx_channel, _) in fixture.channels(&fixture_types).unwrap() {
+            let Some(offset) = dmx_channel
+                .offset
+                .as_ref()
+                .and_then(|offset| offset.first())
+            else {
+                continue;
+            };
+
+            num_offset_channels += 1;
+            assert_eq!(
+                fixture.channel_cell(dmx_channel.name().as_ref()),
+                Some((*offset as u16 - 1) / 3 + 1)
+            );
+        }
+
+        assert_eq!(num_offset_channels, 24);
+    }
+}
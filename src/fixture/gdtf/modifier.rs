@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+fn default_max() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub enum DimmerCurve {
+    #[default]
+    Linear,
+    SquareLaw,
+    SCurve,
+
+    /// Output values for evenly spaced input values between 0 and 1,
+    /// values in between are interpolated linearly
+    Lut(Vec<f32>),
+}
+
+impl DimmerCurve {
+    pub fn apply(&self, value: f32) -> f32 {
+        match self {
+            Self::Linear => value,
+            Self::SquareLaw => value * value,
+            Self::SCurve => value * value * (3.0 - 2.0 * value),
+            Self::Lut(points) => match points.len() {
+                0 => value,
+                1 => points[0],
+                num_points => {
+                    let position = value * (num_points - 1) as f32;
+                    let idx = (position.floor() as usize).min(num_points - 2);
+                    let fract = position - idx as f32;
+
+                    points[idx] + (points[idx + 1] - points[idx]) * fract
+                }
+            },
+        }
+    }
+}
+
+/// Modifies the output value of every channel of the attribute.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct FixtureAttributeModifier {
+    pub attribute: String,
+
+    #[serde(default)]
+    pub curve: DimmerCurve,
+
+    #[serde(default)]
+    pub invert: bool,
+
+    /// Added to the output value in (8-bit) DMX steps
+    #[serde(default)]
+    pub offset: i16,
+
+    #[serde(default)]
+    pub min: f32,
+
+    #[serde(default = "default_max")]
+    pub max: f32,
+}
+
+impl Default for FixtureAttributeModifier {
+    fn default() -> Self {
+        Self {
+            attribute: "Dimmer".to_owned(),
+            curve: DimmerCurve::default(),
+            invert: false,
+            offset: 0,
+            min: 0.0,
+            max: default_max(),
+        }
+    }
+}
+
+impl FixtureAttributeModifier {
+    /// Applies the modifier to a value between 0 and 1.
+    pub fn apply(&self, value: f32) -> f32 {
+        let value = if self.invert { 1.0 - value } else { value };
+        let value = self.curve.apply(value.clamp(0.0, 1.0));
+        let value = value + self.offset as f32 / 255.0;
+
+        value.clamp(self.min.max(0.0), self.max.min(1.0).max(self.min))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct FixtureOutputModifiers {
+    /// Outputs the pan value on the tilt channels and vice versa.
+    /// The attribute modifiers are applied before swapping.
+    #[serde(default)]
+    pub swap_pan_tilt: bool,
+
+    #[serde(default)]
+    pub attributes: Vec<FixtureAttributeModifier>,
+}
+
+impl FixtureOutputModifiers {
+    pub fn attribute_modifier(&self, attribute: &str) -> Option<&FixtureAttributeModifier> {
+        self.attributes
+            .iter()
+            .find(|modifier| modifier.attribute == attribute)
+    }
+
+    /// The attribute, whose channels the value of `attribute` is output on.
+    pub fn output_attribute<'a>(&self, attribute: &'a str) -> &'a str {
+        match attribute {
+            "Pan" if self.swap_pan_tilt => "Tilt",
+            "Tilt" if self.swap_pan_tilt => "Pan",
+            _ => attribute,
+        }
+    }
+
+    pub fn is_modified(&self, attribute: &str) -> bool {
+        self.output_attribute(attribute) != attribute
+            || self.attribute_modifier(attribute).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DimmerCurve, FixtureAttributeModifier};
+
+    #[test]
+    fn test_dimmer_curves() {
+        assert_eq!(DimmerCurve::SquareLaw.apply(0.5), 0.25);
+        assert_eq!(DimmerCurve::SCurve.apply(0.5), 0.5);
+        assert_eq!(DimmerCurve::SCurve.apply(1.0), 1.0);
+
+        let lut = DimmerCurve::Lut(vec![0.0, 0.25, 1.0]);
+        assert_eq!(lut.apply(0.25), 0.125);
+        assert_eq!(lut.apply(0.75), 0.625);
+        assert_eq!(lut.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_attribute_modifier() {
+        let modifier = FixtureAttributeModifier {
+            attribute: "Pan".to_owned(),
+            invert: true,
+            max: 0.5,
+            ..Default::default()
+        };
+
+        assert_eq!(modifier.apply(0.0), 0.5);
+        assert_eq!(modifier.apply(0.75), 0.25);
+
+        let modifier = FixtureAttributeModifier {
+            offset: 51,
+            min: 0.1,
+            ..Default::default()
+        };
+
+        assert_eq!(modifier.apply(0.0), 0.2);
+        assert_eq!(modifier.apply(1.0), 1.0);
+    }
+}
@@ -7,6 +7,8 @@ pub enum FixtureHandlerError {
     FixtureHandlerUpdateError(Box<dyn std::error::Error>),
     FixtureError(FixtureError),
     FixtureAddressOverlap(u16, u16, u16),
+    ParkAddressOutOfRange(u16, u16),
+    NoChannelsToPark,
 }
 
 impl std::fmt::Display for FixtureHandlerError {
@@ -27,6 +29,12 @@ impl std::fmt::Display for FixtureHandlerError {
                     universe, start, end
                 )
             }
+            FixtureHandlerError::ParkAddressOutOfRange(universe, address) => {
+                write!(f, "Address {}.{} is out of range", universe, address)
+            }
+            FixtureHandlerError::NoChannelsToPark => {
+                write!(f, "None of the fixtures have a channel for the attributes")
+            }
         }
     }
 }
@@ -1,10 +1,16 @@
-use std::collections::{BTreeSet, HashMap};
+use std::{
+    collections::{BTreeSet, HashMap},
+    time,
+};
 
 use itertools::Itertools;
 
-use crate::dmx::{DemexDmxOutput, DemexDmxOutputTrait};
+use crate::{
+    dmx::{routing::DmxRouting, DemexDmxOutput, DemexDmxOutputTrait},
+    utils::clock,
+};
 
-use self::error::FixtureHandlerError;
+use self::{error::FixtureHandlerError, park::ParkHandler};
 
 use super::{
     gdtf::GdtfFixture, presets::PresetHandler, selection::FixtureSelection, timing::TimingHandler,
@@ -12,6 +18,7 @@ use super::{
 };
 
 pub mod error;
+pub mod park;
 pub mod sync;
 
 pub type FixtureTypeList = [gdtf::fixture_type::FixtureType];
@@ -38,11 +45,22 @@ fn compare_universe_output_data(
 
 fn write_universe_data(
     universe_data: &mut [u8; 512],
+    mut universe_changed_at: Option<&mut [time::Instant; 512]>,
     fixture_data: &[u8],
     fixture_universe_offset: u16,
 ) {
+    let now = clock::now();
+
     for (i, d) in fixture_data.iter().enumerate() {
-        universe_data[i + fixture_universe_offset as usize] = *d;
+        let channel_idx = i + fixture_universe_offset as usize;
+
+        if universe_data[channel_idx] != *d {
+            if let Some(universe_changed_at) = universe_changed_at.as_mut() {
+                universe_changed_at[channel_idx] = now;
+            }
+        }
+
+        universe_data[channel_idx] = *d;
     }
 }
 
@@ -50,7 +68,12 @@ fn write_universe_data(
 pub struct FixtureHandler {
     fixtures: Vec<GdtfFixture>,
     outputs: Vec<DemexDmxOutput>,
+    routing: DmxRouting,
+    park_handler: ParkHandler,
+    // parked addresses, that were applied to the last sent data
+    applied_parked_addresses: Vec<(u16, u16, u8)>,
     universe_output_data: HashMap<u16, [u8; 512]>,
+    universe_output_changed_at: HashMap<u16, [time::Instant; 512]>,
     grand_master: u8,
     is_controller: bool,
 }
@@ -63,6 +86,7 @@ impl FixtureHandler {
     pub fn new(
         fixtures: Vec<GdtfFixture>,
         outputs: Vec<DemexDmxOutput>,
+        routing: DmxRouting,
         is_controller: bool,
     ) -> Result<Self, FixtureHandlerError> {
         // check if the fixtures overlap
@@ -88,21 +112,42 @@ impl FixtureHandler {
         }
 
         let mut universe_output_data = HashMap::new();
+        let mut universe_output_changed_at = HashMap::new();
+        // universes of the outputs are physical universes, so the ones
+        // claimed by a route aren't used as logical universes
         for universe in outputs
             .iter()
-            .filter(|output| output.should_output())
-            .filter_map(|output| output.config().universes())
-            .flatten()
-            .dedup()
+            .enumerate()
+            .filter(|(_, output)| output.should_output())
+            .filter_map(|(output_idx, output)| {
+                output
+                    .config()
+                    .universes()
+                    .map(|universes| (output_idx, universes))
+            })
+            .flat_map(|(output_idx, universes)| {
+                let routing = &routing;
+
+                universes
+                    .into_iter()
+                    .filter(move |universe| !routing.is_destination(output_idx, *universe))
+            })
+            .chain(routing.routed_universes())
+            .unique()
         {
             log::debug!("Outputting on universe {}", universe);
             universe_output_data.insert(universe, [0; 512]);
+            universe_output_changed_at.insert(universe, [clock::now(); 512]);
         }
 
         Ok(Self {
             universe_output_data,
+            universe_output_changed_at,
             fixtures,
             outputs,
+            routing,
+            park_handler: ParkHandler::default(),
+            applied_parked_addresses: Vec::new(),
             is_controller,
             grand_master: Self::default_grandmaster_value(),
         })
@@ -142,6 +187,24 @@ impl FixtureHandler {
         &self.outputs
     }
 
+    pub fn universe_output_data(&self) -> &HashMap<u16, [u8; 512]> {
+        &self.universe_output_data
+    }
+
+    pub fn park_handler(&self) -> &ParkHandler {
+        &self.park_handler
+    }
+
+    pub fn park_handler_mut(&mut self) -> &mut ParkHandler {
+        &mut self.park_handler
+    }
+
+    pub fn shutdown_outputs(&mut self) {
+        for output in &mut self.outputs {
+            output.shutdown();
+        }
+    }
+
     pub fn home_all(&mut self, clear_sources: bool) -> Result<(), FixtureHandlerError> {
         for f in self.fixtures.iter_mut() {
             f.home(clear_sources)
@@ -213,6 +276,7 @@ impl FixtureHandler {
                     preset_handler,
                     timing_handler,
                     self.grand_master as f32 / 255.0,
+                    &self.park_handler,
                 )
                 .map_err(FixtureHandlerError::FixtureError)?;
 
@@ -230,24 +294,65 @@ impl FixtureHandler {
             let universe_data = self.universe_output_data.get_mut(&f.universe());
 
             if let Some(universe_data) = universe_data {
-                write_universe_data(universe_data, &data_packet, fixture_universe_offset);
+                write_universe_data(
+                    universe_data,
+                    self.universe_output_changed_at.get_mut(&f.universe()),
+                    &data_packet,
+                    fixture_universe_offset,
+                );
                 dirty_universes.insert(f.universe());
             }
         }
 
-        for output in &mut self.outputs {
-            for (universe, data) in &self.universe_output_data {
+        // merge data of inputs (e.g. Art-Net input) into the data we're sending,
+        // without touching our own output data
+        let mut merged_output_data = self.universe_output_data.clone();
+        for output in &self.outputs {
+            output.merge_input_into(
+                &mut merged_output_data,
+                &self.universe_output_changed_at,
+                &mut dirty_universes,
+            );
+        }
+
+        // parked addresses override everything, including the inputs
+        let parked_addresses = self.park_handler.parked_addresses().collect::<Vec<_>>();
+        if parked_addresses != self.applied_parked_addresses {
+            dirty_universes.extend(
+                parked_addresses
+                    .iter()
+                    .chain(self.applied_parked_addresses.iter())
+                    .map(|(universe, _, _)| *universe),
+            );
+        }
+
+        for (universe, address, value) in &parked_addresses {
+            if let Some(data) = merged_output_data.get_mut(universe) {
+                data[*address as usize - 1] = *value;
+            }
+        }
+
+        self.applied_parked_addresses = parked_addresses;
+
+        for (output_idx, output) in self.outputs.iter_mut().enumerate() {
+            for (universe, data) in &merged_output_data {
                 if !dirty_universes.contains(universe) {
                     continue;
                 }
 
-                if let Err(err) = output.send(*universe, data) {
-                    log::warn!(
-                        "Failed to send data via {:?} for universe {}. Did the corresponding output thread panic?\n{}",
-                        output, universe, err
-                    );
+                for physical_universe in self.routing.physical_universes(output_idx, *universe) {
+                    if let Err(err) = output.send(physical_universe, data) {
+                        log::warn!(
+                            "Failed to send data via {:?} for universe {}. Did the corresponding output thread panic?\n{}",
+                            output, physical_universe, err
+                        );
+                    }
                 }
             }
+
+            if !dirty_universes.is_empty() {
+                output.sync();
+            }
         }
 
         Ok(dirty_universes.len())
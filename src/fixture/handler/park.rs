@@ -0,0 +1,143 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParkTarget {
+    FixtureAttribute {
+        fixture_id: u32,
+        attribute: String,
+    },
+
+    /// A raw DMX address of a logical universe
+    Address {
+        universe: u16,
+        address: u16,
+    },
+}
+
+impl fmt::Display for ParkTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FixtureAttribute {
+                fixture_id,
+                attribute,
+            } => write!(f, "Fixture {} {}", fixture_id, attribute),
+            Self::Address { universe, address } => write!(f, "{}.{}", universe, address),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParkedValue {
+    pub target: ParkTarget,
+
+    /// Value between 0 and 1. For fixture attributes, this is the value
+    /// of the channel function of the attribute.
+    pub value: f32,
+}
+
+impl ParkedValue {
+    pub fn dmx_value(&self) -> u8 {
+        (self.value.clamp(0.0, 1.0) * 255.0).round() as u8
+    }
+}
+
+/// Values, that are locked regardless of the programmer, executors and inputs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParkHandler {
+    #[serde(default)]
+    parked: Vec<ParkedValue>,
+}
+
+impl ParkHandler {
+    pub fn parked(&self) -> &[ParkedValue] {
+        &self.parked
+    }
+
+    /// Parks `target` at `value`, replacing the value, if it's already parked.
+    pub fn park(&mut self, target: ParkTarget, value: f32) {
+        if let Some(parked) = self
+            .parked
+            .iter_mut()
+            .find(|parked| parked.target == target)
+        {
+            parked.value = value;
+        } else {
+            self.parked.push(ParkedValue { target, value });
+        }
+    }
+
+    /// Returns `false`, if `target` wasn't parked.
+    pub fn unpark(&mut self, target: &ParkTarget) -> bool {
+        let num_parked = self.parked.len();
+        self.parked.retain(|parked| parked.target != *target);
+        self.parked.len() != num_parked
+    }
+
+    pub fn unpark_all(&mut self) -> usize {
+        let num_parked = self.parked.len();
+        self.parked.clear();
+        num_parked
+    }
+
+    pub fn fixture_attribute_value(&self, fixture_id: u32, attribute: &str) -> Option<f32> {
+        self.parked.iter().find_map(|parked| match &parked.target {
+            ParkTarget::FixtureAttribute {
+                fixture_id: parked_fixture_id,
+                attribute: parked_attribute,
+            } if *parked_fixture_id == fixture_id && parked_attribute == attribute => {
+                Some(parked.value)
+            }
+            _ => None,
+        })
+    }
+
+    /// The parked raw addresses as `(universe, address, dmx value)`.
+    /// Addresses outside of the universe are skipped.
+    pub fn parked_addresses(&self) -> impl Iterator<Item = (u16, u16, u8)> + '_ {
+        self.parked.iter().filter_map(|parked| match parked.target {
+            ParkTarget::Address { universe, address } if (1..=512).contains(&address) => {
+                Some((universe, address, parked.dmx_value()))
+            }
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParkHandler, ParkTarget};
+
+    #[test]
+    fn test_park_handler() {
+        let mut park_handler = ParkHandler::default();
+
+        let dimmer = ParkTarget::FixtureAttribute {
+            fixture_id: 1,
+            attribute: "Dimmer".to_owned(),
+        };
+
+        park_handler.park(dimmer.clone(), 0.5);
+        park_handler.park(dimmer.clone(), 1.0);
+        park_handler.park(
+            ParkTarget::Address {
+                universe: 2,
+                address: 12,
+            },
+            0.0,
+        );
+
+        assert_eq!(park_handler.parked().len(), 2);
+        assert_eq!(park_handler.fixture_attribute_value(1, "Dimmer"), Some(1.0));
+        assert_eq!(park_handler.fixture_attribute_value(2, "Dimmer"), None);
+        assert_eq!(
+            park_handler.parked_addresses().collect::<Vec<_>>(),
+            vec![(2, 12, 0)]
+        );
+
+        assert!(park_handler.unpark(&dimmer));
+        assert!(!park_handler.unpark(&dimmer));
+        assert_eq!(park_handler.unpark_all(), 1);
+    }
+}
@@ -1,23 +1,93 @@
-use std::ops::Range;
+use std::{fmt, ops::Range};
 
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    dmx::{DemexDmxOutput, DemexDmxOutputConfig},
+    dmx::{routing::DmxRouting, DemexDmxOutput, DemexDmxOutputConfig},
     headless::id::DemexProtoDeviceId,
 };
 
 use super::{
+    error::FixtureError,
     gdtf::{GdtfFixture, GdtfFixturePatch},
     handler::FixtureTypeList,
     layout::FixtureLayout,
 };
 
+const DMX_UNIVERSE_SIZE: u16 = 512;
+
+#[derive(Debug)]
+pub enum PatchValidationIssue {
+    FixtureError(u32, FixtureError),
+    AddressOutOfRange {
+        fixture_id: u32,
+        universe: u16,
+        start_address: u16,
+        end_address: u32,
+    },
+    AddressOverlap {
+        universe: u16,
+        fixture_ids: (u32, u32),
+        start_address: u16,
+        end_address: u16,
+    },
+    RouteOutputNotFound {
+        universe: u16,
+        output: usize,
+    },
+    RouteUniverseOutOfRange {
+        universe: u16,
+        output: usize,
+    },
+}
+
+impl fmt::Display for PatchValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FixtureError(fixture_id, err) => write!(f, "Fixture {}: {}", fixture_id, err),
+            Self::AddressOutOfRange {
+                fixture_id,
+                universe,
+                start_address,
+                end_address,
+            } => write!(
+                f,
+                "Fixture {} is out of range (U{}): {} - {}",
+                fixture_id, universe, start_address, end_address
+            ),
+            Self::AddressOverlap {
+                universe,
+                fixture_ids: (fixture_a, fixture_b),
+                start_address,
+                end_address,
+            } => write!(
+                f,
+                "Fixtures {} and {} overlap (U{}): {} - {}",
+                fixture_a, fixture_b, universe, start_address, end_address
+            ),
+            Self::RouteOutputNotFound { universe, output } => write!(
+                f,
+                "Universe {} is routed to output {}, which doesn't exist",
+                universe, output
+            ),
+            Self::RouteUniverseOutOfRange { universe, output } => write!(
+                f,
+                "Universe {} is routed to a universe of output {}, that is out of range",
+                universe, output
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SerializablePatch {
     fixtures: Vec<GdtfFixturePatch>,
     layout: FixtureLayout,
     outputs: Vec<DemexDmxOutputConfig>,
+
+    #[serde(default)]
+    routing: DmxRouting,
 }
 
 impl SerializablePatch {
@@ -27,6 +97,7 @@ impl SerializablePatch {
             fixture_types,
             layout: self.layout,
             outputs: self.outputs,
+            routing: self.routing,
         }
     }
 
@@ -35,6 +106,7 @@ impl SerializablePatch {
             fixtures: patch.fixtures.clone(),
             layout: patch.layout.clone(),
             outputs: patch.outputs.clone(),
+            routing: patch.routing.clone(),
         }
     }
 }
@@ -45,6 +117,7 @@ pub struct Patch {
     fixture_types: Vec<gdtf::fixture_type::FixtureType>,
     layout: FixtureLayout,
     outputs: Vec<DemexDmxOutputConfig>,
+    routing: DmxRouting,
 }
 
 impl Patch {
@@ -76,22 +149,164 @@ impl Patch {
         &mut self.outputs
     }
 
-    pub fn is_address_range_unpatched(&self, _address_range: Range<u16>, _universe: u16) -> bool {
-        todo!();
-        /*
-        for fixture in self.fixtures.iter().filter(|f| f.universe == universe) {
-            let fixture_type = self.fixture_types().get(&fixture.fixture_type).unwrap();
-            let fixture_mode = fixture_type.modes.get(&fixture.fixture_mode).unwrap();
+    pub fn routing(&self) -> &DmxRouting {
+        &self.routing
+    }
+
+    pub fn routing_mut(&mut self) -> &mut DmxRouting {
+        &mut self.routing
+    }
+
+    /// Returns the address ranges, that are occupied in the given universe, sorted by
+    /// their start address. Fixtures with an unknown footprint are ignored.
+    fn occupied_address_ranges(&self, universe: u16) -> Vec<(u32, Range<u16>)> {
+        let mut ranges = self
+            .fixtures
+            .iter()
+            .filter(|fixture| fixture.universe == universe)
+            .filter_map(|fixture| {
+                fixture
+                    .address_footprint(&self.fixture_types)
+                    .ok()
+                    .map(|footprint| {
+                        (
+                            fixture.id,
+                            fixture.start_address..fixture.start_address.saturating_add(footprint),
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        ranges.sort_by_key(|(_, range)| range.start);
+        ranges
+    }
+
+    pub fn is_address_range_unpatched(&self, address_range: Range<u16>, universe: u16) -> bool {
+        self.occupied_address_ranges(universe)
+            .iter()
+            .all(|(_, range)| range.end <= address_range.start || address_range.end <= range.start)
+    }
+
+    /// Finds the first address (starting at `from_address`), where `footprint` channels
+    /// are unpatched in the given universe.
+    pub fn next_free_address(
+        &self,
+        universe: u16,
+        footprint: u16,
+        from_address: u16,
+    ) -> Option<u16> {
+        let mut address = from_address.max(1);
+
+        for (_, range) in self.occupied_address_ranges(universe) {
+            if address.checked_add(footprint)? <= range.start {
+                break;
+            }
+
+            address = address.max(range.end);
+        }
+
+        (address as u32 + footprint as u32 - 1 <= DMX_UNIVERSE_SIZE as u32).then_some(address)
+    }
+
+    /// Checks the whole patch and reports every issue, that would prevent the
+    /// fixtures from being loaded.
+    pub fn validate(&self) -> Vec<PatchValidationIssue> {
+        let mut issues = Vec::new();
+
+        for fixture in &self.fixtures {
+            match fixture.address_footprint(&self.fixture_types) {
+                Ok(footprint) => {
+                    let end_address = fixture.start_address as u32 + footprint as u32 - 1;
+
+                    if fixture.start_address == 0 || end_address > DMX_UNIVERSE_SIZE as u32 {
+                        issues.push(PatchValidationIssue::AddressOutOfRange {
+                            fixture_id: fixture.id,
+                            universe: fixture.universe,
+                            start_address: fixture.start_address,
+                            end_address,
+                        });
+                    }
+                }
+                Err(err) => issues.push(PatchValidationIssue::FixtureError(fixture.id, err)),
+            }
+        }
+
+        for universe in self
+            .fixtures
+            .iter()
+            .map(|fixture| fixture.universe)
+            .unique()
+        {
+            let ranges = self.occupied_address_ranges(universe);
+
+            for (idx, (fixture_a, range_a)) in ranges.iter().enumerate() {
+                // ranges are sorted, so only the following ranges, that start
+                // before this one ends, can overlap
+                for (fixture_b, range_b) in ranges[idx + 1..]
+                    .iter()
+                    .take_while(|(_, range_b)| range_b.start < range_a.end)
+                {
+                    issues.push(PatchValidationIssue::AddressOverlap {
+                        universe,
+                        fixture_ids: (*fixture_a, *fixture_b),
+                        start_address: range_b.start,
+                        end_address: range_a.end.min(range_b.end) - 1,
+                    });
+                }
+            }
+        }
 
-            let fixture_range = fixture.start_address
-                ..(fixture.start_address + fixture_mode.channel_types.len() as u16);
+        for route in self.routing.routes() {
+            if route.output >= self.outputs.len() {
+                issues.push(PatchValidationIssue::RouteOutputNotFound {
+                    universe: route.universe,
+                    output: route.output,
+                });
+            }
 
-            if ranges_overlap(fixture_range, address_range.clone()) {
-                return false;
+            if route.physical_universes().contains(&None) {
+                issues.push(PatchValidationIssue::RouteUniverseOutOfRange {
+                    universe: route.universe,
+                    output: route.output,
+                });
             }
         }
-        true
-        */
+
+        issues
+    }
+
+    /// Adds the given fixtures to the patch and returns them as loaded fixtures.
+    /// If the patch would become invalid, it is left unchanged.
+    pub fn add_fixtures(
+        &mut self,
+        new_fixtures: Vec<GdtfFixturePatch>,
+    ) -> Result<Vec<GdtfFixture>, String> {
+        let num_patched_fixtures = self.fixtures.len();
+        self.fixtures.extend(new_fixtures.iter().cloned());
+
+        let issues = self.validate();
+
+        let new_fixtures = if issues.is_empty() {
+            new_fixtures
+                .into_iter()
+                .map(|fixture| {
+                    fixture
+                        .into_fixture(&self.fixture_types)
+                        .map_err(|err| err.to_string())
+                })
+                .collect::<Result<Vec<_>, _>>()
+        } else {
+            Err(format!(
+                "The patch would be invalid:\n{}",
+                issues.iter().join("\n")
+            ))
+        };
+
+        if new_fixtures.is_err() {
+            self.fixtures.truncate(num_patched_fixtures);
+        }
+
+        new_fixtures
     }
 }
 
@@ -114,3 +329,106 @@ impl Patch {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::fixture::gdtf::GdtfFixturePatch;
+
+    use super::{Patch, PatchValidationIssue};
+
+    const DIMMER_FIXTURE_TYPE_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_data/fixtures/GDTF_Template_a@Basic_Conventional@test.gdtf"
+    );
+
+    // dimmers with a footprint of one channel, given as (universe, start address)
+    fn dimmer_patch(addresses: impl IntoIterator<Item = (u16, u16)>) -> Patch {
+        let mut patch = Patch::default();
+        *patch.fixture_types_mut() =
+            gdtf::GdtfFile::new(fs::File::open(DIMMER_FIXTURE_TYPE_FILE).unwrap())
+                .unwrap()
+                .description
+                .fixture_types;
+
+        let fixture_type_id = patch.fixture_types()[0].fixture_type_id;
+        let fixtures = addresses
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (universe, start_address))| GdtfFixturePatch {
+                id: idx as u32 + 1,
+                name: format!("Dimmer {}", idx + 1),
+                fixture_type_id,
+                fixture_type_dmx_mode: "Standard mode".to_owned(),
+                universe,
+                start_address,
+                modifiers: Default::default(),
+            })
+            .collect::<Vec<_>>();
+        patch.fixtures_mut().extend(fixtures);
+
+        patch
+    }
+
+    #[test]
+    fn test_validate_address_overlap() {
+        // adjacent fixtures and the same address in another universe don't overlap
+        assert!(dimmer_patch([(0, 1), (0, 2), (1, 1)]).validate().is_empty());
+
+        let issues = dimmer_patch([(0, 1), (0, 2), (0, 2)]).validate();
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0],
+            PatchValidationIssue::AddressOverlap {
+                universe: 0,
+                fixture_ids: (2, 3),
+                start_address: 2,
+                end_address: 2,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_validate_address_out_of_range() {
+        assert!(dimmer_patch([(0, 512)]).validate().is_empty());
+
+        let issues = dimmer_patch([(0, 0), (0, 513)]).validate();
+        assert_eq!(issues.len(), 2);
+        assert!(matches!(
+            issues[0],
+            PatchValidationIssue::AddressOutOfRange {
+                fixture_id: 1,
+                start_address: 0,
+                ..
+            }
+        ));
+        assert!(matches!(
+            issues[1],
+            PatchValidationIssue::AddressOutOfRange {
+                fixture_id: 2,
+                start_address: 513,
+                end_address: 513,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_next_free_address() {
+        let patch = dimmer_patch([(0, 1), (0, 2), (0, 4)]);
+
+        assert_eq!(patch.next_free_address(0, 1, 0), Some(3));
+        assert_eq!(patch.next_free_address(0, 2, 1), Some(5));
+        assert_eq!(patch.next_free_address(0, 1, 4), Some(5));
+        assert_eq!(patch.next_free_address(1, 1, 1), Some(1));
+
+        // only address 511 is free
+        let patch = dimmer_patch((1..=510).chain([512]).map(|address| (0, address)));
+        assert_eq!(patch.next_free_address(0, 1, 1), Some(511));
+        assert_eq!(patch.next_free_address(0, 2, 1), None);
+
+        let patch = dimmer_patch((1..=512).map(|address| (0, address)));
+        assert_eq!(patch.next_free_address(0, 1, 1), None);
+    }
+}
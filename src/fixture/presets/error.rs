@@ -23,6 +23,9 @@ pub enum PresetHandlerError {
     CueNotFound(u32, CueIdx),
     CantUpdateNonDefaultCue(u32, CueIdx),
     InvalidCueRange(CueIdx, CueIdx),
+    TargetAlreadyExists(String),
+    TargetOutOfRange(u32),
+    CantMergeEffectPreset(FixturePresetId),
 }
 
 impl std::fmt::Display for PresetHandlerError {
@@ -96,6 +99,23 @@ impl std::fmt::Display for PresetHandlerError {
                     cue_idx_from_major, cue_idx_from_minor, cue_idx_to_major, cue_idx_to_minor
                 )
             }
+            PresetHandlerError::TargetAlreadyExists(target) => {
+                write!(
+                    f,
+                    "{} already exists. Use \"merge\" or \"override\" to copy onto it",
+                    target
+                )
+            }
+            PresetHandlerError::TargetOutOfRange(target) => {
+                write!(
+                    f,
+                    "Objects copied to {} would exceed the highest possible id",
+                    target
+                )
+            }
+            PresetHandlerError::CantMergeEffectPreset(id) => {
+                write!(f, "Preset {} is an effect preset and can't be merged", id)
+            }
         }
     }
 }
@@ -23,6 +23,11 @@ impl FixtureGroup {
         self.id
     }
 
+    pub fn with_id(mut self, id: u32) -> Self {
+        self.id = id;
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
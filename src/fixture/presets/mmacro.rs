@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::parser::nodes::action::Action;
 
+use super::preset::FixturePresetId;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MMacro {
     id: u32,
@@ -33,4 +35,16 @@ impl MMacro {
     pub fn action(&self) -> &Action {
         &self.action
     }
+
+    pub fn map_group_ids(&mut self, map: &impl Fn(u32) -> u32) {
+        self.action.map_group_ids(map);
+    }
+
+    pub fn map_preset_ids(&mut self, map: &impl Fn(FixturePresetId) -> FixturePresetId) {
+        self.action.map_preset_ids(map);
+    }
+
+    pub fn map_sequence_ids(&mut self, map: &impl Fn(u32) -> u32) {
+        self.action.map_sequence_ids(map);
+    }
 }
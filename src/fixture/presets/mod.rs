@@ -562,21 +562,10 @@ impl PresetHandler {
             CueTrigger::Manual,
         );
 
-        let cues = self
-            .sequences
+        self.sequences
             .get_mut(&sequence_id)
             .ok_or(PresetHandlerError::PresetNotFound(sequence_id))?
-            .cues_mut();
-
-        for (idx, c) in cues.iter().enumerate() {
-            if c.cue_idx() > discrete_cue_idx {
-                cues.insert(idx, cue);
-                return Ok(());
-            }
-        }
-
-        // if we didn't insert the cue yet, it means it's the last cue
-        cues.push(cue);
+            .insert_cue(cue);
 
         Ok(())
     }
@@ -657,3 +646,600 @@ impl PresetHandler {
         Ok(initial_len - sequence.cues().len())
     }
 }
+
+/// Shifts a cue index, so that `from` lands on `target`. Point cues of the
+/// first whole cue are shifted along, those of the following cues are kept.
+fn shift_cue_idx(cue_idx: CueIdx, from: CueIdx, target: CueIdx) -> CueIdx {
+    if cue_idx.0 == from.0 {
+        (target.0, target.1 + cue_idx.1 - from.1)
+    } else {
+        (target.0 + cue_idx.0 - from.0, cue_idx.1)
+    }
+}
+
+/// Shifts an object id, so that `from` lands on `target`.
+fn shift_id(id: u32, from: u32, target: u32) -> Result<u32, PresetHandlerError> {
+    target
+        .checked_add(id - from)
+        .ok_or(PresetHandlerError::TargetOutOfRange(target))
+}
+
+// Copy and move
+// Objects are copied to consecutive ids starting at the target. Targets, that already
+// exist, are only touched with a `collision` mode: `Override` replaces them and
+// `Merge` adds the data of the source for fixtures, that aren't part of them yet.
+// Moving an object rewrites every reference to it.
+impl PresetHandler {
+    pub fn copy_presets(
+        &mut self,
+        from: FixturePresetId,
+        to: FixturePresetId,
+        target: FixturePresetId,
+        collision: Option<UpdateMode>,
+    ) -> Result<HashMap<FixturePresetId, FixturePresetId>, PresetHandlerError> {
+        self.transfer_presets(from, to, target, collision, false)
+    }
+
+    pub fn move_presets(
+        &mut self,
+        from: FixturePresetId,
+        to: FixturePresetId,
+        target: FixturePresetId,
+        collision: Option<UpdateMode>,
+    ) -> Result<HashMap<FixturePresetId, FixturePresetId>, PresetHandlerError> {
+        self.transfer_presets(from, to, target, collision, true)
+    }
+
+    fn transfer_presets(
+        &mut self,
+        from: FixturePresetId,
+        to: FixturePresetId,
+        target: FixturePresetId,
+        collision: Option<UpdateMode>,
+        remove_source: bool,
+    ) -> Result<HashMap<FixturePresetId, FixturePresetId>, PresetHandlerError> {
+        for other in [to, target] {
+            if from.feature_group != other.feature_group {
+                return Err(PresetHandlerError::FeatureGroupMismatch(
+                    from.feature_group,
+                    other.feature_group,
+                ));
+            }
+        }
+
+        let mapping = (from.preset_id..=to.preset_id)
+            .map(|preset_id| FixturePresetId {
+                feature_group: from.feature_group,
+                preset_id,
+            })
+            .filter(|id| self.presets.contains_key(id))
+            .map(|id| {
+                Ok((
+                    id,
+                    FixturePresetId {
+                        feature_group: target.feature_group,
+                        preset_id: shift_id(id.preset_id, from.preset_id, target.preset_id)?,
+                    },
+                ))
+            })
+            .collect::<Result<HashMap<_, _>, PresetHandlerError>>()?;
+
+        if mapping.is_empty() {
+            return Err(PresetHandlerError::FeaturePresetNotFound(from));
+        }
+
+        for (source_id, target_id) in &mapping {
+            // presets, that are moved away, don't collide
+            let Some(existing) = self
+                .presets
+                .get(target_id)
+                .filter(|_| !(remove_source && mapping.contains_key(target_id)))
+            else {
+                continue;
+            };
+
+            match collision {
+                None => {
+                    return Err(PresetHandlerError::TargetAlreadyExists(format!(
+                        "Preset {}",
+                        target_id
+                    )))
+                }
+                Some(UpdateMode::Merge) if !existing.can_merge(&self.presets[source_id]) => {
+                    return Err(PresetHandlerError::CantMergeEffectPreset(*target_id))
+                }
+                _ => {}
+            }
+        }
+
+        let sources = mapping
+            .keys()
+            .filter_map(|id| {
+                if remove_source {
+                    self.presets.remove(id)
+                } else {
+                    self.presets.get(id).cloned()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for source in sources {
+            let target_id = mapping[&source.id()];
+
+            match self.presets.get_mut(&target_id) {
+                Some(existing) if collision == Some(UpdateMode::Merge) => {
+                    existing.merge(source)?;
+                }
+                _ => {
+                    self.presets.insert(target_id, source.with_id(target_id));
+                }
+            }
+        }
+
+        if remove_source {
+            let map = |id: FixturePresetId| mapping.get(&id).copied().unwrap_or(id);
+
+            for preset in self.presets.values_mut() {
+                preset.map_preset_ids(&map);
+            }
+
+            for cue in self
+                .sequences
+                .values_mut()
+                .flat_map(|sequence| sequence.cues_mut())
+            {
+                cue.map_preset_ids(&map);
+            }
+
+            for mmacro in self.macros.values_mut() {
+                mmacro.map_preset_ids(&map);
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    pub fn copy_groups(
+        &mut self,
+        from: u32,
+        to: u32,
+        target: u32,
+        collision: Option<UpdateMode>,
+    ) -> Result<HashMap<u32, u32>, PresetHandlerError> {
+        self.transfer_groups(from, to, target, collision, false)
+    }
+
+    pub fn move_groups(
+        &mut self,
+        from: u32,
+        to: u32,
+        target: u32,
+        collision: Option<UpdateMode>,
+    ) -> Result<HashMap<u32, u32>, PresetHandlerError> {
+        self.transfer_groups(from, to, target, collision, true)
+    }
+
+    fn transfer_groups(
+        &mut self,
+        from: u32,
+        to: u32,
+        target: u32,
+        collision: Option<UpdateMode>,
+        remove_source: bool,
+    ) -> Result<HashMap<u32, u32>, PresetHandlerError> {
+        let mapping = (from..=to)
+            .filter(|id| self.groups.contains_key(id))
+            .map(|id| Ok((id, shift_id(id, from, target)?)))
+            .collect::<Result<HashMap<_, _>, PresetHandlerError>>()?;
+
+        if mapping.is_empty() {
+            return Err(PresetHandlerError::PresetNotFound(from));
+        }
+
+        if collision.is_none() {
+            if let Some(target_id) = mapping.values().find(|target_id| {
+                self.groups.contains_key(target_id)
+                    && !(remove_source && mapping.contains_key(target_id))
+            }) {
+                return Err(PresetHandlerError::TargetAlreadyExists(format!(
+                    "Group {}",
+                    target_id
+                )));
+            }
+        }
+
+        let sources = mapping
+            .keys()
+            .filter_map(|id| {
+                if remove_source {
+                    self.groups.remove(id)
+                } else {
+                    self.groups.get(id).cloned()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for source in sources {
+            let target_id = mapping[&source.id()];
+
+            match self.groups.get_mut(&target_id) {
+                Some(existing) if collision == Some(UpdateMode::Merge) => existing
+                    .fixture_selection_mut()
+                    .extend_from(source.fixture_selection()),
+                _ => {
+                    self.groups.insert(target_id, source.with_id(target_id));
+                }
+            }
+        }
+
+        if remove_source {
+            let map = |id: u32| mapping.get(&id).copied().unwrap_or(id);
+
+            for cue in self
+                .sequences
+                .values_mut()
+                .flat_map(|sequence| sequence.cues_mut())
+            {
+                cue.map_group_ids(&map);
+            }
+
+            for mmacro in self.macros.values_mut() {
+                mmacro.map_group_ids(&map);
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    pub fn copy_sequences(
+        &mut self,
+        from: u32,
+        to: u32,
+        target: u32,
+        collision: Option<UpdateMode>,
+    ) -> Result<HashMap<u32, u32>, PresetHandlerError> {
+        self.transfer_sequences(from, to, target, collision, false)
+    }
+
+    /// Executors of moved sequences have to be pointed to the new ids by the caller.
+    pub fn move_sequences(
+        &mut self,
+        from: u32,
+        to: u32,
+        target: u32,
+        collision: Option<UpdateMode>,
+    ) -> Result<HashMap<u32, u32>, PresetHandlerError> {
+        self.transfer_sequences(from, to, target, collision, true)
+    }
+
+    fn transfer_sequences(
+        &mut self,
+        from: u32,
+        to: u32,
+        target: u32,
+        collision: Option<UpdateMode>,
+        remove_source: bool,
+    ) -> Result<HashMap<u32, u32>, PresetHandlerError> {
+        let mapping = (from..=to)
+            .filter(|id| self.sequences.contains_key(id))
+            .map(|id| Ok((id, shift_id(id, from, target)?)))
+            .collect::<Result<HashMap<_, _>, PresetHandlerError>>()?;
+
+        if mapping.is_empty() {
+            return Err(PresetHandlerError::PresetNotFound(from));
+        }
+
+        for (source_id, target_id) in &mapping {
+            let Some(existing) = self
+                .sequences
+                .get(target_id)
+                .filter(|_| !(remove_source && mapping.contains_key(target_id)))
+            else {
+                continue;
+            };
+
+            match collision {
+                None => {
+                    return Err(PresetHandlerError::TargetAlreadyExists(format!(
+                        "Sequence {}",
+                        target_id
+                    )))
+                }
+                Some(UpdateMode::Merge) => {
+                    // sequences are merged cue by cue
+                    for cue in self.sequences[source_id].cues() {
+                        if existing
+                            .find_cue(cue.cue_idx())
+                            .is_some_and(|existing_cue| !existing_cue.can_merge(cue))
+                        {
+                            return Err(PresetHandlerError::CantUpdateNonDefaultCue(
+                                *target_id,
+                                cue.cue_idx(),
+                            ));
+                        }
+                    }
+                }
+                Some(UpdateMode::Override) => {}
+            }
+        }
+
+        let sources = mapping
+            .keys()
+            .filter_map(|id| {
+                if remove_source {
+                    self.sequences.remove(id)
+                } else {
+                    self.sequences.get(id).cloned()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for source in sources {
+            let target_id = mapping[&source.id()];
+
+            match self.sequences.get_mut(&target_id) {
+                Some(existing) if collision == Some(UpdateMode::Merge) => {
+                    for cue in source.cues().iter().cloned() {
+                        match existing.find_cue_mut(cue.cue_idx()) {
+                            Some(existing_cue) => {
+                                existing_cue.merge(target_id, cue)?;
+                            }
+                            None => existing.insert_cue(cue),
+                        }
+                    }
+                }
+                _ => {
+                    self.sequences.insert(target_id, source.with_id(target_id));
+                }
+            }
+        }
+
+        if remove_source {
+            let map = |id: u32| mapping.get(&id).copied().unwrap_or(id);
+
+            for mmacro in self.macros.values_mut() {
+                mmacro.map_sequence_ids(&map);
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    pub fn copy_sequence_cues(
+        &mut self,
+        sequence_id: u32,
+        from: CueIdx,
+        to: CueIdx,
+        target_sequence_id: u32,
+        target: CueIdx,
+        collision: Option<UpdateMode>,
+    ) -> Result<HashMap<CueIdx, CueIdx>, PresetHandlerError> {
+        self.transfer_sequence_cues(
+            sequence_id,
+            from,
+            to,
+            target_sequence_id,
+            target,
+            collision,
+            false,
+        )
+    }
+
+    pub fn move_sequence_cues(
+        &mut self,
+        sequence_id: u32,
+        from: CueIdx,
+        to: CueIdx,
+        target_sequence_id: u32,
+        target: CueIdx,
+        collision: Option<UpdateMode>,
+    ) -> Result<HashMap<CueIdx, CueIdx>, PresetHandlerError> {
+        self.transfer_sequence_cues(
+            sequence_id,
+            from,
+            to,
+            target_sequence_id,
+            target,
+            collision,
+            true,
+        )
+    }
+
+    fn transfer_sequence_cues(
+        &mut self,
+        sequence_id: u32,
+        from: CueIdx,
+        to: CueIdx,
+        target_sequence_id: u32,
+        target: CueIdx,
+        collision: Option<UpdateMode>,
+        remove_source: bool,
+    ) -> Result<HashMap<CueIdx, CueIdx>, PresetHandlerError> {
+        if from > to {
+            return Err(PresetHandlerError::InvalidCueRange(from, to));
+        }
+
+        let source_sequence = self.get_sequence(sequence_id)?;
+        let target_sequence = self.get_sequence(target_sequence_id)?;
+
+        let mapping = source_sequence
+            .cues()
+            .iter()
+            .map(|cue| cue.cue_idx())
+            .filter(|cue_idx| (from..=to).contains(cue_idx))
+            .map(|cue_idx| (cue_idx, shift_cue_idx(cue_idx, from, target)))
+            .collect::<HashMap<_, _>>();
+
+        if mapping.is_empty() {
+            return Err(PresetHandlerError::CueNotFound(sequence_id, from));
+        }
+
+        for (source_idx, target_idx) in &mapping {
+            // cues, that are moved away, don't collide
+            let Some(existing) = target_sequence.find_cue(*target_idx).filter(|_| {
+                !(remove_source
+                    && sequence_id == target_sequence_id
+                    && mapping.contains_key(target_idx))
+            }) else {
+                continue;
+            };
+
+            match collision {
+                None => {
+                    return Err(PresetHandlerError::TargetAlreadyExists(format!(
+                        "Cue {}.{} in sequence {}",
+                        target_idx.0, target_idx.1, target_sequence_id
+                    )))
+                }
+                Some(UpdateMode::Merge)
+                    if source_sequence
+                        .find_cue(*source_idx)
+                        .is_some_and(|cue| !existing.can_merge(cue)) =>
+                {
+                    return Err(PresetHandlerError::CantUpdateNonDefaultCue(
+                        target_sequence_id,
+                        *target_idx,
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        let sources = if remove_source {
+            let cues = self.get_sequence_mut(sequence_id)?.cues_mut();
+            let (moved, kept): (Vec<Cue>, Vec<Cue>) = std::mem::take(cues)
+                .into_iter()
+                .partition(|cue| mapping.contains_key(&cue.cue_idx()));
+
+            *cues = kept;
+            moved
+        } else {
+            source_sequence
+                .cues()
+                .iter()
+                .filter(|cue| mapping.contains_key(&cue.cue_idx()))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let target_sequence = self.get_sequence_mut(target_sequence_id)?;
+
+        for cue in sources {
+            let target_idx = mapping[&cue.cue_idx()];
+
+            match target_sequence.find_cue_mut(target_idx) {
+                Some(existing) if collision == Some(UpdateMode::Merge) => {
+                    existing.merge(target_sequence_id, cue)?;
+                }
+                Some(existing) => *existing = cue.with_cue_idx(target_idx),
+                None => target_sequence.insert_cue(cue.with_cue_idx(target_idx)),
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    /// Renumbers the cues between `from` and `to` to whole cues, starting
+    /// at the whole cue of `from` and counting up by `step`.
+    /// Returns the new index of every renumbered cue.
+    pub fn renumber_sequence_cues(
+        &mut self,
+        sequence_id: u32,
+        from: CueIdx,
+        to: CueIdx,
+        step: u32,
+    ) -> Result<HashMap<CueIdx, CueIdx>, PresetHandlerError> {
+        if from > to {
+            return Err(PresetHandlerError::InvalidCueRange(from, to));
+        }
+
+        let sequence = self.get_sequence_mut(sequence_id)?;
+
+        let mapping = sequence
+            .cues()
+            .iter()
+            .map(|cue| cue.cue_idx())
+            .filter(|cue_idx| (from..=to).contains(cue_idx))
+            .enumerate()
+            .map(|(n, cue_idx)| (cue_idx, (from.0 + n as u32 * step, 0)))
+            .collect::<HashMap<_, _>>();
+
+        // cues outside of the range keep their index
+        if let Some(target_idx) = mapping.values().find(|target_idx| {
+            !mapping.contains_key(target_idx) && sequence.find_cue(**target_idx).is_some()
+        }) {
+            return Err(PresetHandlerError::CueAlreadyExists(
+                sequence_id,
+                *target_idx,
+            ));
+        }
+
+        for cue in std::mem::take(sequence.cues_mut()) {
+            let cue_idx = mapping
+                .get(&cue.cue_idx())
+                .copied()
+                .unwrap_or(cue.cue_idx());
+
+            sequence.insert_cue(cue.with_cue_idx(cue_idx));
+        }
+
+        Ok(mapping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixture::sequence::cue::{Cue, CueIdx},
+        parser::nodes::action::functions::update_function::UpdateMode,
+    };
+
+    use super::PresetHandler;
+
+    fn cue_idxs(preset_handler: &PresetHandler, sequence_id: u32) -> Vec<CueIdx> {
+        preset_handler
+            .get_sequence(sequence_id)
+            .unwrap()
+            .cues()
+            .iter()
+            .map(|cue| cue.cue_idx())
+            .collect()
+    }
+
+    #[test]
+    fn test_copy_sequence_cues() {
+        let mut preset_handler = PresetHandler::default();
+        preset_handler.create_sequence(1, None).unwrap();
+        preset_handler.create_sequence(2, None).unwrap();
+
+        for cue_idx in [(1, 0), (2, 0), (2, 5), (3, 0)] {
+            preset_handler
+                .get_sequence_mut(1)
+                .unwrap()
+                .add_cue(Cue::new_default_builder(cue_idx));
+        }
+
+        preset_handler
+            .copy_sequence_cues(1, (2, 0), (3, 0), 2, (1, 0), None)
+            .unwrap();
+        assert_eq!(cue_idxs(&preset_handler, 2), vec![(1, 0), (1, 5), (2, 0)]);
+
+        // existing cues are only replaced with a collision mode and builder cues can't be merged
+        assert!(preset_handler
+            .copy_sequence_cues(1, (1, 0), (1, 0), 2, (1, 0), None)
+            .is_err());
+        assert!(preset_handler
+            .copy_sequence_cues(1, (1, 0), (1, 0), 2, (1, 0), Some(UpdateMode::Merge))
+            .is_err());
+
+        preset_handler
+            .move_sequence_cues(1, (1, 0), (1, 0), 2, (1, 0), Some(UpdateMode::Override))
+            .unwrap();
+        assert_eq!(cue_idxs(&preset_handler, 1), vec![(2, 0), (2, 5), (3, 0)]);
+
+        preset_handler
+            .renumber_sequence_cues(1, (1, 0), (10, 0), 1)
+            .unwrap();
+        assert_eq!(cue_idxs(&preset_handler, 1), vec![(1, 0), (2, 0), (3, 0)]);
+    }
+}
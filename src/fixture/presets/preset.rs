@@ -21,6 +21,7 @@ use crate::{
         action::{functions::update_function::UpdateMode, ValueOrRange},
         fixture_selector::{FixtureSelector, FixtureSelectorContext},
     },
+    utils::clock,
 };
 
 use super::{error::PresetHandlerError, PresetHandler};
@@ -245,6 +246,11 @@ impl FixturePreset {
         })
     }
 
+    pub fn with_id(mut self, id: FixturePresetId) -> Self {
+        self.id = id;
+        self
+    }
+
     pub fn data(&self) -> &FixturePresetData {
         &self.data
     }
@@ -253,52 +259,62 @@ impl FixturePreset {
         &mut self.data
     }
 
+    /// Returns the values, that applying this preset to the fixture
+    /// would set, keyed by the channel name.
+    pub fn channel_values(
+        &self,
+        fixture_types: &FixtureTypeList,
+        fixture: &GdtfFixture,
+        new_selection: &FixtureSelection,
+    ) -> Vec<(String, FixtureChannelValue3)> {
+        let preset_value = FixtureChannelValue3::Preset {
+            id: self.id,
+            state: Some(FixtureChannelValue2PresetState::now(new_selection.clone())),
+        };
+
+        let channel_values: Vec<(String, FixtureChannelValue3)> = match &self.data {
+            FixturePresetData::Default { data } => data
+                .get(&fixture.id())
+                .map(|fixture_data| {
+                    fixture_data
+                        .keys()
+                        .map(|channel| (channel.clone(), preset_value.clone()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            FixturePresetData::FeatureEffect { runtime } => runtime
+                .effect()
+                .attributes()
+                // if the fixture doesn't have this feature type, skip
+                .filter_map(|attribute| {
+                    fixture
+                        .channels_for_attribute(fixture_types, attribute)
+                        .ok()
+                })
+                .flatten()
+                .map(|(dmx_channel, _, _)| {
+                    (dmx_channel.name().as_ref().to_owned(), preset_value.clone())
+                })
+                .collect(),
+        };
+
+        // if only some cells of the fixture are selected, only their channels are affected
+        channel_values
+            .into_iter()
+            .filter(|(channel, _)| fixture.is_channel_selected(channel, new_selection))
+            .collect()
+    }
+
     pub fn apply(
         &self,
         fixture_types: &FixtureTypeList,
         fixture: &mut GdtfFixture,
         new_selection: FixtureSelection,
     ) -> Result<(), PresetHandlerError> {
-        match &self.data {
-            FixturePresetData::Default { data } => {
-                if let Some(fixture_data) = data.get(&fixture.id()) {
-                    for (preset_chanel_type, _) in fixture_data.iter() {
-                        fixture
-                            .set_programmer_value(
-                                fixture_types,
-                                preset_chanel_type.as_str(),
-                                FixtureChannelValue3::Preset {
-                                    id: self.id,
-                                    state: Some(FixtureChannelValue2PresetState::now(
-                                        new_selection.clone(),
-                                    )),
-                                },
-                            )
-                            .map_err(PresetHandlerError::FixtureError)?;
-                    }
-                }
-            }
-            FixturePresetData::FeatureEffect { runtime } => {
-                for attribute in runtime.effect().attributes() {
-                    // if the fixture doesn't have this feature type, skip
-                    if let Ok(channels) = fixture.channels_for_attribute(fixture_types, attribute) {
-                        for (dmx_channel, _, _) in channels {
-                            fixture
-                                .set_programmer_value(
-                                    fixture_types,
-                                    dmx_channel.name().as_ref(),
-                                    FixtureChannelValue3::Preset {
-                                        id: self.id,
-                                        state: Some(FixtureChannelValue2PresetState::now(
-                                            new_selection.clone(),
-                                        )),
-                                    },
-                                )
-                                .map_err(PresetHandlerError::FixtureError)?;
-                        }
-                    }
-                }
-            }
+        for (channel, value) in self.channel_values(fixture_types, fixture, &new_selection) {
+            fixture
+                .set_programmer_value(fixture_types, channel.as_str(), value)
+                .map_err(PresetHandlerError::FixtureError)?;
         }
 
         Ok(())
@@ -362,22 +378,34 @@ impl FixturePreset {
                         .collect::<Vec<_>>()
                 })
                 .unwrap_or_default(),
-            FixturePresetData::FeatureEffect { runtime } => {
-                let fixture_offset = state
-                    .and_then(|state| state.selection().offset(fixture.id()))
-                    .unwrap_or_default();
-
-                runtime.get_values_with_started(
-                    fixture,
-                    fixture_types,
-                    fixture_offset,
-                    timing_handler,
-                    state.map(|state| state.started()),
-                )
-            }
+            FixturePresetData::FeatureEffect { runtime } => runtime.get_values_with_started(
+                fixture,
+                fixture_types,
+                |channel_name| Self::channel_offset(fixture, channel_name, state),
+                timing_handler,
+                state.map(|state| state.started()),
+            ),
         }
     }
 
+    /// The offset of the channel in the selection, the preset was applied to.
+    /// Channels of cells get the offset of their cell.
+    fn channel_offset(
+        fixture: &GdtfFixture,
+        channel_name: &str,
+        state: Option<&FixtureChannelValue2PresetState>,
+    ) -> f32 {
+        state
+            .and_then(|state| {
+                state.selection().cell_offset(
+                    fixture.id(),
+                    fixture.channel_cell(channel_name),
+                    fixture.num_cells(),
+                )
+            })
+            .unwrap_or_default()
+    }
+
     pub fn value(
         &self,
         fixture: &GdtfFixture,
@@ -388,7 +416,7 @@ impl FixturePreset {
         state: Option<&FixtureChannelValue2PresetState>,
     ) -> Option<FixtureChannelValue3> {
         let started_delta = state
-            .map(|state| state.started().elapsed().as_secs_f32())
+            .map(|state| clock::elapsed(state.started()).as_secs_f32())
             .unwrap_or(0.0);
 
         let _fade = if self.fade_up > 0.0 {
@@ -402,9 +430,7 @@ impl FixturePreset {
                 .get(&fixture.id())
                 .and_then(|values| values.get(channel_name).cloned()),
             FixturePresetData::FeatureEffect { runtime } => {
-                let fixture_offset = state
-                    .and_then(|state| state.selection().offset(fixture.id()))
-                    .unwrap_or_default();
+                let fixture_offset = Self::channel_offset(fixture, channel_name, state);
 
                 runtime
                     .get_channel_value_with_started(
@@ -423,6 +449,35 @@ impl FixturePreset {
         val
     }
 
+    /// Effect presets can't be merged, only replaced.
+    pub fn can_merge(&self, other: &FixturePreset) -> bool {
+        matches!(
+            (&self.data, &other.data),
+            (
+                FixturePresetData::Default { .. },
+                FixturePresetData::Default { .. }
+            )
+        )
+    }
+
+    /// Adds the values of `other` for fixtures, that aren't part of this preset yet.
+    pub fn merge(&mut self, other: FixturePreset) -> Result<usize, PresetHandlerError> {
+        match (&self.data, other.data) {
+            (FixturePresetData::Default { .. }, FixturePresetData::Default { data }) => {
+                self.update(data, UpdateMode::Merge)
+            }
+            _ => Err(PresetHandlerError::CantMergeEffectPreset(self.id)),
+        }
+    }
+
+    pub fn map_preset_ids(&mut self, map: &impl Fn(FixturePresetId) -> FixturePresetId) {
+        if let FixturePresetData::Default { data } = &mut self.data {
+            for value in data.values_mut().flat_map(|values| values.values_mut()) {
+                value.map_preset_ids(map);
+            }
+        }
+    }
+
     pub fn update(
         &mut self,
         values_to_update: HashMap<u32, HashMap<String, FixtureChannelValue3>>,
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::parser::nodes::fixture_selector::{FixtureSelector, FixtureSelectorContext};
@@ -18,6 +20,12 @@ pub struct FixtureSelection {
 
     #[serde(default)]
     reverse: bool,
+
+    /// Selected cells of fixtures, that aren't selected as a whole.
+    /// Every selected cell gets its own offset.
+    #[serde(default)]
+    #[cfg_attr(feature = "ui", egui_probe(skip))]
+    cells: HashMap<u32, Vec<u16>>,
 }
 
 impl Default for FixtureSelection {
@@ -29,6 +37,7 @@ impl Default for FixtureSelection {
             block: 1,
             wings: 1,
             reverse: false,
+            cells: HashMap::new(),
         }
     }
 }
@@ -42,23 +51,56 @@ impl FixtureSelection {
         self.fixtures.iter().any(|id| other.has_fixture(*id))
     }
 
+    /// Selects the cells of the fixture. Without any cells, nothing is selected.
+    pub fn from_cells(fixture_id: u32, cells: Vec<u16>) -> Self {
+        if cells.is_empty() {
+            return Self::default();
+        }
+
+        let mut selection = Self::from(vec![fixture_id]);
+        selection.cells.insert(fixture_id, cells);
+
+        selection
+    }
+
+    /// Adds the fixture (or its cells) of `other` to this selection.
+    fn merge_fixture(&mut self, other: &FixtureSelection, fixture_id: u32) {
+        if !self.fixtures.contains(&fixture_id) {
+            self.fixtures.push(fixture_id);
+
+            if let Some(cells) = other.cells(fixture_id) {
+                self.cells.insert(fixture_id, cells.to_vec());
+            }
+
+            return;
+        }
+
+        match (self.cells.get_mut(&fixture_id), other.cells(fixture_id)) {
+            (Some(cells), Some(other_cells)) => {
+                for cell in other_cells {
+                    if !cells.contains(cell) {
+                        cells.push(*cell);
+                    }
+                }
+            }
+            // the whole fixture is selected
+            (Some(_), None) => {
+                self.cells.remove(&fixture_id);
+            }
+            (None, _) => {}
+        }
+    }
+
     pub fn extend_from(&mut self, other: &FixtureSelection) {
         for fixture in other.fixtures() {
-            if self.fixtures.contains(fixture) {
-                continue;
-            }
-            self.fixtures.push(*fixture);
+            self.merge_fixture(other, *fixture);
         }
     }
 
     pub fn update_from(&mut self, other: &FixtureSelection) {
         // merge fixture list
         for fixture in other.fixtures() {
-            if self.fixtures.contains(fixture) {
-                continue;
-            }
-
-            self.fixtures.push(*fixture);
+            self.merge_fixture(other, *fixture);
         }
 
         // update group, block and wings
@@ -67,8 +109,32 @@ impl FixtureSelection {
         self.wings = other.wings;
     }
 
+    /// Cells can only be subtracted from fixtures, of which only
+    /// some cells are selected.
     pub fn subtract(&mut self, other: &FixtureSelection) {
-        self.fixtures.retain(|f| !other.has_fixture(*f));
+        for fixture_id in &self.fixtures {
+            if let (Some(cells), Some(other_cells)) =
+                (self.cells.get_mut(fixture_id), other.cells(*fixture_id))
+            {
+                cells.retain(|cell| !other_cells.contains(cell));
+            }
+        }
+
+        let cells = &self.cells;
+        self.fixtures.retain(|f| {
+            if !other.has_fixture(*f) {
+                return true;
+            }
+
+            match (cells.get(f), other.cells(*f)) {
+                (Some(cells), Some(_)) => !cells.is_empty(),
+                (None, Some(_)) => true,
+                (_, None) => false,
+            }
+        });
+
+        let fixtures = &self.fixtures;
+        self.cells.retain(|f, _| fixtures.contains(f));
     }
 
     pub fn equals_selector(
@@ -96,6 +162,11 @@ impl FixtureSelection {
         &self.fixtures
     }
 
+    /// The selected cells of the fixture or `None`, if the whole fixture is selected.
+    pub fn cells(&self, fixture_id: u32) -> Option<&[u16]> {
+        self.cells.get(&fixture_id).map(|cells| cells.as_slice())
+    }
+
     pub fn group(&self) -> usize {
         self.group.max(1)
     }
@@ -141,8 +212,47 @@ impl FixtureSelection {
     }
 
     pub fn offset_idx(&self, fixture_id: u32) -> Option<usize> {
-        let fixture_position = self.fixtures.iter().position(|&id| id == fixture_id)?;
+        self.cell_offset_idx(fixture_id, None)
+    }
+
+    /// The offset of a cell of the fixture. If the whole fixture is selected, its
+    /// `num_cells` cells are spread evenly over the offset of the fixture. Channels,
+    /// that don't belong to a cell, get the offset of the fixture (or its first selected cell).
+    pub fn cell_offset(&self, fixture_id: u32, cell: Option<u16>, num_cells: u16) -> Option<f32> {
+        let offset_idx = self.cell_offset_idx(fixture_id, cell)? as f32;
+
+        let cell_offset = match cell {
+            Some(cell) if self.cells(fixture_id).is_none() && num_cells > 1 => {
+                (cell - 1) as f32 / num_cells as f32
+            }
+            _ => 0.0,
+        };
+
+        Some((offset_idx + cell_offset) / self.num_offsets() as f32)
+    }
 
+    pub fn cell_offset_idx(&self, fixture_id: u32, cell: Option<u16>) -> Option<usize> {
+        let mut fixture_position = 0;
+
+        for id in &self.fixtures {
+            let cells = self.cells.get(id);
+
+            if *id == fixture_id {
+                fixture_position += cells
+                    .zip(cell)
+                    .and_then(|(cells, cell)| cells.iter().position(|c| *c == cell))
+                    .unwrap_or(0);
+
+                return Some(self.element_offset_idx(fixture_position));
+            }
+
+            fixture_position += cells.map(|cells| cells.len()).unwrap_or(1);
+        }
+
+        None
+    }
+
+    fn element_offset_idx(&self, fixture_position: usize) -> usize {
         let blocked_offset = fixture_position / self.block();
 
         // let grouped_offset = blocked_offset % (self.num_blocks() / self.group());
@@ -160,15 +270,23 @@ impl FixtureSelection {
             wing_offset = wing_size - wing_offset - 1;
         }
 
-        Some(if self.reverse {
+        if self.reverse {
             self.num_offsets() - 1 - wing_offset
         } else {
             wing_offset
-        })
+        }
+    }
+
+    /// Every selected cell counts as a single element.
+    fn num_elements(&self) -> usize {
+        self.fixtures
+            .iter()
+            .map(|id| self.cells.get(id).map(|cells| cells.len()).unwrap_or(1))
+            .sum()
     }
 
     fn num_blocked_offsets(&self) -> usize {
-        self.fixtures.len().div_ceil(self.block())
+        self.num_elements().div_ceil(self.block())
     }
 
     fn num_grouped_offsets(&self) -> usize {
@@ -224,6 +342,7 @@ mod tests {
             block: 1,
             wings: 1,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
@@ -238,6 +357,7 @@ mod tests {
             block: 1,
             wings: 1,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
@@ -252,6 +372,7 @@ mod tests {
             block: 2,
             wings: 1,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 0, 1, 1, 2, 2, 3, 3, 4, 4]);
@@ -266,6 +387,7 @@ mod tests {
             block: 2,
             wings: 1,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5]);
@@ -280,6 +402,7 @@ mod tests {
             block: 1,
             wings: 1,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 0, 1, 0, 1, 0, 1, 0, 1]);
@@ -294,6 +417,7 @@ mod tests {
             block: 1,
             wings: 1,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0]);
@@ -308,6 +432,7 @@ mod tests {
             block: 1,
             wings: 1,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 2, 0, 1, 2, 0, 1, 2, 0]);
@@ -322,6 +447,7 @@ mod tests {
             block: 1,
             wings: 1,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1]);
@@ -336,6 +462,7 @@ mod tests {
             block: 1,
             wings: 2,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 2, 3, 4, 4, 3, 2, 1, 0]);
@@ -350,6 +477,7 @@ mod tests {
             block: 1,
             wings: 2,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 2, 3, 4, 4, 3, 2, 1, 0, 0]);
@@ -364,6 +492,7 @@ mod tests {
             block: 1,
             wings: 3,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 2, 2, 1, 0, 0, 1, 2, 2]);
@@ -378,6 +507,7 @@ mod tests {
             block: 1,
             wings: 3,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 2, 2, 1, 0, 0, 1, 2, 2, 1]);
@@ -392,6 +522,7 @@ mod tests {
             block: 1,
             wings: 4,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 1, 0, 0, 1, 1, 0, 0, 1]);
@@ -406,9 +537,46 @@ mod tests {
             block: 1,
             wings: 4,
             reverse: false,
+            ..Default::default()
         };
 
         assert_offsets_equal(&selection, &[0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1]);
         assert_eq!(selection.num_offsets(), 2);
     }
+
+    #[test]
+    fn test_cells() {
+        let mut selection = FixtureSelection::from(vec![1]);
+        selection.extend_from(&FixtureSelection::from_cells(2, vec![1, 2, 3]));
+        selection.extend_from(&FixtureSelection::from(vec![3]));
+
+        assert_eq!(selection.num_offsets(), 5);
+        assert_eq!(selection.cell_offset_idx(2, Some(3)), Some(3));
+        assert_eq!(selection.cell_offset_idx(2, None), Some(1));
+        assert_eq!(selection.cell_offset_idx(3, Some(1)), Some(4));
+
+        selection.subtract(&FixtureSelection::from_cells(2, vec![1, 3]));
+        assert_eq!(selection.cells(2), Some([2].as_slice()));
+        assert_eq!(selection.num_offsets(), 3);
+
+        selection.extend_from(&FixtureSelection::from(vec![2]));
+        assert_eq!(selection.cells(2), None);
+
+        // an empty range of cells doesn't select the whole fixture
+        assert!(!FixtureSelection::from_cells(4, vec![]).has_fixture(4));
+    }
+
+    #[test]
+    fn test_whole_fixture_cell_offsets() {
+        let selection = FixtureSelection::from(vec![1, 2]);
+
+        assert_eq!(selection.cell_offset(1, Some(1), 4), Some(0.0));
+        assert_eq!(selection.cell_offset(1, Some(3), 4), Some(0.25));
+        assert_eq!(selection.cell_offset(2, Some(2), 4), Some(0.625));
+        assert_eq!(selection.cell_offset(2, None, 4), Some(0.5));
+
+        // cells, that are selected on their own, already have their own offset
+        let selection = FixtureSelection::from_cells(1, vec![1, 2]);
+        assert_eq!(selection.cell_offset(1, Some(2), 4), Some(0.5));
+    }
 }
@@ -7,7 +7,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     fixture::{
-        channel3::channel_value::{FixtureChannelValue2PresetState, FixtureChannelValue3},
+        channel3::{
+            channel_value::{FixtureChannelValue2PresetState, FixtureChannelValue3},
+            feature::feature_group::FixtureChannel3FeatureGroup,
+        },
         gdtf::GdtfFixture,
         handler::{FixtureHandler, FixtureTypeList},
         presets::{error::PresetHandlerError, preset::FixturePresetId, PresetHandler},
@@ -154,6 +157,15 @@ impl CueTiming {
     }
 }
 
+/// Fade and delay for all channels of a feature group, overriding the in timing of the cue.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct CueFeatureGroupTiming {
+    pub feature_group: FixtureChannel3FeatureGroup,
+    pub fade: f32,
+    pub delay: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
 pub struct CueBuilderEntry {
@@ -201,6 +213,23 @@ pub struct Cue {
     // Delay, before the cue starts fading in
     in_delay: f32,
 
+    // Time, to fade intensities, that are going down. If not set, the in fade is used.
+    #[serde(default)]
+    out_fade: Option<f32>,
+
+    // Delay, before intensities, that are going down, start fading. If not set, the in delay is used.
+    #[serde(default)]
+    out_delay: Option<f32>,
+
+    // Timing overrides for the channels of specific feature groups
+    #[serde(default)]
+    feature_group_timings: Vec<CueFeatureGroupTiming>,
+
+    // Time, to fade back into the previous cue, when stepping back
+    // from this cue. If not set, the in fade is used.
+    #[serde(default)]
+    back_fade: Option<f32>,
+
     // When (as a percentage of the in_fade time), snapping of values, that are not
     // being faded, are changed.
     snap_percent: f32,
@@ -261,6 +290,10 @@ impl Cue {
 
             in_fade: 0.0,
             in_delay: 0.0,
+            out_fade: None,
+            out_delay: None,
+            feature_group_timings: Vec::new(),
+            back_fade: None,
             snap_percent: 0.0,
             block: false,
             timing: CueTiming::default(),
@@ -289,6 +322,10 @@ impl Cue {
 
             in_fade,
             in_delay,
+            out_fade: None,
+            out_delay: None,
+            feature_group_timings: Vec::new(),
+            back_fade: None,
             snap_percent,
             block: false,
             timing,
@@ -302,6 +339,11 @@ impl Cue {
         self.cue_idx
     }
 
+    pub fn with_cue_idx(mut self, cue_idx: CueIdx) -> Self {
+        self.cue_idx = cue_idx;
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -334,6 +376,59 @@ impl Cue {
         &mut self.in_delay
     }
 
+    pub fn out_fade(&self) -> f32 {
+        self.out_fade.unwrap_or(self.in_fade)
+    }
+
+    pub fn out_fade_mut(&mut self) -> &mut Option<f32> {
+        &mut self.out_fade
+    }
+
+    pub fn out_delay(&self) -> f32 {
+        self.out_delay.unwrap_or(self.in_delay)
+    }
+
+    pub fn out_delay_mut(&mut self) -> &mut Option<f32> {
+        &mut self.out_delay
+    }
+
+    pub fn has_out_timing(&self) -> bool {
+        self.out_fade.is_some() || self.out_delay.is_some()
+    }
+
+    pub fn feature_group_timings(&self) -> &[CueFeatureGroupTiming] {
+        &self.feature_group_timings
+    }
+
+    /// Returns the (fade, delay) for a channel of the given feature group.
+    /// Intensities, that are going down, use the out timing of the cue.
+    pub fn channel_fade_and_delay(
+        &self,
+        feature_group: Option<FixtureChannel3FeatureGroup>,
+        is_out: bool,
+    ) -> (f32, f32) {
+        if is_out {
+            return (self.out_fade(), self.out_delay());
+        }
+
+        feature_group
+            .and_then(|feature_group| {
+                self.feature_group_timings
+                    .iter()
+                    .find(|timing| timing.feature_group == feature_group)
+            })
+            .map(|timing| (timing.fade, timing.delay))
+            .unwrap_or((self.in_fade, self.in_delay))
+    }
+
+    pub fn back_fade(&self) -> f32 {
+        self.back_fade.unwrap_or(self.in_fade)
+    }
+
+    pub fn back_fade_mut(&mut self) -> &mut Option<f32> {
+        &mut self.back_fade
+    }
+
     pub fn snap_percent(&self) -> f32 {
         self.snap_percent
     }
@@ -562,6 +657,56 @@ impl Cue {
         }
     }
 
+    /// Builder cues can't be merged, only replaced.
+    pub fn can_merge(&self, other: &Cue) -> bool {
+        matches!(
+            (&self.data, &other.data),
+            (CueDataMode::Default(_), CueDataMode::Default(_))
+        )
+    }
+
+    /// Adds the values of `other` for fixtures, that aren't part of this cue yet.
+    pub fn merge(&mut self, sequence_id: u32, other: Cue) -> Result<usize, PresetHandlerError> {
+        match other.data {
+            CueDataMode::Default(data) => {
+                self.update(sequence_id, data, &other.selection, UpdateMode::Merge)
+            }
+            CueDataMode::Builder(_) => Err(PresetHandlerError::CantUpdateNonDefaultCue(
+                sequence_id,
+                other.cue_idx,
+            )),
+        }
+    }
+
+    pub fn map_preset_ids(&mut self, map: &impl Fn(FixturePresetId) -> FixturePresetId) {
+        match &mut self.data {
+            CueDataMode::Default(data) => {
+                for value in data.values_mut().flatten() {
+                    value.value.map_preset_ids(map);
+                }
+            }
+            CueDataMode::Builder(entries) => {
+                for preset_id in entries
+                    .iter_mut()
+                    .filter_map(|entry| entry.preset_id.as_mut())
+                {
+                    *preset_id = map(*preset_id);
+                }
+            }
+        }
+    }
+
+    pub fn map_group_ids(&mut self, map: &impl Fn(u32) -> u32) {
+        if let CueDataMode::Builder(entries) = &mut self.data {
+            for group_id in entries
+                .iter_mut()
+                .filter_map(|entry| entry.group_id.as_mut())
+            {
+                *group_id = map(*group_id);
+            }
+        }
+    }
+
     pub fn should_snap_channel_value_for_fixture(
         &self,
         fixture_id: u32,
@@ -582,7 +727,17 @@ impl Cue {
     }
 
     pub fn in_time(&self, preset_handler: &PresetHandler) -> f32 {
-        self.in_delay + self.in_fade + self.total_offset(preset_handler)
+        let longest_timing = self
+            .feature_group_timings
+            .iter()
+            .map(|timing| timing.delay + timing.fade)
+            .chain([
+                self.in_delay + self.in_fade,
+                self.out_delay() + self.out_fade(),
+            ])
+            .fold(0.0, f32::max);
+
+        longest_timing + self.total_offset(preset_handler)
     }
 
     pub fn selection(&self, preset_handler: &PresetHandler) -> FixtureSelection {
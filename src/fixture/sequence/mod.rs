@@ -117,6 +117,11 @@ impl Sequence {
         self.id
     }
 
+    pub fn with_id(mut self, id: u32) -> Self {
+        self.id = id;
+        self
+    }
+
     pub fn stop_behavior(&self) -> SequenceStopBehavior {
         self.stop_behavior
     }
@@ -137,6 +142,17 @@ impl Sequence {
         self.cues.push(cue);
     }
 
+    /// Inserts the cue before the first cue with a higher index.
+    pub fn insert_cue(&mut self, cue: Cue) {
+        let idx = self
+            .cues
+            .iter()
+            .position(|c| c.cue_idx() > cue.cue_idx())
+            .unwrap_or(self.cues.len());
+
+        self.cues.insert(idx, cue);
+    }
+
     pub fn cues(&self) -> &Vec<Cue> {
         &self.cues
     }
@@ -2,22 +2,155 @@ use std::{collections::HashMap, time};
 
 use serde::{Deserialize, Serialize};
 
-use crate::fixture::{
-    channel3::channel_value::FixtureChannelValue3,
-    gdtf::GdtfFixture,
-    handler::{FixtureHandler, FixtureTypeList},
-    presets::PresetHandler,
-    timing::TimingHandler,
-    value_source::FixtureChannelValuePriority,
+use crate::{
+    fixture::{
+        channel3::{
+            channel_value::FixtureChannelValue3,
+            feature::feature_group::FixtureChannel3FeatureGroup,
+        },
+        gdtf::GdtfFixture,
+        handler::{FixtureHandler, FixtureTypeList},
+        presets::{error::PresetHandlerError, PresetHandler},
+        timing::TimingHandler,
+        value_source::FixtureChannelValuePriority,
+    },
+    utils::clock,
 };
 
 use super::{
-    cue::{Cue, CueTrigger},
+    cue::{Cue, CueIdx, CueTrigger},
     FadeFixtureChannelValue, Sequence, SequenceStopBehavior,
 };
 
 pub struct ActiveCueData {}
 
+// Tracked values, that are crossfaded by a cue jump, are stored under these cue indices
+const CUE_JUMP_IDX: usize = usize::MAX;
+const CUE_JUMP_INTERRUPTED_IDX: usize = usize::MAX - 1;
+
+type CueStateValues = HashMap<u32, HashMap<String, (usize, FixtureChannelValue3)>>;
+
+/// A jump to a cue, that isn't the next one (e.g. go back or goto).
+/// All tracked values are crossfaded into the state, the sequence
+/// would have after running up to the target cue.
+#[derive(Debug, Clone)]
+struct SequenceRuntimeCueJump {
+    cue_idx: usize,
+    fade: f32,
+    delay: f32,
+    started_at: time::Instant,
+
+    // Computed on the first update after the jump,
+    // values are tagged with the cue they originate from
+    target_values: Option<CueStateValues>,
+}
+
+impl SequenceRuntimeCueJump {
+    fn alpha(&self) -> f32 {
+        let delta = clock::elapsed(self.started_at).as_secs_f32() - self.delay;
+
+        if delta < 0.0 {
+            0.0
+        } else if self.fade <= 0.0 {
+            1.0
+        } else {
+            (delta / self.fade).min(1.0)
+        }
+    }
+
+    /// Returns true, once the jump is finished.
+    fn update(
+        &mut self,
+        tracked_values: &mut HashMap<u32, HashMap<String, Vec<(usize, FadeFixtureChannelValue)>>>,
+        sequence: &Sequence,
+        fixture_types: &FixtureTypeList,
+        fixture_handler: &FixtureHandler,
+        preset_handler: &PresetHandler,
+        timing_handler: &TimingHandler,
+        priority: FixtureChannelValuePriority,
+    ) -> bool {
+        if self.target_values.is_none() {
+            let target_values = SequenceRuntime::cue_state_values(
+                sequence,
+                self.cue_idx,
+                &self.started_at,
+                fixture_types,
+                fixture_handler,
+                preset_handler,
+                timing_handler,
+            );
+
+            for (fixture_id, channels) in &target_values {
+                let fixture_values = tracked_values.entry(*fixture_id).or_default();
+
+                for channel_name in channels.keys() {
+                    fixture_values.entry(channel_name.clone()).or_default();
+                }
+            }
+
+            for (fixture_id, channels) in tracked_values.iter_mut() {
+                for (channel_name, values) in channels.iter_mut() {
+                    // a previous jump was interrupted, so its
+                    // values are now part of the current state
+                    for (value_cue_idx, _) in values.iter_mut() {
+                        if *value_cue_idx == CUE_JUMP_IDX {
+                            *value_cue_idx = CUE_JUMP_INTERRUPTED_IDX;
+                        }
+                    }
+
+                    let target_value = target_values
+                        .get(fixture_id)
+                        .and_then(|channels| channels.get(channel_name))
+                        .map(|(_, value)| value.clone())
+                        .unwrap_or(FixtureChannelValue3::Home);
+
+                    values.push((
+                        CUE_JUMP_IDX,
+                        FadeFixtureChannelValue::new(target_value, 0.0, priority),
+                    ));
+                }
+            }
+
+            self.target_values = Some(target_values);
+        }
+
+        let alpha = self.alpha();
+
+        for values in tracked_values
+            .values_mut()
+            .flat_map(|channels| channels.values_mut())
+        {
+            for (value_cue_idx, value) in values.iter_mut() {
+                if *value_cue_idx == CUE_JUMP_IDX {
+                    value.set_alpha(alpha);
+                }
+            }
+        }
+
+        if alpha < 1.0 {
+            return false;
+        }
+
+        tracked_values.clear();
+
+        for (fixture_id, channels) in self.target_values.take().unwrap() {
+            let fixture_values = tracked_values.entry(fixture_id).or_default();
+
+            for (channel_name, (value_cue_idx, value)) in channels {
+                fixture_values.insert(
+                    channel_name,
+                    vec![(
+                        value_cue_idx,
+                        FadeFixtureChannelValue::new(value, 1.0, priority),
+                    )],
+                );
+            }
+        }
+
+        true
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum SequenceRuntimeState {
     #[default]
@@ -39,10 +172,7 @@ impl SequenceRuntimeState {
 
     pub fn start(time_offset: f32) -> Self {
         Self::Cues {
-            active_cues: vec![(
-                0,
-                time::Instant::now() - time::Duration::from_secs_f32(time_offset),
-            )],
+            active_cues: vec![(0, clock::now() - time::Duration::from_secs_f32(time_offset))],
             current_cue: 0,
         }
     }
@@ -129,8 +259,7 @@ impl SequenceRuntimeState {
                     let should_clear_tracked_values = (current_cue + 1) >= num_cues;
 
                     let next_cue = (current_cue + 1) % num_cues;
-                    let activated_at =
-                        time::Instant::now() - time::Duration::from_secs_f32(time_offset);
+                    let activated_at = clock::now() - time::Duration::from_secs_f32(time_offset);
 
                     active_cues.retain(|(i, _)| *i != next_cue);
                     active_cues.push((next_cue, activated_at));
@@ -167,6 +296,16 @@ pub struct SequenceRuntime {
     #[serde(default, skip_serializing, skip_deserializing)]
     #[cfg_attr(feature = "ui", egui_probe(skip))]
     tracked_values: HashMap<u32, HashMap<String, Vec<(usize, FadeFixtureChannelValue)>>>,
+
+    #[serde(default, skip_serializing, skip_deserializing)]
+    #[cfg_attr(feature = "ui", egui_probe(skip))]
+    cue_jump: Option<SequenceRuntimeCueJump>,
+
+    // The cue, that was the target of the last finished jump. Its values
+    // are already fully faded in and must not be faded in again.
+    #[serde(default, skip_serializing, skip_deserializing)]
+    #[cfg_attr(feature = "ui", egui_probe(skip))]
+    jumped_cue_idx: Option<usize>,
 }
 
 impl SequenceRuntime {
@@ -175,6 +314,8 @@ impl SequenceRuntime {
             sequence_id,
             state: SequenceRuntimeState::default(),
             tracked_values: HashMap::new(),
+            cue_jump: None,
+            jumped_cue_idx: None,
         }
     }
 
@@ -182,6 +323,10 @@ impl SequenceRuntime {
         self.sequence_id
     }
 
+    pub fn set_sequence_id(&mut self, sequence_id: u32) {
+        self.sequence_id = sequence_id;
+    }
+
     pub fn is_started(&self) -> bool {
         self.state.is_started()
     }
@@ -242,32 +387,20 @@ impl SequenceRuntime {
             let fixture_cue_delta =
                 (cue_delta - cue.offset_for_fixture(*fixture_id, preset_handler)).max(0.0);
 
+            let fixture = fixture_handler.fixture_immut(*fixture_id).unwrap();
+
             let cue_values = cue.values_for_fixture(
-                fixture_handler.fixture_immut(*fixture_id).unwrap(),
+                fixture,
                 fixture_types,
                 preset_handler,
                 timing_handler,
                 Some(*cue_activated_at),
             );
 
-            let mut fixture_cue_fade = if fixture_cue_delta < cue.in_delay() {
-                0.0
-            } else {
-                ((fixture_cue_delta - cue.in_delay()) / cue.in_fade()).min(1.0)
-            };
-
-            fixture_cue_fade = cue.fading_function().apply(fixture_cue_fade);
-
-            if fixture_cue_fade == 0.0 {
-                continue;
-            }
-
             for value in cue_values {
                 if is_mib {
-                    let attribute = fixture_handler
-                        .fixture_immut(*fixture_id)
-                        .unwrap()
-                        .get_channel_attribute(fixture_types, value.channel_name());
+                    let attribute =
+                        fixture.get_channel_attribute(fixture_types, value.channel_name());
 
                     if attribute.is_ok_and(|attribute| attribute == "Dimmer") {
                         continue;
@@ -277,6 +410,37 @@ impl SequenceRuntime {
                 let fixture_values = tracked_values.entry(*fixture_id).or_default();
                 let (channel_name, value) = value.into();
 
+                let feature_group = fixture
+                    .get_channel_feature_group(fixture_types, &channel_name)
+                    .ok();
+
+                let is_out = cue.has_out_timing()
+                    && feature_group == Some(FixtureChannel3FeatureGroup::Dimmer)
+                    && Self::is_out_value(
+                        fixture_values.get(&channel_name),
+                        cue_idx,
+                        &value,
+                        fixture,
+                        fixture_types,
+                        &channel_name,
+                        preset_handler,
+                        timing_handler,
+                    );
+
+                let (fade, delay) = cue.channel_fade_and_delay(feature_group, is_out);
+
+                let mut fixture_cue_fade = if fixture_cue_delta < delay {
+                    0.0
+                } else {
+                    ((fixture_cue_delta - delay) / fade).min(1.0)
+                };
+
+                fixture_cue_fade = cue.fading_function().apply(fixture_cue_fade);
+
+                if fixture_cue_fade == 0.0 {
+                    continue;
+                }
+
                 if let Some(existing_values) = fixture_values.get_mut(&channel_name) {
                     if fixture_cue_fade == 0.0 {
                         continue;
@@ -329,12 +493,56 @@ impl SequenceRuntime {
         }
     }
 
+    /// Whether the value of the cue is lower than the value, the channel had
+    /// before the cue started fading, i.e. the channel is fading out.
+    fn is_out_value(
+        existing_values: Option<&Vec<(usize, FadeFixtureChannelValue)>>,
+        cue_idx: usize,
+        value: &FixtureChannelValue3,
+        fixture: &GdtfFixture,
+        fixture_types: &FixtureTypeList,
+        channel_name: &str,
+        preset_handler: &PresetHandler,
+        timing_handler: &TimingHandler,
+    ) -> bool {
+        let mut previous_value = FixtureChannelValue3::Home;
+        for (_, v) in existing_values
+            .into_iter()
+            .flatten()
+            .filter(|(value_cue_idx, _)| *value_cue_idx != cue_idx)
+        {
+            previous_value = FixtureChannelValue3::Mix {
+                a: Box::new(previous_value),
+                b: Box::new(v.value().clone()),
+                mix: v.alpha(),
+            };
+        }
+
+        let (_, previous_value) = previous_value.get_as_discrete(
+            fixture,
+            fixture_types,
+            channel_name,
+            preset_handler,
+            timing_handler,
+        );
+        let (_, value) = value.get_as_discrete(
+            fixture,
+            fixture_types,
+            channel_name,
+            preset_handler,
+            timing_handler,
+        );
+
+        value < previous_value
+    }
+
     pub fn update_values(
         tracked_values: &mut HashMap<u32, HashMap<String, Vec<(usize, FadeFixtureChannelValue)>>>,
         sequence: &Sequence,
         active_cues: &[(usize, time::Instant)],
         current_cue_idx: usize,
         next_cue_idx: Option<usize>,
+        jumped_cue_idx: Option<usize>,
         fixture_handler: &FixtureHandler,
         fixture_types: &FixtureTypeList,
         preset_handler: &PresetHandler,
@@ -348,9 +556,11 @@ impl SequenceRuntime {
                 tracked_values.clear();
             }
 
-            let cue_delta = time::Instant::now()
-                .duration_since(*cue_activated_at)
-                .as_secs_f32();
+            let cue_delta = if jumped_cue_idx == Some(*cue_idx) {
+                f32::INFINITY
+            } else {
+                clock::elapsed(*cue_activated_at).as_secs_f32()
+            };
 
             let cue_affected_fixtures = cue.affected_fixtures(preset_handler);
 
@@ -403,6 +613,25 @@ impl SequenceRuntime {
         timing_handler: &TimingHandler,
         priority: FixtureChannelValuePriority,
     ) -> bool {
+        if let Some(cue_jump) = self.cue_jump.as_mut() {
+            let sequence = preset_handler.get_sequence(self.sequence_id).unwrap();
+
+            if cue_jump.update(
+                &mut self.tracked_values,
+                sequence,
+                fixture_types,
+                fixture_handler,
+                preset_handler,
+                timing_handler,
+                priority,
+            ) {
+                self.jumped_cue_idx = Some(cue_jump.cue_idx);
+                self.cue_jump = None;
+            }
+
+            return false;
+        }
+
         if let Some((active_cues, current_cue_idx, current_cue_activated_at)) =
             self.state.when_started_mut()
         {
@@ -418,22 +647,26 @@ impl SequenceRuntime {
                 let cue = sequence.cue(*cue_idx);
                 let cue_in_time = cue.in_time(preset_handler);
 
-                let cue_delta = time::Instant::now()
-                    .duration_since(*cue_activated_at)
-                    .as_secs_f32();
+                let cue_delta = clock::elapsed(*cue_activated_at).as_secs_f32();
 
                 cue_delta <= cue_in_time || *cue_idx == *current_cue_idx
             });
 
+            if self.jumped_cue_idx.is_some_and(|jumped_cue_idx| {
+                !active_cues.iter().any(|(i, _)| *i == jumped_cue_idx)
+            }) {
+                self.jumped_cue_idx = None;
+            }
+
             if let Some(next_cue_idx) = Self::next_cue_idx(sequence, *current_cue_idx) {
                 let next_cue = sequence.cue(next_cue_idx);
 
                 let should_activate = match next_cue.trigger() {
                     CueTrigger::Time(time) => {
-                        current_cue_activated_at.elapsed().as_secs_f32() >= *time
+                        clock::elapsed(*current_cue_activated_at).as_secs_f32() >= *time
                     }
                     CueTrigger::Follow => {
-                        current_cue_activated_at.elapsed().as_secs_f32()
+                        clock::elapsed(*current_cue_activated_at).as_secs_f32()
                             >= current_cue.in_time(preset_handler)
                     }
                     CueTrigger::Manual => false,
@@ -441,7 +674,7 @@ impl SequenceRuntime {
 
                 if should_activate {
                     active_cues.retain(|(i, _)| *i != next_cue_idx);
-                    active_cues.push((next_cue_idx, time::Instant::now()));
+                    active_cues.push((next_cue_idx, clock::now()));
                     *current_cue_idx = next_cue_idx;
                 }
             }
@@ -452,6 +685,7 @@ impl SequenceRuntime {
                 active_cues,
                 *current_cue_idx,
                 Self::next_cue_idx(sequence, *current_cue_idx),
+                self.jumped_cue_idx,
                 fixture_handler,
                 fixture_types,
                 preset_handler,
@@ -472,6 +706,8 @@ impl SequenceRuntime {
     pub fn stop(&mut self) {
         self.state = SequenceRuntimeState::Stopped;
         self.tracked_values.clear();
+        self.cue_jump = None;
+        self.jumped_cue_idx = None;
     }
 
     pub fn should_auto_restart(&self, preset_handler: &PresetHandler) -> bool {
@@ -491,6 +727,12 @@ impl SequenceRuntime {
             return true;
         }
 
+        // finish a running jump immediately, so the next cue starts from the jump target
+        if let Some(cue_jump) = self.cue_jump.as_mut() {
+            cue_jump.fade = 0.0;
+            cue_jump.delay = 0.0;
+        }
+
         let (should_clear_tracked_values, new_state) = self.state.clone().next_cue(
             sequence.cues().len(),
             sequence.stop_behavior(),
@@ -506,6 +748,123 @@ impl SequenceRuntime {
         self.state == SequenceRuntimeState::Stopped
     }
 
+    /// Returns the values of all channels, as they are after the sequence ran up to
+    /// (and including) the cue at `cue_idx`. Tracking starts at the last block cue.
+    fn cue_state_values(
+        sequence: &Sequence,
+        cue_idx: usize,
+        cue_activated_at: &time::Instant,
+        fixture_types: &FixtureTypeList,
+        fixture_handler: &FixtureHandler,
+        preset_handler: &PresetHandler,
+        timing_handler: &TimingHandler,
+    ) -> CueStateValues {
+        let first_cue_idx = (0..=cue_idx)
+            .rev()
+            .find(|idx| sequence.cue(*idx).block())
+            .unwrap_or(0);
+
+        let mut values: CueStateValues = HashMap::new();
+
+        for idx in first_cue_idx..=cue_idx {
+            let cue = sequence.cue(idx);
+
+            for fixture_id in cue.affected_fixtures(preset_handler) {
+                let Some(fixture) = fixture_handler.fixture_immut(fixture_id) else {
+                    continue;
+                };
+
+                let fixture_values = values.entry(fixture_id).or_default();
+
+                for value in cue.values_for_fixture(
+                    fixture,
+                    fixture_types,
+                    preset_handler,
+                    timing_handler,
+                    Some(*cue_activated_at),
+                ) {
+                    let (channel_name, value) = value.into();
+                    fixture_values.insert(channel_name, (idx, value));
+                }
+            }
+        }
+
+        values
+    }
+
+    fn jump_to_cue(&mut self, cue_idx: usize, fade: f32, delay: f32, time_offset: f32) {
+        let started_at = clock::now() - time::Duration::from_secs_f32(time_offset);
+
+        self.state = SequenceRuntimeState::Cues {
+            active_cues: vec![(cue_idx, started_at)],
+            current_cue: cue_idx,
+        };
+
+        self.cue_jump = Some(SequenceRuntimeCueJump {
+            cue_idx,
+            fade,
+            delay,
+            started_at,
+            target_values: None,
+        });
+        self.jumped_cue_idx = None;
+    }
+
+    /// Jumps to the given cue, using the in timing of the cue. If the sequence isn't
+    /// started yet, it is started at this cue.
+    pub fn goto_cue(
+        &mut self,
+        preset_handler: &PresetHandler,
+        cue_idx: CueIdx,
+        time_offset: f32,
+    ) -> Result<(), PresetHandlerError> {
+        let sequence = preset_handler.get_sequence(self.sequence_id)?;
+
+        let idx = sequence
+            .cues()
+            .iter()
+            .position(|cue| cue.cue_idx() == cue_idx)
+            .ok_or(PresetHandlerError::CueNotFound(self.sequence_id, cue_idx))?;
+        let cue = sequence.cue(idx);
+
+        self.jump_to_cue(idx, cue.in_fade(), cue.in_delay(), time_offset);
+
+        Ok(())
+    }
+
+    /// Steps back to the previous cue, using the back timing of the current cue.
+    /// Returns false, if there is no previous cue to step back to.
+    pub fn previous_cue(&mut self, preset_handler: &PresetHandler, time_offset: f32) -> bool {
+        let Some((_, current_cue_idx, _)) = self.state.when_started() else {
+            return false;
+        };
+
+        let sequence = preset_handler.get_sequence(self.sequence_id).unwrap();
+
+        let Some(previous_cue_idx) = Self::previous_cue_idx(sequence, current_cue_idx) else {
+            return false;
+        };
+
+        let back_fade = sequence.cue(current_cue_idx).back_fade();
+        self.jump_to_cue(previous_cue_idx, back_fade, 0.0, time_offset);
+
+        true
+    }
+
+    fn previous_cue_idx(sequence: &Sequence, current_cue_idx: usize) -> Option<usize> {
+        if current_cue_idx == 0 {
+            if sequence.stop_behavior() == SequenceStopBehavior::Restart
+                && sequence.cues().len() > 1
+            {
+                Some(sequence.cues().len() - 1)
+            } else {
+                None
+            }
+        } else {
+            Some(current_cue_idx - 1)
+        }
+    }
+
     fn next_cue_idx(sequence: &Sequence, current_cue_idx: usize) -> Option<usize> {
         if current_cue_idx == sequence.cues().len() - 1 {
             if sequence.stop_behavior() == SequenceStopBehavior::Restart {
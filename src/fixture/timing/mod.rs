@@ -5,9 +5,16 @@ use serde::{Deserialize, Serialize};
 use speed_master::SpeedMasterValue;
 use timecode::Timecode;
 
-use crate::input::{midi::MidiQuarterTimecodePiece, timecode::packet::TimecodePacket};
+use crate::{
+    input::{midi::MidiQuarterTimecodePiece, timecode::packet::TimecodePacket},
+    parser::nodes::action::queue::ActionQueue,
+};
 
-use super::{handler::FixtureHandler, presets::PresetHandler, updatables::UpdatableHandler};
+use super::{
+    handler::{FixtureHandler, FixtureTypeList},
+    presets::PresetHandler,
+    updatables::UpdatableHandler,
+};
 
 pub mod error;
 pub mod speed_master;
@@ -86,18 +93,46 @@ impl TimingHandler {
             .ok_or(TimingHandlerError::TimecodeNotFound(id))
     }
 
+    pub fn start_timecode(&mut self, id: u32) -> Result<(), TimingHandlerError> {
+        let current_millis = self.current_timecode_packet.millis();
+
+        self.timecodes
+            .get_mut(&id)
+            .ok_or(TimingHandlerError::TimecodeNotFound(id))?
+            .start(current_millis);
+
+        Ok(())
+    }
+
+    pub fn stop_timecode(
+        &mut self,
+        id: u32,
+        fixture_handler: &mut FixtureHandler,
+    ) -> Result<(), TimingHandlerError> {
+        self.timecodes
+            .get_mut(&id)
+            .ok_or(TimingHandlerError::TimecodeNotFound(id))?
+            .stop(fixture_handler);
+
+        Ok(())
+    }
+
     fn update_running_timecodes(
         &mut self,
+        fixture_types: &FixtureTypeList,
         fixture_handler: &mut FixtureHandler,
         preset_handler: &PresetHandler,
         updatable_handler: &mut UpdatableHandler,
+        action_queue: &mut ActionQueue,
     ) {
         self.timecodes.values_mut().for_each(|timecode| {
             timecode.update(
                 self.current_timecode_packet.millis(),
+                fixture_types,
                 fixture_handler,
                 preset_handler,
                 updatable_handler,
+                action_queue,
             )
         });
     }
@@ -105,25 +140,41 @@ impl TimingHandler {
     pub fn update_timecode(
         &mut self,
         timecode_packet: TimecodePacket,
+        fixture_types: &FixtureTypeList,
         fixture_handler: &mut FixtureHandler,
         preset_handler: &PresetHandler,
         updatable_handler: &mut UpdatableHandler,
+        action_queue: &mut ActionQueue,
     ) {
         self.current_timecode_packet = timecode_packet;
 
-        self.update_running_timecodes(fixture_handler, preset_handler, updatable_handler);
+        self.update_running_timecodes(
+            fixture_types,
+            fixture_handler,
+            preset_handler,
+            updatable_handler,
+            action_queue,
+        );
     }
 
     pub fn update_timecode_quarter_frame(
         &mut self,
         piece: MidiQuarterTimecodePiece,
+        fixture_types: &FixtureTypeList,
         fixture_handler: &mut FixtureHandler,
         preset_handler: &PresetHandler,
         updatable_handler: &mut UpdatableHandler,
+        action_queue: &mut ActionQueue,
     ) {
         self.current_timecode_packet.update_from(piece);
 
-        self.update_running_timecodes(fixture_handler, preset_handler, updatable_handler);
+        self.update_running_timecodes(
+            fixture_types,
+            fixture_handler,
+            preset_handler,
+            updatable_handler,
+            action_queue,
+        );
     }
 
     pub fn current_timecode_packet(&self) -> &TimecodePacket {
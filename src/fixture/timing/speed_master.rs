@@ -2,6 +2,8 @@ use std::time;
 
 use serde::{Deserialize, Serialize};
 
+use crate::utils::clock;
+
 use super::tap::TapChain;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -49,8 +51,8 @@ impl SpeedMasterValue {
         // and false if we are in the second half of a beat
 
         if let Some(interval) = self.tap_chain.last_tap() {
-            let duration = time::Instant::now().duration_since(interval).as_secs_f64()
-                % self.secs_per_beat() as f64;
+            let duration =
+                clock::now().duration_since(interval).as_secs_f64() % self.secs_per_beat() as f64;
 
             duration < self.secs_per_beat() as f64 / 2.0
         } else {
@@ -1,9 +1,18 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use state::TimecodeState;
-use trigger::TimecodeTrigger;
+use trigger::{TimecodeTrigger, TimecodeTriggerType};
 
-use crate::fixture::{
-    handler::FixtureHandler, presets::PresetHandler, updatables::UpdatableHandler,
+use crate::{
+    fixture::{
+        channel3::channel_value::FixtureChannelValue3,
+        handler::{FixtureHandler, FixtureTypeList},
+        presets::{preset::FixturePresetId, PresetHandler},
+        updatables::UpdatableHandler,
+        value_source::FixtureChannelValueSource,
+    },
+    parser::nodes::action::queue::ActionQueue,
 };
 
 pub mod state;
@@ -35,19 +44,87 @@ impl Timecode {
         &self.state
     }
 
+    /// Returns the value, that was set by a trigger of this timecode, if it is running.
+    pub fn channel_value(&self, fixture_id: u32, channel: &str) -> Option<&FixtureChannelValue3> {
+        match &self.state {
+            TimecodeState::Running {
+                timecode_values, ..
+            } => timecode_values
+                .get(&fixture_id)
+                .and_then(|values| values.get(channel)),
+            TimecodeState::Stopped => None,
+        }
+    }
+
+    pub fn map_preset_ids(&mut self, map: &impl Fn(FixturePresetId) -> FixturePresetId) {
+        for trigger in &mut self.triggers {
+            if let TimecodeTriggerType::SetPreset { preset, .. } = &mut trigger.trigger_type {
+                *preset = map(*preset);
+            }
+        }
+    }
+
+    fn first_trigger_idx_after(&self, millis: u64) -> usize {
+        self.triggers
+            .iter()
+            .position(|trigger| trigger.millis > millis)
+            .unwrap_or(self.triggers.len())
+    }
+
+    /// Starts the timecode. Triggers, that lie before `current_millis`, aren't fired.
+    pub fn start(&mut self, current_millis: u64) {
+        if self.state.is_running() {
+            return;
+        }
+
+        self.state = TimecodeState::Running {
+            current_trigger_idx: self.first_trigger_idx_after(current_millis),
+            current_millis,
+            timecode_values: HashMap::new(),
+        };
+    }
+
+    /// Stops the timecode and releases all values, that were set by its triggers.
+    pub fn stop(&mut self, fixture_handler: &mut FixtureHandler) {
+        if let TimecodeState::Running {
+            timecode_values, ..
+        } = &self.state
+        {
+            for fixture_id in timecode_values.keys() {
+                if let Some(fixture) = fixture_handler.fixture(*fixture_id) {
+                    fixture.remove_value_source(FixtureChannelValueSource::Timecode {
+                        timecode_id: self.id,
+                    });
+                }
+            }
+        }
+
+        self.state = TimecodeState::Stopped;
+    }
+
     pub fn update(
         &mut self,
         new_millis: u64,
+        fixture_types: &FixtureTypeList,
         fixture_handler: &mut FixtureHandler,
         preset_handler: &PresetHandler,
         updatable_handler: &mut UpdatableHandler,
+        action_queue: &mut ActionQueue,
     ) {
+        let first_trigger_idx = self.first_trigger_idx_after(new_millis);
+
         if let TimecodeState::Running {
             current_trigger_idx,
             current_millis,
-            ..
+            timecode_values,
         } = &mut self.state
         {
+            // the timecode jumped backwards (e.g. the source was rewound),
+            // so continue from the first trigger after the new position
+            if new_millis < *current_millis {
+                *current_trigger_idx = first_trigger_idx;
+            }
+
             loop {
                 if *current_trigger_idx >= self.triggers.len()
                     || self.triggers[*current_trigger_idx].millis > new_millis
@@ -56,10 +133,14 @@ impl Timecode {
                 }
 
                 self.triggers[*current_trigger_idx].update(
+                    self.id,
                     new_millis,
+                    fixture_types,
                     fixture_handler,
                     preset_handler,
                     updatable_handler,
+                    timecode_values,
+                    action_queue,
                 );
                 *current_trigger_idx += 1;
             }
@@ -1,10 +1,17 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::fixture::{
-    handler::FixtureHandler,
-    presets::{preset::FixturePresetId, PresetHandler},
-    selection::FixtureSelection,
-    updatables::UpdatableHandler,
+use crate::{
+    fixture::{
+        channel3::channel_value::FixtureChannelValue3,
+        handler::{FixtureHandler, FixtureTypeList},
+        presets::{preset::FixturePresetId, PresetHandler},
+        selection::FixtureSelection,
+        updatables::UpdatableHandler,
+        value_source::FixtureChannelValueSource,
+    },
+    parser::nodes::action::queue::ActionQueue,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +21,12 @@ pub enum TimecodeTriggerType {
         preset: FixturePresetId,
     },
     ExecutorGo(u32),
+    ExecutorStop(u32),
+    ExecutorFaderLevel {
+        executor_id: u32,
+        level: f32,
+    },
+    Macro(u32),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,24 +37,69 @@ pub struct TimecodeTrigger {
 
 impl TimecodeTrigger {
     pub fn update(
-        &mut self,
+        &self,
+        timecode_id: u32,
         new_millis: u64,
+        fixture_types: &FixtureTypeList,
         fixture_handler: &mut FixtureHandler,
         preset_handler: &PresetHandler,
         updatable_handler: &mut UpdatableHandler,
+        timecode_values: &mut HashMap<u32, HashMap<String, FixtureChannelValue3>>,
+        action_queue: &mut ActionQueue,
     ) {
-        let time_offset = (new_millis - self.millis) as f32 / 1000.0;
+        let time_offset = new_millis.saturating_sub(self.millis) as f32 / 1000.0;
+
+        match &self.trigger_type {
+            TimecodeTriggerType::SetPreset { selection, preset } => {
+                let Ok(preset) = preset_handler.get_preset(*preset) else {
+                    log::warn!("Timecode {} failed to find preset {}", timecode_id, preset);
+                    return;
+                };
+
+                for fixture_id in selection.fixtures() {
+                    let Some(fixture) = fixture_handler.fixture(*fixture_id) else {
+                        continue;
+                    };
 
-        match self.trigger_type {
+                    timecode_values
+                        .entry(*fixture_id)
+                        .or_default()
+                        .extend(preset.channel_values(fixture_types, fixture, selection));
+
+                    fixture.push_value_source(FixtureChannelValueSource::Timecode { timecode_id });
+                }
+            }
             TimecodeTriggerType::ExecutorGo(executor_id) => {
                 let _ = updatable_handler.executor_go(
-                    executor_id,
+                    *executor_id,
                     fixture_handler,
                     preset_handler,
                     time_offset,
                 );
             }
-            _ => todo!(),
+            TimecodeTriggerType::ExecutorStop(executor_id) => {
+                let _ =
+                    updatable_handler.stop_executor(*executor_id, fixture_handler, preset_handler);
+            }
+            TimecodeTriggerType::ExecutorFaderLevel { executor_id, level } => {
+                if let Ok(executor) = updatable_handler.executor_mut(*executor_id) {
+                    executor.set_value(
+                        level.clamp(0.0, 1.0),
+                        fixture_handler,
+                        preset_handler,
+                        time_offset,
+                    );
+                }
+            }
+            TimecodeTriggerType::Macro(macro_id) => match preset_handler.get_macro(*macro_id) {
+                Ok(mmacro) => action_queue.enqueue_now(mmacro.action().clone()),
+                Err(err) => log::warn!(
+                    "Timecode {} failed to run macro {}: {}",
+                    timecode_id,
+                    macro_id,
+                    err
+                ),
+            },
         }
     }
 }
@@ -10,8 +10,8 @@ use crate::fixture::{
     error::FixtureError,
     gdtf::GdtfFixture,
     handler::{FixtureHandler, FixtureTypeList},
-    presets::PresetHandler,
-    sequence::{runtime::SequenceRuntime, FadeFixtureChannelValue},
+    presets::{error::PresetHandlerError, PresetHandler},
+    sequence::{cue::CueIdx, runtime::SequenceRuntime, FadeFixtureChannelValue},
     timing::TimingHandler,
     value_source::{FixtureChannelValuePriority, FixtureChannelValueSource},
 };
@@ -102,6 +102,35 @@ impl DemexExecutor {
         }
     }
 
+    pub fn go_back(&mut self, preset_handler: &PresetHandler, time_offset: f32) {
+        if !self.is_active() {
+            return;
+        }
+
+        self.runtime.previous_cue(preset_handler, time_offset);
+    }
+
+    /// Jumps to the given cue. If the executor isn't active, it's started at this cue.
+    pub fn goto_cue(
+        &mut self,
+        cue_idx: CueIdx,
+        fixture_handler: &mut FixtureHandler,
+        preset_handler: &PresetHandler,
+        time_offset: f32,
+    ) -> Result<(), PresetHandlerError> {
+        let was_active = self.is_active();
+
+        self.runtime
+            .goto_cue(preset_handler, cue_idx, time_offset)?;
+
+        if !was_active {
+            self.value = 1.0;
+            self.push_value_sources(fixture_handler, preset_handler);
+        }
+
+        Ok(())
+    }
+
     pub fn set_value(
         &mut self,
         value: f32,
@@ -125,6 +154,27 @@ impl DemexExecutor {
         self.runtime.is_started()
     }
 
+    /// A stopped copy of the executor, without any playback state.
+    pub fn without_runtime_state(&self) -> Self {
+        Self {
+            id: self.id,
+            priority: self.priority,
+            stomp_protected: self.stomp_protected,
+            value: 0.0,
+            runtime: SequenceRuntime::new(self.runtime.sequence_id()),
+            fader_function: self.fader_function.clone(),
+        }
+    }
+
+    /// Continues the playback of `other`, but keeps the own sequence.
+    pub fn take_runtime_state(&mut self, other: Self) {
+        let sequence_id = self.runtime.sequence_id();
+
+        self.runtime = other.runtime;
+        self.runtime.set_sequence_id(sequence_id);
+        self.value = other.value;
+    }
+
     pub fn fixtures(&self, preset_handler: &PresetHandler) -> HashSet<u32> {
         let sequence = preset_handler
             .get_sequence(self.runtime.sequence_id())
@@ -143,6 +193,14 @@ impl DemexExecutor {
 
         // self.started_at = Some(time::Instant::now() - time::Duration::from_secs_f32(time_offset));
 
+        self.push_value_sources(fixture_handler, preset_handler);
+    }
+
+    fn push_value_sources(
+        &self,
+        fixture_handler: &mut FixtureHandler,
+        preset_handler: &PresetHandler,
+    ) {
         for fixture_id in self.fixtures(preset_handler) {
             if let Some(fixture) = fixture_handler.fixture(fixture_id) {
                 fixture.push_value_source(FixtureChannelValueSource::Executor {
@@ -2,17 +2,21 @@ use std::collections::HashMap;
 
 use error::UpdatableHandlerError;
 use executor::{fader_function::DemexExecutorFaderFunction, DemexExecutor};
+use pixel_map::PixelMap;
 use serde::{Deserialize, Serialize};
 
 use super::{
     handler::{FixtureHandler, FixtureTypeList},
+    layout::FixtureLayout,
     presets::PresetHandler,
-    sequence::runtime::SequenceRuntime,
+    selection::FixtureSelection,
+    sequence::{cue::CueIdx, runtime::SequenceRuntime},
     timing::TimingHandler,
 };
 
 pub mod error;
 pub mod executor;
+pub mod pixel_map;
 pub mod runtime;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -24,6 +28,9 @@ pub enum StompSource {
 pub struct UpdatableHandler {
     executors: HashMap<u32, DemexExecutor>,
 
+    #[serde(default)]
+    pixel_maps: HashMap<u32, PixelMap>,
+
     #[serde(default, skip_serializing, skip_deserializing)]
     stomps: Vec<StompSource>,
 }
@@ -36,9 +43,76 @@ impl UpdatableHandler {
             .any(|(_, fader)| sequence_id == fader.runtime().sequence_id()))
     }
 
+    /// The id of an active executor, that runs the sequence
+    pub fn sequence_running_executor(&self, sequence_id: u32) -> Option<u32> {
+        self.executors
+            .iter()
+            .find(|(_, executor)| {
+                executor.is_active() && executor.runtime().sequence_id() == sequence_id
+            })
+            .map(|(id, _)| *id)
+    }
+
+    /// Points the executors of moved sequences to their new ids.
+    pub fn remap_sequence_ids(&mut self, mapping: &HashMap<u32, u32>) {
+        for executor in self.executors.values_mut() {
+            if let Some(sequence_id) = mapping.get(&executor.runtime().sequence_id()) {
+                executor.runtime_mut().set_sequence_id(*sequence_id);
+            }
+        }
+    }
+
     pub fn last_stomp_source(&self) -> Option<StompSource> {
         self.stomps.last().cloned()
     }
+
+    /// A copy of the show data, without the playback state of executors and pixel maps.
+    pub fn without_runtime_state(&self) -> Self {
+        Self {
+            executors: self
+                .executors
+                .iter()
+                .map(|(id, executor)| (*id, executor.without_runtime_state()))
+                .collect(),
+            pixel_maps: self
+                .pixel_maps
+                .iter()
+                .map(|(id, pixel_map)| (*id, pixel_map.without_runtime_state()))
+                .collect(),
+            stomps: Vec::new(),
+        }
+    }
+
+    /// Replaces the show data with `restored`, but keeps the playback state of the
+    /// executors and pixel maps, that exist in both. The others are stopped.
+    pub fn restore(
+        &mut self,
+        mut restored: Self,
+        fixture_handler: &mut FixtureHandler,
+        preset_handler: &PresetHandler,
+    ) {
+        for (id, mut executor) in self.executors.drain() {
+            match restored.executors.get_mut(&id) {
+                Some(restored_executor) => restored_executor.take_runtime_state(executor),
+                None if executor.is_active() => executor.stop(fixture_handler, preset_handler),
+                None => {}
+            }
+        }
+
+        for (id, mut pixel_map) in self.pixel_maps.drain() {
+            match restored.pixel_maps.get_mut(&id) {
+                Some(restored_pixel_map) => restored_pixel_map.take_runtime_state(pixel_map),
+                None => pixel_map.release(fixture_handler),
+            }
+        }
+
+        restored.stomps = std::mem::take(&mut self.stomps);
+        restored.stomps.retain(|stomp| match stomp {
+            StompSource::Executor(id) => restored.executors.contains_key(id),
+        });
+
+        *self = restored;
+    }
 }
 
 // Executors
@@ -155,6 +229,29 @@ impl UpdatableHandler {
         Ok(())
     }
 
+    pub fn executor_go_back(
+        &mut self,
+        id: u32,
+        preset_handler: &PresetHandler,
+        time_offset: f32,
+    ) -> Result<(), UpdatableHandlerError> {
+        self.executor_mut(id)?.go_back(preset_handler, time_offset);
+        Ok(())
+    }
+
+    pub fn executor_goto_cue(
+        &mut self,
+        id: u32,
+        cue_idx: CueIdx,
+        fixture_handler: &mut FixtureHandler,
+        preset_handler: &PresetHandler,
+        time_offset: f32,
+    ) -> Result<(), UpdatableHandlerError> {
+        self.executor_mut(id)?
+            .goto_cue(cue_idx, fixture_handler, preset_handler, time_offset)
+            .map_err(UpdatableHandlerError::PresetHandlerError)
+    }
+
     pub fn executor_stomp(&mut self, id: u32) {
         self.executor_unstomp(id);
         self.stomps.push(StompSource::Executor(id));
@@ -166,3 +263,66 @@ impl UpdatableHandler {
         });
     }
 }
+
+// Pixel maps
+impl UpdatableHandler {
+    pub fn create_pixel_map(
+        &mut self,
+        id: u32,
+        selection: FixtureSelection,
+    ) -> Result<(), UpdatableHandlerError> {
+        if self.pixel_maps.contains_key(&id) {
+            return Err(UpdatableHandlerError::UpdatableAlreadyExists(id));
+        }
+
+        self.pixel_maps.insert(id, PixelMap::new(id, selection));
+        Ok(())
+    }
+
+    pub fn pixel_map_mut(&mut self, id: u32) -> Result<&mut PixelMap, UpdatableHandlerError> {
+        self.pixel_maps
+            .get_mut(&id)
+            .ok_or(UpdatableHandlerError::UpdatableNotFound(id))
+    }
+
+    pub fn pixel_map(&self, id: u32) -> Result<&PixelMap, UpdatableHandlerError> {
+        self.pixel_maps
+            .get(&id)
+            .ok_or(UpdatableHandlerError::UpdatableNotFound(id))
+    }
+
+    pub fn pixel_maps(&self) -> &HashMap<u32, PixelMap> {
+        &self.pixel_maps
+    }
+
+    pub fn next_pixel_map_id(&self) -> u32 {
+        self.pixel_maps.keys().max().unwrap_or(&0) + 1
+    }
+
+    pub fn delete_pixel_map(
+        &mut self,
+        id: u32,
+        fixture_handler: &mut FixtureHandler,
+    ) -> Result<(), UpdatableHandlerError> {
+        self.pixel_maps
+            .remove(&id)
+            .ok_or(UpdatableHandlerError::UpdatableNotFound(id))?
+            .release(fixture_handler);
+        Ok(())
+    }
+
+    pub fn update_pixel_maps(
+        &mut self,
+        fixture_types: &FixtureTypeList,
+        layout: &FixtureLayout,
+        fixture_handler: &mut FixtureHandler,
+    ) {
+        for pixel_map in self.pixel_maps.values_mut() {
+            let executor = pixel_map
+                .executor()
+                .and_then(|executor_id| self.executors.get(&executor_id));
+
+            pixel_map.update(fixture_types, layout, fixture_handler, executor);
+        }
+    }
+}
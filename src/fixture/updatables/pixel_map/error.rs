@@ -0,0 +1,24 @@
+#[derive(Debug)]
+pub enum PixelMapError {
+    IoError(String, std::io::Error),
+    ImageError(String, image::ImageError),
+    NoImagesFound(String),
+}
+
+impl std::fmt::Display for PixelMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::IoError(path, err) => write!(f, "Failed to read {}: {}", path, err),
+            Self::ImageError(path, err) => write!(f, "Failed to load image {}: {}", path, err),
+            Self::NoImagesFound(directory) => {
+                write!(f, "No PNG images found in {}", directory)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PixelMapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
@@ -0,0 +1,455 @@
+use std::{collections::HashMap, sync::Arc, thread, time};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use source::PixelMapSource;
+
+use crate::{
+    fixture::{
+        channel3::channel_value::FixtureChannelValue3,
+        error::FixtureError,
+        gdtf::{rgb_channel_value, GdtfFixture},
+        handler::{FixtureHandler, FixtureTypeList},
+        layout::FixtureLayout,
+        selection::FixtureSelection,
+        value_source::{FixtureChannelValuePriority, FixtureChannelValueSource},
+    },
+    utils::clock,
+};
+
+use super::executor::{fader_function::DemexExecutorFaderFunction, DemexExecutor};
+
+pub mod error;
+pub mod font;
+pub mod source;
+
+fn default_one() -> f32 {
+    1.0
+}
+
+fn luma([r, g, b]: [f32; 3]) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+// filled by the loading thread, once the media is decoded
+type PixelMapMediaSlot = Arc<Mutex<Option<Vec<image::RgbImage>>>>;
+
+#[derive(Debug, Clone, Default)]
+struct PixelMapState {
+    /// Seconds since the start, scaled by the speed
+    time: f32,
+    last_update: Option<time::Instant>,
+
+    loaded_source: Option<PixelMapSource>,
+    // shared, so cloning the pixel map (e.g. for the show file) doesn't copy the images
+    media: Arc<Vec<image::RgbImage>>,
+    loading_source: Option<(PixelMapSource, PixelMapMediaSlot)>,
+
+    values: HashMap<u32, HashMap<String, FixtureChannelValue3>>,
+}
+
+/// Samples a source at the layout positions of the mapped fixtures
+/// and outputs the resulting colors and intensities.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub struct PixelMap {
+    #[cfg_attr(feature = "ui", egui_probe(skip))]
+    id: u32,
+
+    name: String,
+
+    source: PixelMapSource,
+
+    /// The bounding box of the layout entries of the mapped fixtures is
+    /// stretched over the source. Cells are spread along the longer side
+    /// of their fixture.
+    selection: FixtureSelection,
+
+    #[serde(default)]
+    priority: FixtureChannelValuePriority,
+
+    /// The executor, whose fader controls the pixel map. If its fader function
+    /// is `Speed`, it controls the speed, otherwise the level. The pixel map
+    /// runs, while the executor is active.
+    #[serde(default)]
+    executor: Option<u32>,
+
+    #[serde(default = "default_one")]
+    level: f32,
+
+    #[serde(default = "default_one")]
+    speed: f32,
+
+    #[serde(default, skip_serializing)]
+    #[cfg_attr(feature = "ui", egui_probe(skip))]
+    running: bool,
+
+    #[serde(skip)]
+    #[cfg_attr(feature = "ui", egui_probe(skip))]
+    state: PixelMapState,
+}
+
+impl PixelMap {
+    pub fn new(id: u32, selection: FixtureSelection) -> Self {
+        Self {
+            id,
+            name: format!("Pixel Map {}", id),
+            source: PixelMapSource::default(),
+            selection,
+            priority: FixtureChannelValuePriority::default(),
+            executor: None,
+            level: 1.0,
+            speed: 1.0,
+            running: false,
+            state: PixelMapState::default(),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &PixelMapSource {
+        &self.source
+    }
+
+    pub fn priority(&self) -> FixtureChannelValuePriority {
+        self.priority
+    }
+
+    pub fn executor(&self) -> Option<u32> {
+        self.executor
+    }
+
+    /// Whether the pixel map was started manually. Pixel maps, that are
+    /// controlled by an executor, run while the executor is active.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn start(&mut self) {
+        self.running = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    /// A stopped copy of the pixel map, without any playback state.
+    pub fn without_runtime_state(&self) -> Self {
+        Self {
+            id: self.id,
+            name: self.name.clone(),
+            source: self.source.clone(),
+            selection: self.selection.clone(),
+            priority: self.priority,
+            executor: self.executor,
+            level: self.level,
+            speed: self.speed,
+            running: false,
+            state: PixelMapState::default(),
+        }
+    }
+
+    /// Continues the playback of `other`.
+    pub fn take_runtime_state(&mut self, other: Self) {
+        self.running = other.running;
+        self.state = other.state;
+    }
+
+    pub fn channel_value(&self, fixture_id: u32, channel: &str) -> Option<&FixtureChannelValue3> {
+        self.state
+            .values
+            .get(&fixture_id)
+            .and_then(|values| values.get(channel))
+    }
+
+    /// Removes the value source of the pixel map from every mapped fixture.
+    pub fn release(&mut self, fixture_handler: &mut FixtureHandler) {
+        for fixture_id in self.state.values.keys() {
+            if let Some(fixture) = fixture_handler.fixture(*fixture_id) {
+                fixture.remove_value_source(FixtureChannelValueSource::PixelMap {
+                    pixel_map_id: self.id,
+                });
+            }
+        }
+
+        self.state.values.clear();
+        self.state.last_update = None;
+        self.state.time = 0.0;
+    }
+
+    pub fn update(
+        &mut self,
+        fixture_types: &FixtureTypeList,
+        layout: &FixtureLayout,
+        fixture_handler: &mut FixtureHandler,
+        executor: Option<&DemexExecutor>,
+    ) {
+        let (running, level, speed) = match executor {
+            Some(executor) if *executor.fader_function() == DemexExecutorFaderFunction::Speed => (
+                executor.is_active(),
+                self.level,
+                self.speed * executor.value(),
+            ),
+            Some(executor) => (
+                executor.is_active(),
+                self.level * executor.value(),
+                self.speed,
+            ),
+            None => (self.running, self.level, self.speed),
+        };
+
+        if !running {
+            if !self.state.values.is_empty() {
+                self.release(fixture_handler);
+            }
+
+            return;
+        }
+
+        let delta = self
+            .state
+            .last_update
+            .map(|last_update| clock::elapsed(last_update).as_secs_f32())
+            .unwrap_or_default();
+        self.state.last_update = Some(clock::now());
+        self.state.time += delta * speed;
+
+        self.update_media();
+
+        let values = self.mapped_values(fixture_types, layout, fixture_handler, level);
+
+        // fixtures, that are no longer mapped, are released
+        for fixture_id in self.state.values.keys() {
+            if values.contains_key(fixture_id) {
+                continue;
+            }
+
+            if let Some(fixture) = fixture_handler.fixture(*fixture_id) {
+                fixture.remove_value_source(FixtureChannelValueSource::PixelMap {
+                    pixel_map_id: self.id,
+                });
+            }
+        }
+
+        for fixture_id in values.keys() {
+            if self.state.values.contains_key(fixture_id) {
+                continue;
+            }
+
+            if let Some(fixture) = fixture_handler.fixture(*fixture_id) {
+                fixture.push_value_source(FixtureChannelValueSource::PixelMap {
+                    pixel_map_id: self.id,
+                });
+            }
+        }
+
+        self.state.values = values;
+    }
+
+    /// Decoding images takes too long for the update loop, so the media of the
+    /// source is loaded on a background thread. Until then, the previous media is used.
+    fn update_media(&mut self) {
+        if let Some((source, slot)) = self.state.loading_source.take() {
+            // the result is dropped, if the source changed in the meantime
+            if source == self.source {
+                let media = slot.lock().take();
+
+                match media {
+                    Some(media) => {
+                        self.state.media = Arc::new(media);
+                        self.state.loaded_source = Some(source);
+                    }
+                    None => self.state.loading_source = Some((source, slot)),
+                }
+
+                return;
+            }
+        }
+
+        if self.state.loaded_source.as_ref() == Some(&self.source) {
+            return;
+        }
+
+        let slot = PixelMapMediaSlot::default();
+        self.state.loading_source = Some((self.source.clone(), slot.clone()));
+
+        let (id, source) = (self.id, self.source.clone());
+        thread::spawn(move || {
+            let media = source
+                .load_media()
+                .inspect_err(|err| {
+                    log::error!("Failed to load source of pixel map {}: {}", id, err)
+                })
+                .unwrap_or_default();
+
+            *slot.lock() = Some(media);
+        });
+    }
+
+    fn mapped_values(
+        &self,
+        fixture_types: &FixtureTypeList,
+        layout: &FixtureLayout,
+        fixture_handler: &FixtureHandler,
+        level: f32,
+    ) -> HashMap<u32, HashMap<String, FixtureChannelValue3>> {
+        let entries = layout
+            .fixtures()
+            .iter()
+            .filter(|entry| self.selection.has_fixture(entry.fixture_id()))
+            .map(|entry| {
+                (
+                    entry.fixture_id(),
+                    emath::Rect::from_center_size(*entry.position(), *entry.size()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let Some(bounds) = entries
+            .iter()
+            .map(|(_, rect)| *rect)
+            .reduce(|a, b| a.union(b))
+        else {
+            return HashMap::new();
+        };
+
+        let normalize = |position: emath::Pos2| {
+            let normalized = (position - bounds.min) / bounds.size();
+
+            emath::pos2(
+                if bounds.width() > 0.0 {
+                    normalized.x
+                } else {
+                    0.5
+                },
+                if bounds.height() > 0.0 {
+                    normalized.y
+                } else {
+                    0.5
+                },
+            )
+        };
+
+        entries
+            .into_iter()
+            .filter_map(|(fixture_id, rect)| {
+                let fixture = fixture_handler.fixture_immut(fixture_id)?;
+
+                self.fixture_values(fixture_types, fixture, rect, normalize, level)
+                    .inspect_err(|err| log::error!("Failed to map fixture {}: {}", fixture_id, err))
+                    .ok()
+                    .map(|values| (fixture_id, values))
+            })
+            .collect()
+    }
+
+    fn fixture_values(
+        &self,
+        fixture_types: &FixtureTypeList,
+        fixture: &GdtfFixture,
+        rect: emath::Rect,
+        normalize: impl Fn(emath::Pos2) -> emath::Pos2,
+        level: f32,
+    ) -> Result<HashMap<String, FixtureChannelValue3>, FixtureError> {
+        let sample = |position: emath::Pos2| {
+            self.source
+                .sample(&self.state.media, normalize(position), self.state.time)
+        };
+
+        let num_cells = fixture.num_cells();
+        let cell_colors = (1..=num_cells)
+            .map(|cell| {
+                let t = (cell as f32 - 0.5) / num_cells as f32;
+
+                sample(if rect.width() >= rect.height() {
+                    emath::pos2(rect.left() + rect.width() * t, rect.center().y)
+                } else {
+                    emath::pos2(rect.center().x, rect.top() + rect.height() * t)
+                })
+            })
+            .collect::<Vec<_>>();
+        let fixture_color = sample(rect.center());
+
+        let color = |cell: Option<u16>| {
+            cell.and_then(|cell| cell_colors.get(cell as usize - 1))
+                .copied()
+                .unwrap_or(fixture_color)
+        };
+
+        let channels = fixture
+            .channels(fixture_types)?
+            .filter(|(channel, _)| {
+                fixture.is_channel_selected(channel.name().as_ref(), &self.selection)
+            })
+            .map(|(channel, logical_channel)| {
+                let dimmer_function_idx =
+                    logical_channel
+                        .channel_functions
+                        .iter()
+                        .position(|channel_function| {
+                            channel_function.attribute.first().unwrap().as_ref() == "Dimmer"
+                        });
+
+                (
+                    channel,
+                    fixture.channel_cell(channel.name().as_ref()),
+                    dimmer_function_idx,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // without a dimmer, the level is applied to the color
+        let color_scale = if channels.iter().any(|(_, _, dimmer)| dimmer.is_some()) {
+            1.0
+        } else {
+            level
+        };
+
+        let mut values = HashMap::new();
+        let mut color_cells = Vec::new();
+
+        for (channel, cell, _) in &channels {
+            let [r, g, b] = color(*cell);
+
+            if let Some(value) =
+                rgb_channel_value(channel, [r * color_scale, g * color_scale, b * color_scale])
+            {
+                values.insert(channel.name().as_ref().to_owned(), value);
+                color_cells.push(*cell);
+            }
+        }
+
+        // dimmers output the level, if their cell (or the fixture) has color channels,
+        // otherwise the brightness of the color
+        for (channel, cell, dimmer_function_idx) in &channels {
+            let Some(channel_function_idx) = dimmer_function_idx else {
+                continue;
+            };
+
+            let has_color = match cell {
+                Some(_) => color_cells.contains(cell),
+                None => !color_cells.is_empty(),
+            };
+
+            values.insert(
+                channel.name().as_ref().to_owned(),
+                FixtureChannelValue3::Discrete {
+                    channel_function_idx: *channel_function_idx,
+                    value: if has_color {
+                        level
+                    } else {
+                        level * luma(color(*cell))
+                    },
+                },
+            );
+        }
+
+        Ok(values)
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    error::PixelMapError,
+    font::{self, GLYPH_HEIGHT, GLYPH_WIDTH},
+};
+
+fn color_to_rgb(color: ecolor::Color32) -> [f32; 3] {
+    [
+        color.r() as f32 / 255.0,
+        color.g() as f32 / 255.0,
+        color.b() as f32 / 255.0,
+    ]
+}
+
+fn scale_rgb([r, g, b]: [f32; 3], scale: f32) -> [f32; 3] {
+    [r * scale, g * scale, b * scale]
+}
+
+fn hash(x: i32, y: i32, z: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x8DA6_B343)
+        ^ (y as u32).wrapping_mul(0xD816_3841)
+        ^ (z as u32).wrapping_mul(0xCB1A_B31F);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0x5BD1_E995);
+    h ^= h >> 15;
+
+    (h & 0xFFFF) as f32 / 65535.0
+}
+
+/// Smoothly interpolated value noise between 0 and 1
+fn value_noise(x: f32, y: f32, z: f32) -> f32 {
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let (x0, y0, z0) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+    let (tx, ty, tz) = (
+        smooth(x - x0 as f32),
+        smooth(y - y0 as f32),
+        smooth(z - z0 as f32),
+    );
+
+    let plane = |z: i32| {
+        lerp(
+            lerp(hash(x0, y0, z), hash(x0 + 1, y0, z), tx),
+            lerp(hash(x0, y0 + 1, z), hash(x0 + 1, y0 + 1, z), tx),
+            ty,
+        )
+    };
+
+    lerp(plane(z0), plane(z0 + 1), tz)
+}
+
+fn load_image(path: &Path) -> Result<image::RgbImage, PixelMapError> {
+    image::open(path)
+        .map(|image| image.to_rgb8())
+        .map_err(|err| PixelMapError::ImageError(path.display().to_string(), err))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
+pub enum PixelMapSource {
+    /// A PNG image, that is stretched over the mapped fixtures
+    Image { file: String },
+
+    /// The PNG images of a directory, played in the order of their file names
+    ImageSequence { directory: String, fps: f32 },
+
+    /// Moves back and forth between the two colors
+    Gradient {
+        from: ecolor::Color32,
+        to: ecolor::Color32,
+
+        /// Direction of the gradient in degrees
+        angle: f32,
+
+        /// Cycles per second
+        speed: f32,
+    },
+
+    Noise {
+        color: ecolor::Color32,
+
+        /// Number of noise cells across the mapped area
+        scale: f32,
+
+        /// Changes per second
+        speed: f32,
+    },
+
+    /// Text, that scrolls in from the right
+    TextScroll {
+        text: String,
+        color: ecolor::Color32,
+
+        /// Number of pixel columns, that are visible at once
+        columns: f32,
+
+        /// Pixel columns per second
+        speed: f32,
+    },
+}
+
+impl Default for PixelMapSource {
+    fn default() -> Self {
+        Self::Gradient {
+            from: ecolor::Color32::RED,
+            to: ecolor::Color32::BLUE,
+            angle: 0.0,
+            speed: 0.25,
+        }
+    }
+}
+
+impl fmt::Display for PixelMapSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Image { file } => write!(f, "Image ({})", file),
+            Self::ImageSequence { directory, .. } => write!(f, "Image Sequence ({})", directory),
+            Self::Gradient { .. } => write!(f, "Gradient"),
+            Self::Noise { .. } => write!(f, "Noise"),
+            Self::TextScroll { text, .. } => write!(f, "Text Scroll ({})", text),
+        }
+    }
+}
+
+impl PixelMapSource {
+    /// Loads the images of the source. Generated sources have no images.
+    pub fn load_media(&self) -> Result<Vec<image::RgbImage>, PixelMapError> {
+        match self {
+            Self::Image { file } => Ok(vec![load_image(Path::new(file))?]),
+            Self::ImageSequence { directory, .. } => {
+                let mut paths = fs::read_dir(directory)
+                    .map_err(|err| PixelMapError::IoError(directory.clone(), err))?
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| {
+                        path.extension()
+                            .is_some_and(|extension| extension.eq_ignore_ascii_case("png"))
+                    })
+                    .collect::<Vec<PathBuf>>();
+
+                if paths.is_empty() {
+                    return Err(PixelMapError::NoImagesFound(directory.clone()));
+                }
+
+                paths.sort();
+                paths.iter().map(|path| load_image(path)).collect()
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Samples the source at `position` (between 0 and 1 on both axes, y pointing down)
+    /// `time` seconds after the start.
+    pub fn sample(&self, media: &[image::RgbImage], position: emath::Pos2, time: f32) -> [f32; 3] {
+        let position = position.clamp(emath::Pos2::ZERO, emath::pos2(1.0, 1.0));
+
+        match self {
+            Self::Image { .. } | Self::ImageSequence { .. } => {
+                let frame_idx = match self {
+                    Self::ImageSequence { fps, .. } if !media.is_empty() => {
+                        (time.max(0.0) * fps) as usize % media.len()
+                    }
+                    _ => 0,
+                };
+
+                let Some(frame) = media.get(frame_idx) else {
+                    return [0.0; 3];
+                };
+
+                let x = (position.x * (frame.width() - 1) as f32).round() as u32;
+                let y = (position.y * (frame.height() - 1) as f32).round() as u32;
+                let [r, g, b] = frame.get_pixel(x, y).0;
+
+                [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0]
+            }
+            Self::Gradient {
+                from,
+                to,
+                angle,
+                speed,
+            } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+
+                // projection onto the direction, scaled so the corners are at 0 and 1
+                let projected = 0.5
+                    + ((position.x - 0.5) * cos + (position.y - 0.5) * sin)
+                        / (cos.abs() + sin.abs());
+
+                let phase = (projected + time * speed).rem_euclid(2.0);
+                let mix = if phase <= 1.0 { phase } else { 2.0 - phase };
+
+                let [from, to] = [color_to_rgb(*from), color_to_rgb(*to)];
+                [0, 1, 2].map(|idx| from[idx] + (to[idx] - from[idx]) * mix)
+            }
+            Self::Noise {
+                color,
+                scale,
+                speed,
+            } => scale_rgb(
+                color_to_rgb(*color),
+                value_noise(position.x * scale, position.y * scale, time * speed),
+            ),
+            Self::TextScroll {
+                text,
+                color,
+                columns,
+                speed,
+            } => {
+                let num_columns = columns.max(1.0).ceil() as i64;
+                let total_columns = (text.chars().count() * GLYPH_WIDTH) as i64 + num_columns;
+
+                let column = (position.x * columns.max(1.0) + time * speed).floor() as i64;
+                let text_column = column.rem_euclid(total_columns) - num_columns;
+
+                let row = ((position.y * GLYPH_HEIGHT as f32) as usize).min(GLYPH_HEIGHT - 1);
+
+                if text_column >= 0 && font::text_pixel(text, text_column as usize, row) {
+                    color_to_rgb(*color)
+                } else {
+                    [0.0; 3]
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PixelMapSource;
+
+    #[test]
+    fn test_gradient() {
+        let gradient = PixelMapSource::Gradient {
+            from: ecolor::Color32::BLACK,
+            to: ecolor::Color32::WHITE,
+            angle: 0.0,
+            speed: 0.5,
+        };
+
+        assert_eq!(gradient.sample(&[], emath::pos2(0.0, 0.5), 0.0), [0.0; 3]);
+        assert_eq!(gradient.sample(&[], emath::pos2(1.0, 0.0), 0.0), [1.0; 3]);
+
+        // moves back after reaching the end
+        assert_eq!(gradient.sample(&[], emath::pos2(1.0, 0.0), 1.0), [0.5; 3]);
+    }
+}
@@ -67,6 +67,8 @@ pub trait FixtureChannelValueSourceTrait {
 pub enum FixtureChannelValueSource {
     Programmer,
     Executor { executor_id: u32 },
+    Timecode { timecode_id: u32 },
+    PixelMap { pixel_map_id: u32 },
 }
 
 impl FixtureChannelValueSource {
@@ -93,6 +95,9 @@ impl FixtureChannelValueSource {
                         |s| !matches!(s, StompSource::Executor(id) if id == executor_id),
                     ))
             }
+            // timecode values are held until the timecode is stopped
+            Self::Timecode { .. } => Ok(false),
+            Self::PixelMap { .. } => Ok(false),
         }
     }
 }
@@ -142,6 +147,43 @@ impl FixtureChannelValueSourceTrait for Vec<FixtureChannelValueSource> {
                                 ))
                             }
                         }
+                        FixtureChannelValueSource::Timecode { timecode_id } => timing_handler
+                            .get_timecode(*timecode_id)
+                            .ok()
+                            .and_then(|timecode| {
+                                timecode.channel_value(fixture.id(), channel.name().as_ref())
+                            })
+                            .map(|value| {
+                                FadeFixtureChannelValue::new(
+                                    value.clone(),
+                                    1.0,
+                                    FixtureChannelValuePriority::Ltp,
+                                )
+                            })
+                            .ok_or_else(|| {
+                                FixtureError::GdtfChannelValueNotFound(
+                                    channel.name().as_ref().to_owned(),
+                                )
+                            }),
+                        FixtureChannelValueSource::PixelMap { pixel_map_id } => updatable_handler
+                            .pixel_map(*pixel_map_id)
+                            .ok()
+                            .and_then(|pixel_map| {
+                                pixel_map
+                                    .channel_value(fixture.id(), channel.name().as_ref())
+                                    .map(|value| {
+                                        FadeFixtureChannelValue::new(
+                                            value.clone(),
+                                            1.0,
+                                            pixel_map.priority(),
+                                        )
+                                    })
+                            })
+                            .ok_or_else(|| {
+                                FixtureError::GdtfChannelValueNotFound(
+                                    channel.name().as_ref().to_owned(),
+                                )
+                            }),
                     }
                 }
             })
@@ -204,6 +246,8 @@ impl FixtureChannelValueSource {
         match self {
             Self::Programmer => "P".to_string(),
             Self::Executor { executor_id } => executor_id.to_string(),
+            Self::Timecode { timecode_id } => format!("TC{}", timecode_id),
+            Self::PixelMap { pixel_map_id } => format!("PM{}", pixel_map_id),
         }
     }
 }
@@ -215,6 +259,8 @@ impl fmt::Display for FixtureChannelValueSource {
             Self::Executor {
                 executor_id: runtime_id,
             } => write!(f, "Exe({})", runtime_id),
+            Self::Timecode { timecode_id } => write!(f, "Tc({})", timecode_id),
+            Self::PixelMap { pixel_map_id } => write!(f, "Pm({})", pixel_map_id),
         }
     }
 }
@@ -1,10 +1,16 @@
-use std::{collections::HashSet, net, sync::Arc, thread::JoinHandle};
+use std::{
+    collections::HashMap,
+    io, net,
+    sync::{mpsc, Arc, OnceLock},
+    thread::JoinHandle,
+};
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 
 use crate::{
-    fixture::patch::SerializablePatch,
+    fixture::{patch::SerializablePatch, selection::FixtureSelection},
     headless::sync::DemexProtoSync,
+    parser::nodes::action::DeferredAction,
     show::{context::ShowContext, DemexNoUiShow},
     utils::{
         thread::{self, DemexThreadStatsHandler},
@@ -26,31 +32,106 @@ enum DemexHeadlessNodeState {
     Verified,
 }
 
-#[derive(Default)]
+type DemexHeadlessNodeWriter = Arc<Mutex<Protocol>>;
+
+#[derive(Default, Clone)]
 pub struct DemexHeadlessConroller {
-    nodes: Arc<RwLock<HashSet<u32>>>,
+    // verified nodes, with a protocol instance used for sending packets to them
+    nodes: Arc<RwLock<HashMap<u32, DemexHeadlessNodeWriter>>>,
+
+    // packets are sent to the nodes by a separate thread, so broadcasting never blocks
+    broadcast_tx: Arc<OnceLock<mpsc::Sender<DemexProtoControllerPacket>>>,
 }
 
 impl DemexHeadlessConroller {
+    fn broadcast(&self, packet: DemexProtoControllerPacket) {
+        // nodes can't connect before the controller thread is started
+        let Some(broadcast_tx) = self.broadcast_tx.get() else {
+            return;
+        };
+
+        if broadcast_tx.send(packet).is_err() {
+            log::warn!("Failed to broadcast packet, the broadcast thread stopped");
+        }
+    }
+
+    fn start_broadcast_thread(&self, stats: Arc<RwLock<DemexThreadStatsHandler>>) {
+        let (tx, rx) = mpsc::channel::<DemexProtoControllerPacket>();
+
+        if self.broadcast_tx.set(tx).is_err() {
+            log::warn!("Broadcast thread is already running");
+            return;
+        }
+
+        let nodes = self.nodes.clone();
+
+        thread::demex_simple_thread("demex-proto-broadcast".to_string(), stats, move |_, _| {
+            for packet in rx.iter() {
+                // don't hold the lock while sending, so nodes can connect in the meantime
+                let writers = nodes
+                    .read()
+                    .iter()
+                    .map(|(node_id, writer)| (*node_id, writer.clone()))
+                    .collect::<Vec<_>>();
+
+                for (node_id, writer) in writers {
+                    if let Err(err) = writer.lock().send_packet(&packet) {
+                        log::warn!("Failed to send packet to node {}: {}", node_id, err);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Sends an action, that was run on the controller, to every verified node,
+    /// so it can be replayed there.
+    pub fn broadcast_action(
+        &self,
+        action: DeferredAction,
+        fixture_selection: Option<FixtureSelection>,
+    ) {
+        self.broadcast(DemexProtoControllerPacket::Action {
+            action: Box::new(action),
+            fixture_selection,
+        });
+    }
+
+    /// Tells every verified node to request a new show file.
+    pub fn broadcast_show_file_update(&self) {
+        self.broadcast(DemexProtoControllerPacket::ShowFileUpdate);
+    }
+
     pub fn start_controller_thread(
-        self,
+        &self,
         stats: Arc<RwLock<DemexThreadStatsHandler>>,
         show_context: ShowContext,
     ) -> JoinHandle<()> {
+        self.start_broadcast_thread(stats.clone());
+
+        let nodes = self.nodes.clone();
+
         thread::demex_simple_thread("demex-proto".to_string(), stats, move |_, _| {
             let listener = net::TcpListener::bind(("0.0.0.0", DEMEX_HEADLESS_TCP_PORT)).unwrap();
             log::debug!("Started listener");
 
             for stream in listener.incoming() {
                 let show_context = show_context.clone();
-                let nodes = self.nodes.clone();
+                let nodes = nodes.clone();
 
                 std::thread::spawn(move || {
                     let mut node_state = DemexHeadlessNodeState::default();
+                    let mut node_id: Option<u32> = None;
 
                     let mut protocol = Protocol::with_stream(stream.unwrap()).unwrap();
 
-                    let _ = protocol.send_packet(&DemexProtoControllerPacket::HeadlessInfoRequest);
+                    // reading blocks, so every packet is sent using a separate writer,
+                    // which is shared with the broadcasting of actions
+                    let writer: DemexHeadlessNodeWriter =
+                        Arc::new(Mutex::new(protocol.try_clone().unwrap()));
+
+                    let _ = writer
+                        .lock()
+                        .send_packet(&DemexProtoControllerPacket::HeadlessInfoRequest);
 
                     loop {
                         let packet = protocol.read_packet::<DemexProtoHeadlessNodePacket>();
@@ -71,14 +152,18 @@ impl DemexHeadlessConroller {
                                         break;
                                     }
 
-                                    if nodes.read().contains(&id) {
+                                    if nodes.read().contains_key(&id) {
                                         log::warn!("Duplicate node id: {id}, shutting down..");
                                         break;
                                     }
 
-                                    let _ = protocol
+                                    let _ = writer
+                                        .lock()
                                         .send_packet(&DemexProtoControllerPacket::ShowFileUpdate);
                                     node_state = DemexHeadlessNodeState::Verified;
+
+                                    nodes.write().insert(id, writer.clone());
+                                    node_id = Some(id);
                                 }
                                 DemexProtoHeadlessNodePacket::ShowFileRequest => {
                                     if node_state != DemexHeadlessNodeState::Verified {
@@ -95,15 +180,22 @@ impl DemexHeadlessConroller {
                                         patch: SerializablePatch::from_patch(
                                             &show_context.patch.read(),
                                         ),
+                                        park_handler: show_context
+                                            .fixture_handler
+                                            .read()
+                                            .park_handler()
+                                            .clone(),
                                     });
 
-                                    protocol
+                                    let mut writer = writer.lock();
+
+                                    writer
                                         .send_packet(&DemexProtoControllerPacket::ShowFile {
                                             show_file,
                                         })
                                         .unwrap();
 
-                                    protocol
+                                    writer
                                         .send_packet(&DemexProtoControllerPacket::Sync {
                                             sync: Box::new(DemexProtoSync::get(&show_context)),
                                         })
@@ -114,16 +206,26 @@ impl DemexHeadlessConroller {
                                         break;
                                     }
 
-                                    protocol
+                                    writer
+                                        .lock()
                                         .send_packet(&DemexProtoControllerPacket::Sync {
                                             sync: Box::new(DemexProtoSync::get(&show_context)),
                                         })
                                         .unwrap();
                                 }
                             }
+                        } else if let Err(err) = packet {
+                            if err.kind() == io::ErrorKind::UnexpectedEof {
+                                log::debug!("Node disconnected, shutting down..");
+                                break;
+                            }
                         }
                     }
 
+                    if let Some(node_id) = node_id {
+                        nodes.write().remove(&node_id);
+                    }
+
                     let _ = protocol.shutdown(net::Shutdown::Both);
                 });
             }
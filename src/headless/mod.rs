@@ -4,6 +4,7 @@ pub mod id;
 pub mod node;
 pub mod packet;
 pub mod protocol;
+pub mod repl;
 pub mod sync;
 
 const DEMEX_HEADLESS_TCP_PORT: u16 = 4545;
@@ -1,11 +1,14 @@
 use std::{net, time};
 
 use crate::{
+    fixture::selection::FixtureSelection,
     headless::{
         packet::{controller::DemexProtoControllerPacket, node::DemexProtoHeadlessNodePacket},
         protocol::Protocol,
         DEMEX_HEADLESS_TCP_PORT,
     },
+    input::DemexInputDeviceHandler,
+    parser::nodes::{action::DeferredAction, fixture_selector::FixtureSelectorContext},
     show::context::ShowContext,
     utils::version::VERSION_STR,
 };
@@ -13,9 +16,36 @@ use crate::{
 use super::{error::DemexHeadlessError, id::DemexProtoDeviceId};
 
 #[derive(Default)]
-pub struct DemexHeadlessNode {}
+pub struct DemexHeadlessNode {
+    // nodes don't have input devices, but actions need a handler to run
+    input_device_handler: DemexInputDeviceHandler,
+}
 
 impl DemexHeadlessNode {
+    fn run_action(
+        &mut self,
+        action: DeferredAction,
+        fixture_selection: Option<FixtureSelection>,
+        show_context: &ShowContext,
+    ) {
+        // `issued_at` was converted to our clock on deserialization, so time based
+        // actions (e.g. executor go) compensate the latency of the network.
+        let result = action.action.run(
+            &mut show_context.fixture_handler.write(),
+            &mut show_context.preset_handler.write(),
+            FixtureSelectorContext::new(&fixture_selection),
+            &mut show_context.updatable_handler.write(),
+            &mut self.input_device_handler,
+            &mut show_context.timing_handler.write(),
+            &show_context.patch.read(),
+            action.issued_at,
+        );
+
+        if let Err(err) = result {
+            log::warn!("Failed to execute action {:?}: {}", action.action, err);
+        }
+    }
+
     pub fn start_headless_in_current_thread(
         &mut self,
         master_ip: String,
@@ -67,8 +97,12 @@ impl DemexHeadlessNode {
                         sync.apply(&show_context);
                         last_sync = Some(time::Instant::now());
                     }
-                    DemexProtoControllerPacket::Action { action: _ } => {
+                    DemexProtoControllerPacket::Action {
+                        action,
+                        fixture_selection,
+                    } => {
                         log::debug!("Received action, executing..");
+                        self.run_action(*action, fixture_selection, &show_context);
                     }
                 }
             }
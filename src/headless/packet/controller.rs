@@ -1,7 +1,8 @@
 use std::io::{self, Read};
 
 use crate::{
-    headless::sync::DemexProtoSync, parser::nodes::action::DeferredAction, show::DemexNoUiShow,
+    fixture::selection::FixtureSelection, headless::sync::DemexProtoSync,
+    parser::nodes::action::DeferredAction, show::DemexNoUiShow,
 };
 
 use super::{
@@ -21,9 +22,19 @@ const ACTION: u8 = 0x05;
 pub enum DemexProtoControllerPacket {
     HeadlessInfoRequest,
     ShowFileUpdate,
-    ShowFile { show_file: Box<DemexNoUiShow> },
-    Sync { sync: Box<DemexProtoSync> },
-    Action { action: Box<DeferredAction> },
+    ShowFile {
+        show_file: Box<DemexNoUiShow>,
+    },
+    Sync {
+        sync: Box<DemexProtoSync>,
+    },
+    Action {
+        action: Box<DeferredAction>,
+
+        /// The fixture selection of the controller at the time the action was run,
+        /// so actions referring to the current selection resolve identically on the nodes.
+        fixture_selection: Option<FixtureSelection>,
+    },
 }
 
 impl From<&DemexProtoControllerPacket> for u8 {
@@ -61,9 +72,13 @@ impl DemexProtoSerialize for DemexProtoControllerPacket {
                 bytes_written += demex_proto_write_u64(buf, serialized_sync.len() as u64)?;
                 bytes_written += demex_proto_write_bytes(buf, &serialized_sync)?;
             }
-            Self::Action { action } => {
+            Self::Action {
+                action,
+                fixture_selection,
+            } => {
                 let mut serialized_action = Vec::new();
-                ciborium::into_writer(action, &mut serialized_action).map_err(io::Error::other)?;
+                ciborium::into_writer(&(action, fixture_selection), &mut serialized_action)
+                    .map_err(io::Error::other)?;
 
                 bytes_written += demex_proto_write_u64(buf, serialized_action.len() as u64)?;
                 bytes_written += demex_proto_write_bytes(buf, &serialized_action)?;
@@ -105,9 +120,13 @@ impl DemexProtoDeserialize for DemexProtoControllerPacket {
                 let mut action_buf = vec![0; len as usize];
                 demex_proto_read_bytes(buf, &mut action_buf)?;
 
-                let action = ciborium::from_reader(&action_buf[..]).map_err(io::Error::other)?;
+                let (action, fixture_selection) =
+                    ciborium::from_reader(&action_buf[..]).map_err(io::Error::other)?;
 
-                Ok(DemexProtoControllerPacket::Action { action })
+                Ok(DemexProtoControllerPacket::Action {
+                    action,
+                    fixture_selection,
+                })
             }
             _ => Err(io::Error::new(
                 io::ErrorKind::InvalidData,
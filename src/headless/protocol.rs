@@ -23,6 +23,12 @@ impl Protocol {
         })
     }
 
+    /// Create a second Protocol operating on the same underlying socket.
+    /// This is useful, if one thread is blocked reading, while another one needs to send.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Self::with_stream(self.stream.try_clone()?)
+    }
+
     /// Establish a connection, wrap stream in BufReader/Writer
     pub fn connect(dest: SocketAddr) -> io::Result<Self> {
         let stream = TcpStream::connect(dest)?;
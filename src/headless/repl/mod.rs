@@ -0,0 +1,301 @@
+use std::{
+    io::{self, BufRead, Write},
+    net,
+    path::PathBuf,
+    sync::Arc,
+    thread, time,
+};
+
+use itertools::Itertools;
+use parking_lot::Mutex;
+
+use crate::{
+    fixture::{patch::SerializablePatch, selection::FixtureSelection},
+    input::DemexInputDeviceHandler,
+    lexer::{token::Token, Lexer},
+    parser::{
+        nodes::{
+            action::{queue::ActionQueue, result::ActionRunResult, Action, DeferredAction},
+            fixture_selector::FixtureSelectorContext,
+        },
+        Parser2,
+    },
+    show::{context::ShowContext, dispatch::ShowActionDispatcher, history::ShowHistory, DemexShow},
+    utils::version::VERSION_STR,
+};
+
+use super::{controller::DemexHeadlessConroller, error::DemexHeadlessError};
+
+const DEMEX_REPL_PROMPT: &str = "demex> ";
+const DEMEX_REPL_EXIT_COMMAND: &str = "exit";
+const DEMEX_REPL_INPUT_UPDATE_INTERVAL: time::Duration = time::Duration::from_millis(10);
+
+/// Runs a controller without UI, that reads command lines from stdin or a local TCP port.
+pub struct DemexRepl {
+    show_context: ShowContext,
+    headless_controller: DemexHeadlessConroller,
+
+    action_queue: ActionQueue,
+    history: ShowHistory,
+    fixture_selection: Option<FixtureSelection>,
+
+    input_device_handler: DemexInputDeviceHandler,
+    // tokens, that were entered using input device buttons
+    command_input: Vec<Token>,
+
+    // the loaded show, so data, that isn't part of the show context
+    // (e.g. the ui config), is kept when saving
+    show: DemexShow,
+    show_file: Option<PathBuf>,
+}
+
+impl DemexRepl {
+    pub fn new(
+        show_context: ShowContext,
+        headless_controller: DemexHeadlessConroller,
+        show: DemexShow,
+        show_file: Option<PathBuf>,
+    ) -> Self {
+        let input_device_handler = DemexInputDeviceHandler::new(
+            show.input_device_configs
+                .iter()
+                .cloned()
+                .map_into()
+                .collect::<Vec<_>>(),
+        );
+
+        Self {
+            show_context,
+            headless_controller,
+            action_queue: ActionQueue::default(),
+            history: ShowHistory::default(),
+            fixture_selection: None,
+            input_device_handler,
+            command_input: Vec::new(),
+            show,
+            show_file,
+        }
+    }
+
+    pub fn action_queue_mut(&mut self) -> &mut ActionQueue {
+        &mut self.action_queue
+    }
+
+    /// Parses a command line and enqueues the resulting action.
+    pub fn enqueue_line(&mut self, line: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut lexer = Lexer::new(line);
+
+        let tokens = lexer
+            .tokenize()
+            .map_err(|err| format!("{}\n{}", err, err.span().underline(line)))?;
+
+        let action = Parser2::new(&tokens)
+            .with_spans(lexer.spans())
+            .parse()
+            .map_err(|err| match err.span() {
+                Some(span) => format!("{}\n{}", err, span.underline(line)),
+                None => err.to_string(),
+            })?;
+
+        self.action_queue.enqueue_now(action);
+
+        Ok(())
+    }
+
+    fn save_show(&mut self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let show_file = self
+            .show_file
+            .clone()
+            .ok_or("No show file to save to, pass one using --show")?;
+
+        self.show.input_device_configs = self
+            .input_device_handler
+            .devices()
+            .iter()
+            .map(|device| device.config().clone())
+            .collect();
+        self.show.preset_handler = self.show_context.preset_handler.read().clone();
+        self.show.updatable_handler = self.show_context.updatable_handler.read().clone();
+        self.show.timing_handler = self.show_context.timing_handler.read().clone();
+        self.show.patch = SerializablePatch::from_patch(&self.show_context.patch.read());
+        self.show.park_handler = self
+            .show_context
+            .fixture_handler
+            .read()
+            .park_handler()
+            .clone();
+
+        self.show.save(&show_file)?;
+
+        Ok(show_file)
+    }
+
+    fn run_and_handle_action(
+        &mut self,
+        action: DeferredAction,
+        out: &mut impl Write,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (action, issued_at) = (action.action, action.issued_at);
+
+        match &action {
+            Action::ClearAll => {
+                self.fixture_selection = None;
+            }
+            Action::Save => {
+                let show_file = self.save_show()?;
+                writeln!(out, "info: Show saved to {}", show_file.display())?;
+            }
+            _ => {}
+        }
+
+        let result = ShowActionDispatcher {
+            show_context: &self.show_context,
+            fixture_selection: &self.fixture_selection,
+            input_device_handler: &mut self.input_device_handler,
+            history: &mut self.history,
+            headless_controller: &self.headless_controller,
+        }
+        .dispatch(&action, issued_at)?;
+
+        match result {
+            ActionRunResult::Warn(warn) => writeln!(out, "warning: {}", warn)?,
+            ActionRunResult::Info(info) => writeln!(out, "info: {}", info)?,
+            ActionRunResult::InfoWithLink(info, link) => {
+                writeln!(out, "info: {} ({})", info, link)?
+            }
+            #[cfg(feature = "ui")]
+            ActionRunResult::EditWindow(_) => {
+                writeln!(out, "warning: Edit windows are not available without UI")?
+            }
+            ActionRunResult::UpdateSelectedFixtures(selection) => {
+                self.fixture_selection = selection;
+            }
+            ActionRunResult::Default => {}
+        }
+
+        Ok(())
+    }
+
+    /// Executes every enqueued action, writing the results to `out`.
+    pub fn execute_action_queue(&mut self, out: &mut impl Write) -> io::Result<()> {
+        while let Some(action) = self.action_queue.dequeue() {
+            if let Err(err) = self.run_and_handle_action(action, out) {
+                log::warn!("Failed to execute action: {}", err);
+                writeln!(out, "error: {}", err)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handles the messages of the input devices and sends them feedback.
+    /// Actions, that are triggered by the input devices, are executed as well.
+    fn update_input_devices(&mut self) {
+        if let Err(err) = self.input_device_handler.update(
+            &mut self.show_context.fixture_handler.write(),
+            &mut self.show_context.preset_handler.write(),
+            &mut self.show_context.updatable_handler.write(),
+            &mut self.show_context.timing_handler.write(),
+            &self.show_context.patch.read(),
+            FixtureSelectorContext::new(&self.fixture_selection.clone()),
+            &mut self.action_queue,
+            &mut self.fixture_selection,
+            &mut self.command_input,
+        ) {
+            log::warn!("Failed to update input devices: {}", err);
+        }
+
+        // failures are logged, there is no session to write the results to
+        let _ = self.execute_action_queue(&mut io::sink());
+    }
+
+    fn start_input_thread(repl: &Arc<Mutex<Self>>) {
+        let repl = Arc::downgrade(repl);
+
+        thread::spawn(move || {
+            while let Some(repl) = repl.upgrade() {
+                repl.lock().update_input_devices();
+                drop(repl);
+
+                thread::sleep(DEMEX_REPL_INPUT_UPDATE_INTERVAL);
+            }
+        });
+    }
+
+    /// Parses a command line and executes it, writing the results to `out`.
+    pub fn handle_line(&mut self, line: &str, out: &mut impl Write) -> io::Result<()> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(err) = self.enqueue_line(line) {
+            writeln!(out, "error: {}", err)?;
+        }
+
+        self.execute_action_queue(out)
+    }
+
+    fn run_session(
+        repl: &Mutex<Self>,
+        reader: impl BufRead,
+        mut out: impl Write,
+    ) -> io::Result<()> {
+        writeln!(out, "demex v{} (by @matteolutz), Welcome!", VERSION_STR)?;
+        write!(out, "{}", DEMEX_REPL_PROMPT)?;
+        out.flush()?;
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if line.trim() == DEMEX_REPL_EXIT_COMMAND {
+                break;
+            }
+
+            repl.lock().handle_line(&line, &mut out)?;
+
+            write!(out, "{}", DEMEX_REPL_PROMPT)?;
+            out.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads command lines from stdin or, if `port` is set, from every connection on the local TCP port.
+    /// Returns, once stdin is closed or the listener fails.
+    pub fn start_in_current_thread(self, port: Option<u16>) -> Result<(), DemexHeadlessError> {
+        let repl = Arc::new(Mutex::new(self));
+        Self::start_input_thread(&repl);
+
+        let Some(port) = port else {
+            log::info!("Reading commands from stdin");
+
+            return Self::run_session(&repl, io::stdin().lock(), io::stdout())
+                .map_err(DemexHeadlessError::IOError);
+        };
+
+        let listener =
+            net::TcpListener::bind(("127.0.0.1", port)).map_err(DemexHeadlessError::IOError)?;
+        log::info!("Listening for commands on 127.0.0.1:{}", port);
+
+        for stream in listener.incoming() {
+            let stream = stream.map_err(DemexHeadlessError::IOError)?;
+            let repl = repl.clone();
+
+            thread::spawn(move || {
+                let peer_addr = stream.peer_addr().ok();
+                log::debug!("Accepted repl connection from {:?}", peer_addr);
+
+                let result = stream.try_clone().and_then(|reader| {
+                    Self::run_session(&repl, io::BufReader::new(reader), stream)
+                });
+
+                if let Err(err) = result {
+                    log::warn!("Repl connection {:?} failed: {}", peer_addr, err);
+                }
+            });
+        }
+
+        Ok(())
+    }
+}
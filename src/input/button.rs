@@ -1,5 +1,3 @@
-use std::time;
-
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -8,6 +6,7 @@ use crate::{
         patch::Patch,
         presets::{preset::FixturePresetId, PresetHandler},
         selection::FixtureSelection,
+        sequence::cue::CueIdx,
         timing::TimingHandler,
         updatables::UpdatableHandler,
     },
@@ -16,6 +15,7 @@ use crate::{
         action::{queue::ActionQueue, Action},
         fixture_selector::{FixtureSelector, FixtureSelectorContext, FixtureSelectorError},
     },
+    utils::clock,
 };
 
 use super::error::DemexInputDeviceError;
@@ -24,6 +24,12 @@ use super::error::DemexInputDeviceError;
 #[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
 pub enum DemexInputButton {
     ExecutorGo(u32),
+    ExecutorGoBack(u32),
+    ExecutorGotoCue {
+        id: u32,
+        #[cfg_attr(feature = "ui", egui_probe(skip))]
+        cue_idx: CueIdx,
+    },
     ExecutorStop(u32),
     ExecutorFlash {
         id: u32,
@@ -87,6 +93,12 @@ impl DemexInputButton {
                     .executor_go(*executor_id, fixture_handler, preset_handler, 0.0)
                     .map_err(DemexInputDeviceError::UpdatableHandlerError)?;
             }
+            Self::ExecutorGoBack(executor_id) => updatable_handler
+                .executor_go_back(*executor_id, preset_handler, 0.0)
+                .map_err(DemexInputDeviceError::UpdatableHandlerError)?,
+            Self::ExecutorGotoCue { id, cue_idx } => updatable_handler
+                .executor_goto_cue(*id, *cue_idx, fixture_handler, preset_handler, 0.0)
+                .map_err(DemexInputDeviceError::UpdatableHandlerError)?,
             Self::ExecutorStop(executor_id) => updatable_handler
                 .stop_executor(*executor_id, fixture_handler, preset_handler)
                 .map_err(DemexInputDeviceError::UpdatableHandlerError)?,
@@ -131,7 +143,7 @@ impl DemexInputButton {
             }
             Self::SpeedMasterTap { speed_master_id } => {
                 timing_handler
-                    .tap_speed_master_value(*speed_master_id, time::Instant::now())
+                    .tap_speed_master_value(*speed_master_id, clock::now())
                     .map_err(DemexInputDeviceError::TimingHandlerError)?;
             }
             Self::Unused => {}
@@ -165,4 +177,36 @@ impl DemexInputButton {
         }
         Ok(())
     }
+
+    pub fn map_preset_ids(&mut self, map: &impl Fn(FixturePresetId) -> FixturePresetId) {
+        match self {
+            Self::SelectivePreset { preset_id, .. } => *preset_id = map(*preset_id),
+            Self::Macro { action } => action.map_preset_ids(map),
+            _ => {}
+        }
+    }
+
+    pub fn map_sequence_ids(&mut self, map: &impl Fn(u32) -> u32) {
+        if let Self::Macro { action } = self {
+            action.map_sequence_ids(map);
+        }
+    }
+
+    pub fn map_group_ids(&mut self, map: &impl Fn(u32) -> u32) {
+        match self {
+            Self::FixtureSelector { fixture_selector } => fixture_selector.map_group_ids(map),
+            Self::Macro { action } => action.map_group_ids(map),
+            _ => {}
+        }
+    }
+
+    /// Rewrites the cue of the goto cue buttons of the given executors,
+    /// e.g. after the cues of their sequence were moved.
+    pub fn map_cue_idxs(&mut self, executor_ids: &[u32], map: &impl Fn(CueIdx) -> CueIdx) {
+        if let Self::ExecutorGotoCue { id, cue_idx } = self {
+            if executor_ids.contains(id) {
+                *cue_idx = map(*cue_idx);
+            }
+        }
+    }
 }
@@ -6,7 +6,7 @@ use super::{
     button::DemexInputButton,
     fader::DemexInputFader,
     profile::{
-        akai::ApcMiniMk2InputDeviceProfile, midi_timecode::MidiTimecodeProfile,
+        akai::ApcMiniMk2InputDeviceProfile, midi_timecode::MidiTimecodeProfile, osc::OscProfile,
         DemexInputDeviceProfileType,
     },
     DemexInputDeviceProfile,
@@ -79,6 +79,13 @@ impl From<DemexInputDeviceConfig> for DemexInputDevice {
             DemexInputDeviceProfileType::MidiTimecode { ref midi_in_device } => {
                 Box::new(MidiTimecodeProfile::new(midi_in_device.clone()))
             }
+            DemexInputDeviceProfileType::Osc {
+                ref bind_address,
+                ref feedback_address,
+            } => Box::new(OscProfile::new(
+                bind_address.clone(),
+                feedback_address.clone(),
+            )),
         };
 
         DemexInputDevice {
@@ -3,7 +3,8 @@ use crate::{
         handler::error::FixtureHandlerError, presets::error::PresetHandlerError,
         timing::error::TimingHandlerError, updatables::error::UpdatableHandlerError,
     },
-    parser::nodes::fixture_selector::FixtureSelectorError,
+    lexer::error::TokenizationError,
+    parser::{error::ParseError, nodes::fixture_selector::FixtureSelectorError},
 };
 
 #[derive(Debug)]
@@ -23,6 +24,9 @@ pub enum DemexInputDeviceError {
     UpdatableHandlerError(UpdatableHandlerError),
     FixtureSelectorError(FixtureSelectorError),
     TimingHandlerError(TimingHandlerError),
+    // the command, that failed to tokenize or parse
+    TokenizationError(String, TokenizationError),
+    ParseError(String, ParseError),
 
     ButtonAlreadyAssigned(u32),
     FaderAlreadyAssigned(u32),
@@ -34,6 +38,7 @@ pub enum DemexInputDeviceError {
 
     MpscSendError,
     MidirError(Box<dyn std::error::Error>),
+    IOError(std::io::Error),
 }
 
 impl std::error::Error for DemexInputDeviceError {
@@ -62,6 +67,16 @@ impl std::fmt::Display for DemexInputDeviceError {
             Self::UpdatableHandlerError(err) => write!(f, "Updatable handler error: {}", err),
             Self::FixtureSelectorError(err) => write!(f, "Fixture selector error: {}", err),
             Self::TimingHandlerError(err) => write!(f, "Timing handler error: {}", err),
+            Self::TokenizationError(cmd, err) => write!(
+                f,
+                "Tokenization error: {}\n{}",
+                err,
+                err.span().underline(cmd)
+            ),
+            Self::ParseError(cmd, err) => match err.span() {
+                Some(span) => write!(f, "Parse error: {}\n{}", err, span.underline(cmd)),
+                None => write!(f, "Parse error: {}", err),
+            },
 
             Self::ButtonAlreadyAssigned(id) => write!(f, "Button with id {} already assigned", id),
             Self::FaderAlreadyAssigned(id) => write!(f, "Fader with id {} already assigned", id),
@@ -73,6 +88,7 @@ impl std::fmt::Display for DemexInputDeviceError {
 
             Self::MpscSendError => write!(f, "Mpsc send error"),
             Self::MidirError(err) => write!(f, "Midir error: {}", err),
+            Self::IOError(err) => write!(f, "IO error: {}", err),
         }
     }
 }
@@ -9,4 +9,11 @@ pub enum DemexInputDeviceMessage {
 
     Timecode(TimecodePacket),
     TimecodeQuarterFrame { piece: MidiQuarterTimecodePiece },
+
+    Command(String),
+    ExecutorGo(u32),
+    ExecutorStop(u32),
+    ExecutorFaderValue(u32, f32),
+    GrandmasterValue(f32),
+    SpeedMasterTap(u32),
 }
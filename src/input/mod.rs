@@ -1,5 +1,7 @@
+use button::DemexInputButton;
 use device::{DemexInputDevice, DemexInputDeviceConfig};
 use error::DemexInputDeviceError;
+use fader::DemexInputFader;
 use message::DemexInputDeviceMessage;
 
 use crate::{
@@ -7,8 +9,12 @@ use crate::{
         handler::FixtureHandler, patch::Patch, presets::PresetHandler, selection::FixtureSelection,
         timing::TimingHandler, updatables::UpdatableHandler,
     },
-    lexer::token::Token,
-    parser::nodes::{action::queue::ActionQueue, fixture_selector::FixtureSelectorContext},
+    lexer::{token::Token, Lexer},
+    parser::{
+        nodes::{action::queue::ActionQueue, fixture_selector::FixtureSelectorContext},
+        Parser2,
+    },
+    utils::clock,
 };
 
 pub mod button;
@@ -17,6 +23,7 @@ pub mod error;
 pub mod fader;
 pub mod message;
 pub mod midi;
+pub mod osc;
 pub mod profile;
 pub mod timecode;
 
@@ -58,6 +65,12 @@ impl DemexInputDeviceHandler {
         &self.devices
     }
 
+    pub fn buttons_mut(&mut self) -> impl Iterator<Item = &mut DemexInputButton> {
+        self.devices
+            .iter_mut()
+            .flat_map(|device| device.config.buttons_mut().values_mut())
+    }
+
     pub fn update(
         &mut self,
         fixture_handler: &mut FixtureHandler,
@@ -147,17 +160,62 @@ impl DemexInputDeviceHandler {
                     DemexInputDeviceMessage::Timecode(timecode_packet) => timing_handler
                         .update_timecode(
                             timecode_packet,
+                            patch.fixture_types(),
                             fixture_handler,
                             preset_handler,
                             updatable_handler,
+                            macro_exec_cue,
                         ),
                     DemexInputDeviceMessage::TimecodeQuarterFrame { piece } => timing_handler
                         .update_timecode_quarter_frame(
                             piece,
+                            patch.fixture_types(),
                             fixture_handler,
                             preset_handler,
                             updatable_handler,
+                            macro_exec_cue,
                         ),
+                    DemexInputDeviceMessage::Command(cmd) => {
+                        let mut lexer = Lexer::new(&cmd);
+
+                        let tokens = lexer.tokenize().map_err(|err| {
+                            DemexInputDeviceError::TokenizationError(cmd.clone(), err)
+                        })?;
+
+                        let action = Parser2::new(&tokens)
+                            .with_spans(lexer.spans())
+                            .parse()
+                            .map_err(|err| DemexInputDeviceError::ParseError(cmd, err))?;
+
+                        macro_exec_cue.enqueue_now(action);
+                    }
+                    DemexInputDeviceMessage::ExecutorGo(executor_id) => updatable_handler
+                        .executor_go(executor_id, fixture_handler, preset_handler, 0.0)
+                        .map_err(DemexInputDeviceError::UpdatableHandlerError)?,
+                    DemexInputDeviceMessage::ExecutorStop(executor_id) => updatable_handler
+                        .stop_executor(executor_id, fixture_handler, preset_handler)
+                        .map_err(DemexInputDeviceError::UpdatableHandlerError)?,
+                    DemexInputDeviceMessage::ExecutorFaderValue(executor_id, value) => {
+                        DemexInputFader::Fader { executor_id }.handle_change(
+                            value,
+                            fixture_handler,
+                            preset_handler,
+                            updatable_handler,
+                            timing_handler,
+                        )?
+                    }
+                    DemexInputDeviceMessage::GrandmasterValue(value) => {
+                        DemexInputFader::Grandmaster.handle_change(
+                            value,
+                            fixture_handler,
+                            preset_handler,
+                            updatable_handler,
+                            timing_handler,
+                        )?
+                    }
+                    DemexInputDeviceMessage::SpeedMasterTap(speed_master_id) => timing_handler
+                        .tap_speed_master_value(speed_master_id, clock::now())
+                        .map_err(DemexInputDeviceError::TimingHandlerError)?,
                 }
             }
         }
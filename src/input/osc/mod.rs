@@ -0,0 +1,223 @@
+// **Ressources**
+// https://opensoundcontrol.stanford.edu/spec-1_0.html
+
+const OSC_BUNDLE_IDENTIFIER: &[u8] = b"#bundle\0";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArgument {
+    Int(i32),
+    Float(f32),
+    String(String),
+    Bool(bool),
+}
+
+impl OscArgument {
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Self::Int(value) => Some(*value as f32),
+            Self::Float(value) => Some(*value),
+            Self::String(value) => value.parse().ok(),
+            Self::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn type_tag(&self) -> u8 {
+        match self {
+            Self::Int(_) => b'i',
+            Self::Float(_) => b'f',
+            Self::String(_) => b's',
+            Self::Bool(true) => b'T',
+            Self::Bool(false) => b'F',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub arguments: Vec<OscArgument>,
+}
+
+fn padded_len(len: usize) -> usize {
+    (len + 4) & !3
+}
+
+fn read_string(bytes: &[u8], offset: &mut usize) -> Option<String> {
+    let remaining = bytes.get(*offset..)?;
+    let len = remaining.iter().position(|b| *b == 0)?;
+
+    let value = std::str::from_utf8(&remaining[..len]).ok()?.to_owned();
+    *offset += padded_len(len);
+
+    Some(value)
+}
+
+fn read_4_bytes(bytes: &[u8], offset: &mut usize) -> Option<[u8; 4]> {
+    let value = bytes.get(*offset..*offset + 4)?.try_into().ok()?;
+    *offset += 4;
+    Some(value)
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+    buf.extend_from_slice(value.as_bytes());
+    buf.resize(buf.len() + padded_len(value.len()) - value.len(), 0);
+}
+
+impl OscMessage {
+    pub fn new(address: impl Into<String>, arguments: Vec<OscArgument>) -> Self {
+        Self {
+            address: address.into(),
+            arguments,
+        }
+    }
+
+    /// Decodes an OSC packet, which is either a single message or a (nested) bundle.
+    /// Bundle time tags are ignored and all messages are handled immediately.
+    pub fn from_packet(bytes: &[u8]) -> Option<Vec<Self>> {
+        if bytes.starts_with(OSC_BUNDLE_IDENTIFIER) {
+            // skip the identifier and the time tag
+            let mut offset = OSC_BUNDLE_IDENTIFIER.len() + 8;
+            let mut messages = Vec::new();
+
+            while offset < bytes.len() {
+                let size = i32::from_be_bytes(read_4_bytes(bytes, &mut offset)?);
+                let size = usize::try_from(size).ok()?;
+
+                messages.extend(Self::from_packet(bytes.get(offset..offset + size)?)?);
+                offset += size;
+            }
+
+            Some(messages)
+        } else {
+            Self::from_bytes(bytes).map(|message| vec![message])
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut offset = 0;
+
+        let address = read_string(bytes, &mut offset)?;
+        if !address.starts_with('/') {
+            return None;
+        }
+
+        // some older implementations omit the type tag string, if there are no arguments
+        if offset >= bytes.len() {
+            return Some(Self::new(address, Vec::new()));
+        }
+
+        let type_tags = read_string(bytes, &mut offset)?;
+        let type_tags = type_tags.strip_prefix(',')?;
+
+        let mut arguments = Vec::with_capacity(type_tags.len());
+
+        for type_tag in type_tags.bytes() {
+            let argument = match type_tag {
+                b'i' => OscArgument::Int(i32::from_be_bytes(read_4_bytes(bytes, &mut offset)?)),
+                b'f' => OscArgument::Float(f32::from_be_bytes(read_4_bytes(bytes, &mut offset)?)),
+                b's' => OscArgument::String(read_string(bytes, &mut offset)?),
+                b'T' => OscArgument::Bool(true),
+                b'F' => OscArgument::Bool(false),
+                // nil and impulse don't carry any data
+                b'N' | b'I' => continue,
+                _ => return None,
+            };
+
+            arguments.push(argument);
+        }
+
+        Some(Self::new(address, arguments))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        write_string(&mut buf, &self.address);
+
+        let type_tags = std::iter::once(b',')
+            .chain(self.arguments.iter().map(OscArgument::type_tag))
+            .collect::<Vec<_>>();
+        write_string(&mut buf, std::str::from_utf8(&type_tags).unwrap());
+
+        for argument in &self.arguments {
+            match argument {
+                OscArgument::Int(value) => buf.extend_from_slice(&value.to_be_bytes()),
+                OscArgument::Float(value) => buf.extend_from_slice(&value.to_be_bytes()),
+                OscArgument::String(value) => write_string(&mut buf, value),
+                OscArgument::Bool(_) => {}
+            }
+        }
+
+        buf
+    }
+
+    /// Returns the address parts, without the optional `/demex` prefix.
+    pub fn address_parts(&self) -> Vec<&str> {
+        let address = self
+            .address
+            .strip_prefix("/demex/")
+            .unwrap_or(&self.address);
+
+        address.split('/').filter(|part| !part.is_empty()).collect()
+    }
+
+    pub fn first_f32(&self) -> Option<f32> {
+        self.arguments.first().and_then(OscArgument::as_f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc_message_roundtrip() {
+        let message = OscMessage::new(
+            "/demex/exec/1/fader",
+            vec![
+                OscArgument::Float(0.5),
+                OscArgument::Int(3),
+                OscArgument::String("go".to_owned()),
+                OscArgument::Bool(true),
+            ],
+        );
+
+        let bytes = message.to_bytes();
+        assert_eq!(bytes.len() % 4, 0);
+        assert_eq!(OscMessage::from_bytes(&bytes), Some(message));
+    }
+
+    #[test]
+    fn test_osc_bundle() {
+        let message = OscMessage::new("/grandmaster", vec![OscArgument::Float(1.0)]);
+        let message_bytes = message.to_bytes();
+
+        let mut bundle = OSC_BUNDLE_IDENTIFIER.to_vec();
+        bundle.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        for _ in 0..2 {
+            bundle.extend_from_slice(&(message_bytes.len() as i32).to_be_bytes());
+            bundle.extend_from_slice(&message_bytes);
+        }
+
+        assert_eq!(
+            OscMessage::from_packet(&bundle),
+            Some(vec![message.clone(), message])
+        );
+    }
+
+    #[test]
+    fn test_osc_address_parts() {
+        let message = OscMessage::new("/demex/exec/4/go", Vec::new());
+        assert_eq!(message.address_parts(), vec!["exec", "4", "go"]);
+
+        let message = OscMessage::new("/exec/4/fader", Vec::new());
+        assert_eq!(message.address_parts(), vec!["exec", "4", "fader"]);
+    }
+}
@@ -316,6 +316,23 @@ impl DemexInputDeviceProfile for ApcMiniMk2InputDeviceProfile {
                         ApcMiniMk2ButtonLedColor::Red,
                     )?;
                 }
+                DemexInputButton::ExecutorGoBack(id)
+                | DemexInputButton::ExecutorGotoCue { id, .. } => {
+                    let is_started = updatable_handler
+                        .executor(*id)
+                        .map_err(DemexInputDeviceError::UpdatableHandlerError)?
+                        .is_active();
+
+                    self.set_button_led(
+                        *button_id,
+                        if !is_started {
+                            ApcMiniMk2ButtonLedMode::Intens10
+                        } else {
+                            ApcMiniMk2ButtonLedMode::IntensFull
+                        },
+                        ApcMiniMk2ButtonLedColor::Teal,
+                    )?;
+                }
                 DemexInputButton::ExecutorFlash { id, .. } => {
                     let is_started = updatable_handler
                         .executor(*id)
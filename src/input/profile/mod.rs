@@ -2,13 +2,27 @@ use serde::{Deserialize, Serialize};
 
 pub mod akai;
 pub mod midi_timecode;
+pub mod osc;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "ui", derive(egui_probe::EguiProbe))]
 pub enum DemexInputDeviceProfileType {
-    MidiTimecode { midi_in_device: String },
+    MidiTimecode {
+        midi_in_device: String,
+    },
 
-    ApcMiniMk2 { apc_midi: String },
+    ApcMiniMk2 {
+        apc_midi: String,
+    },
+
+    Osc {
+        bind_address: String,
+
+        /// Address (ip:port) for executor feedback. If not set,
+        /// feedback is sent to the last surface that sent a message.
+        #[serde(default)]
+        feedback_address: Option<String>,
+    },
 }
 
 impl Default for DemexInputDeviceProfileType {
@@ -0,0 +1,233 @@
+use std::{
+    collections::HashMap,
+    net::{self, SocketAddr},
+    sync::{mpsc, Arc},
+    thread, time,
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    fixture::{
+        presets::PresetHandler, selection::FixtureSelection, timing::TimingHandler,
+        updatables::UpdatableHandler,
+    },
+    input::{
+        device::DemexInputDeviceConfig,
+        error::DemexInputDeviceError,
+        message::DemexInputDeviceMessage,
+        osc::{OscArgument, OscMessage},
+        DemexInputDeviceProfile,
+    },
+};
+
+pub struct OscProfile {
+    rx: mpsc::Receiver<OscMessage>,
+    socket: Option<net::UdpSocket>,
+
+    /// Feedback is sent here, or to the last sender, if no address is configured.
+    feedback_address: Option<SocketAddr>,
+    last_sender: Arc<Mutex<Option<SocketAddr>>>,
+
+    // (is active, fader value) that was last sent to `executor_feedback_target` for each executor
+    executor_feedback: HashMap<u32, (bool, f32)>,
+    executor_feedback_target: Option<SocketAddr>,
+}
+
+impl std::fmt::Debug for OscProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OscProfile")
+    }
+}
+
+impl OscProfile {
+    fn start_receive_thread(
+        socket: net::UdpSocket,
+        tx: mpsc::Sender<OscMessage>,
+        last_sender: Arc<Mutex<Option<SocketAddr>>>,
+    ) {
+        thread::spawn(move || {
+            log::debug!("Starting OSC input thread..");
+
+            let mut buffer = [0u8; 4096];
+
+            // the profile holds the other reference, so stop once it is dropped
+            while Arc::strong_count(&last_sender) > 1 {
+                let Ok((length, sender)) = socket.recv_from(&mut buffer) else {
+                    continue;
+                };
+
+                let Some(messages) = OscMessage::from_packet(&buffer[..length]) else {
+                    log::debug!(
+                        "failed to deserialize osc bytes: {:02X?}",
+                        &buffer[..length]
+                    );
+                    continue;
+                };
+
+                *last_sender.lock() = Some(sender);
+
+                for message in messages {
+                    if tx.send(message).is_err() {
+                        return;
+                    }
+                }
+            }
+
+            log::debug!("Stopping OSC input thread..");
+        });
+    }
+
+    pub fn new(bind_address: String, feedback_address: Option<String>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let last_sender = Arc::new(Mutex::new(None));
+
+        let socket = net::UdpSocket::bind(bind_address.as_str())
+            .and_then(|socket| {
+                socket.set_read_timeout(Some(time::Duration::from_millis(500)))?;
+                Ok(socket)
+            })
+            .inspect_err(|err| log::warn!("Failed to bind OSC socket to {}: {}", bind_address, err))
+            .ok();
+
+        if let Some(receive_socket) = socket.as_ref().and_then(|socket| socket.try_clone().ok()) {
+            Self::start_receive_thread(receive_socket, tx, last_sender.clone());
+        }
+
+        let feedback_address = feedback_address.and_then(|address| {
+            address
+                .parse()
+                .inspect_err(|err| {
+                    log::warn!("Invalid OSC feedback address {:?}: {}", address, err)
+                })
+                .ok()
+        });
+
+        Self {
+            rx,
+            socket,
+            feedback_address,
+            last_sender,
+            executor_feedback: HashMap::new(),
+            executor_feedback_target: None,
+        }
+    }
+
+    fn is_pressed(message: &OscMessage) -> bool {
+        // buttons on control surfaces send 1 on press and 0 on release
+        message.first_f32().is_none_or(|value| value != 0.0)
+    }
+
+    fn message_to_input(message: &OscMessage) -> Option<DemexInputDeviceMessage> {
+        match message.address_parts().as_slice() {
+            ["cmd"] => message
+                .arguments
+                .first()
+                .and_then(OscArgument::as_str)
+                .map(|cmd| DemexInputDeviceMessage::Command(cmd.to_owned())),
+            ["exec", id, "go"] if Self::is_pressed(message) => {
+                id.parse().ok().map(DemexInputDeviceMessage::ExecutorGo)
+            }
+            ["exec", id, "stop"] if Self::is_pressed(message) => {
+                id.parse().ok().map(DemexInputDeviceMessage::ExecutorStop)
+            }
+            ["exec", id, "fader"] => Some(DemexInputDeviceMessage::ExecutorFaderValue(
+                id.parse().ok()?,
+                message.first_f32()?.clamp(0.0, 1.0),
+            )),
+            ["grandmaster"] => Some(DemexInputDeviceMessage::GrandmasterValue(
+                message.first_f32()?.clamp(0.0, 1.0),
+            )),
+            ["speedmaster", id, "tap"] if Self::is_pressed(message) => {
+                id.parse().ok().map(DemexInputDeviceMessage::SpeedMasterTap)
+            }
+            _ => None,
+        }
+    }
+
+    fn feedback_target(&self) -> Option<SocketAddr> {
+        self.feedback_address.or(*self.last_sender.lock())
+    }
+
+    fn send(&self, message: OscMessage, address: SocketAddr) -> Result<(), DemexInputDeviceError> {
+        let socket = self
+            .socket
+            .as_ref()
+            .ok_or(DemexInputDeviceError::OperationNotSupported)?;
+
+        socket
+            .send_to(&message.to_bytes(), address)
+            .map_err(DemexInputDeviceError::IOError)?;
+
+        Ok(())
+    }
+}
+
+impl DemexInputDeviceProfile for OscProfile {
+    fn update_out(
+        &mut self,
+        _: &DemexInputDeviceConfig,
+        _: &PresetHandler,
+        updatable_handler: &UpdatableHandler,
+        _: &TimingHandler,
+        _: &Option<FixtureSelection>,
+    ) -> Result<(), DemexInputDeviceError> {
+        // nobody to send feedback to yet
+        let Some(target) = self.feedback_target() else {
+            return Ok(());
+        };
+
+        // a new target hasn't received any feedback yet
+        if self.executor_feedback_target != Some(target) {
+            self.executor_feedback.clear();
+            self.executor_feedback_target = Some(target);
+        }
+
+        for (executor_id, executor) in updatable_handler.executors() {
+            let state = (executor.is_active(), executor.value());
+
+            if self.executor_feedback.get(executor_id) == Some(&state) {
+                continue;
+            }
+
+            self.send(
+                OscMessage::new(
+                    format!("/demex/exec/{}/active", executor_id),
+                    vec![OscArgument::Int(state.0 as i32)],
+                ),
+                target,
+            )?;
+            self.send(
+                OscMessage::new(
+                    format!("/demex/exec/{}/fader", executor_id),
+                    vec![OscArgument::Float(state.1)],
+                ),
+                target,
+            )?;
+
+            self.executor_feedback.insert(*executor_id, state);
+        }
+
+        Ok(())
+    }
+
+    fn poll(&self) -> Result<Vec<DemexInputDeviceMessage>, DemexInputDeviceError> {
+        Ok(self
+            .rx
+            .try_iter()
+            .filter_map(|message| {
+                let input_message = Self::message_to_input(&message);
+
+                if input_message.is_none() {
+                    log::debug!("Unhandled OSC message: {:?}", message);
+                }
+
+                input_message
+            })
+            .collect())
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.socket.is_some()
+    }
+}
@@ -1,18 +1,33 @@
+use super::span::Span;
+
 #[derive(Debug)]
 pub enum TokenizationError {
-    UnknownKeyword(String),
-    UnterminatedString,
-    UnknownCharacter(char),
-    InvalidFloatingPoint,
+    UnknownKeyword(String, Span),
+    UnterminatedString(Span),
+    UnknownCharacter(char, Span),
+    InvalidFloatingPoint(Span),
+}
+
+impl TokenizationError {
+    pub fn span(&self) -> Span {
+        match self {
+            TokenizationError::UnknownKeyword(_, span)
+            | TokenizationError::UnterminatedString(span)
+            | TokenizationError::UnknownCharacter(_, span)
+            | TokenizationError::InvalidFloatingPoint(span) => *span,
+        }
+    }
 }
 
 impl std::fmt::Display for TokenizationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TokenizationError::UnknownKeyword(kw) => write!(f, "Unknown keyword: {}", kw),
-            TokenizationError::UnterminatedString => write!(f, "Unterminated string"),
-            TokenizationError::UnknownCharacter(c) => write!(f, "Unknown character: {}", c),
-            TokenizationError::InvalidFloatingPoint => write!(f, "Invalid floating point number"),
+            TokenizationError::UnknownKeyword(kw, _) => write!(f, "Unknown keyword: {}", kw),
+            TokenizationError::UnterminatedString(_) => write!(f, "Unterminated string"),
+            TokenizationError::UnknownCharacter(c, _) => write!(f, "Unknown character: {}", c),
+            TokenizationError::InvalidFloatingPoint(_) => {
+                write!(f, "Invalid floating point number")
+            }
         }
     }
 }
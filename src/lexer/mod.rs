@@ -1,16 +1,115 @@
-use self::{error::TokenizationError, token::Token};
+use self::{error::TokenizationError, span::Span, token::Token};
 
 pub mod error;
+pub mod span;
 pub mod token;
 
+// Generates `KEYWORDS` and `keyword_token` from a single list, so they can't get out of sync.
+// The first spelling of every keyword is the canonical one, the following are aliases.
+macro_rules! keywords {
+    ($($keyword:literal $(| $alias:literal)* => $token:ident,)*) => {
+        /// Canonical spelling of every keyword, aliases excluded
+        pub const KEYWORDS: &[&str] = &[$($keyword),*];
+
+        pub fn keyword_token(keyword: &str) -> Option<Token> {
+            match keyword {
+                $($keyword $(| $alias)* => Some(Token::$token),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+keywords! {
+    "intens" => KeywordIntens,
+    "thru" => KeywordThru,
+    "full" => KeywordFull,
+    "half" => KeywordHalf,
+    "out" => KeywordOut,
+    "home" => KeywordHome,
+    "manset" => KeywordManSet,
+    "record" | "rec" => KeywordRecord,
+    "group" | "g" => KeywordGroup,
+    "macro" => KeywordMacro,
+    "commandslice" => KeywordCommandSlice,
+    "rename" | "ren" => KeywordRename,
+    "clear" => KeywordClear,
+    "color" => KeywordColor,
+    "position" => KeywordPosition,
+    "preset" => KeywordPreset,
+    "test" => KeywordTest,
+    "strobe" => KeywordStrobe,
+    "maintenance" => KeywordMaintenance,
+    "create" => KeywordCreate,
+    "sequence" | "seq" => KeywordSequence,
+    "fader" => KeywordFader,
+    "executor" | "exec" => KeywordExecutor,
+    "as" => KeywordAs,
+    "for" => KeywordFor,
+    "to" => KeywordTo,
+    "cue" => KeywordCue,
+    "with" => KeywordWith,
+    "all" => KeywordAll,
+    "active" => KeywordActive,
+    "update" => KeywordUpdate,
+    "merge" => KeywordMerge,
+    "override" | "overwrite" => KeywordOverride,
+    "nuzul" => KeywordNuzul,
+    "sueud" => KeywordSueud,
+    "save" => KeywordSave,
+    "delete" | "del" => KeywordDelete,
+    "park" => KeywordPark,
+    "unpark" => KeywordUnpark,
+    "really" => KeywordReally,
+    "next" => KeywordNext,
+    "config" => KeywordConfig,
+    "output" => KeywordOutput,
+    "patch" => KeywordPatch,
+    "beam" => KeywordBeam,
+    "control" => KeywordControl,
+    "focus" => KeywordFocus,
+    "feature" => KeywordFeature,
+    "assign" => KeywordAssign,
+    "unassign" => KeywordUnassign,
+    "go" => KeywordGo,
+    "goto" => KeywordGoto,
+    "back" => KeywordBack,
+    "stop" => KeywordStop,
+    "flash" => KeywordFlash,
+    "effect" => KeywordEffect,
+    "button" => KeywordButton,
+    "tokens" => KeywordTokens,
+    "programmer" => KeywordProgrammer,
+    "stomp" => KeywordStomp,
+    "recall" => KeywordRecall,
+    "grandmaster" | "gm" => KeywordGrandmaster,
+    "undo" => KeywordUndo,
+    "redo" => KeywordRedo,
+    "copy" | "cp" => KeywordCopy,
+    "move" | "mv" => KeywordMove,
+    "renumber" => KeywordRenumber,
+    "step" => KeywordStep,
+    "address" => KeywordAddress,
+}
+
 pub struct Lexer<'a> {
     input: &'a str,
     position: usize,
+    spans: Vec<Span>,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(input: &'a str) -> Lexer<'a> {
-        Lexer { input, position: 0 }
+        Lexer {
+            input,
+            position: 0,
+            spans: Vec::new(),
+        }
+    }
+
+    /// The spans of the tokens of the last `tokenize` call, in the same order as the tokens
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
     }
 
     fn peek(&self) -> char {
@@ -32,10 +131,29 @@ impl<'a> Lexer<'a> {
         self.position >= self.input.len()
     }
 
+    fn byte_offset(&self, position: usize) -> usize {
+        self.input
+            .char_indices()
+            .nth(position)
+            .map_or(self.input.len(), |(offset, _)| offset)
+    }
+
+    fn span_from(&self, start: usize) -> Span {
+        Span::new(self.byte_offset(start), self.byte_offset(self.position))
+    }
+
+    fn push_token(&mut self, tokens: &mut Vec<Token>, token: Token, start: usize) {
+        tokens.push(token);
+        self.spans.push(self.span_from(start));
+    }
+
     pub fn tokenize(&mut self) -> Result<Vec<Token>, TokenizationError> {
         let mut tokens = Vec::<Token>::new();
+        self.spans.clear();
 
         while !self.is_eof() {
+            let start = self.position;
+
             if self.peek().is_ascii_digit() {
                 let mut num = String::new();
 
@@ -53,7 +171,9 @@ impl<'a> Lexer<'a> {
                     }
 
                     if fract.is_empty() {
-                        return Err(TokenizationError::InvalidFloatingPoint);
+                        return Err(TokenizationError::InvalidFloatingPoint(
+                            self.span_from(start),
+                        ));
                     }
 
                     let floating_point: f32 = format!("{}.{}", num, fract).parse().unwrap();
@@ -61,11 +181,15 @@ impl<'a> Lexer<'a> {
                     let num: u32 = num.parse().unwrap();
                     let fract: u32 = fract.parse().unwrap();
 
-                    tokens.push(Token::FloatingPoint(floating_point, (num, fract)));
+                    self.push_token(
+                        &mut tokens,
+                        Token::FloatingPoint(floating_point, (num, fract)),
+                        start,
+                    );
                     continue;
                 }
 
-                tokens.push(Token::Integer(num.parse().unwrap()));
+                self.push_token(&mut tokens, Token::Integer(num.parse().unwrap()), start);
             } else if self.peek().is_alphabetic() {
                 let mut keyword_str = String::new();
 
@@ -73,94 +197,37 @@ impl<'a> Lexer<'a> {
                     keyword_str.push(self.consume());
                 }
 
-                let keyword = match keyword_str.as_str() {
-                    "intens" => Some(Token::KeywordIntens),
-                    "thru" => Some(Token::KeywordThru),
-                    "full" => Some(Token::KeywordFull),
-                    "half" => Some(Token::KeywordHalf),
-                    "out" => Some(Token::KeywordOut),
-                    "home" => Some(Token::KeywordHome),
-                    "manset" => Some(Token::KeywordManSet),
-                    "record" | "rec" => Some(Token::KeywordRecord),
-                    "group" | "g" => Some(Token::KeywordGroup),
-                    "macro" => Some(Token::KeywordMacro),
-                    "commandslice" => Some(Token::KeywordCommandSlice),
-                    "rename" | "ren" => Some(Token::KeywordRename),
-                    "clear" => Some(Token::KeywordClear),
-                    "color" => Some(Token::KeywordColor),
-                    "position" => Some(Token::KeywordPosition),
-                    "preset" => Some(Token::KeywordPreset),
-                    "test" => Some(Token::KeywordTest),
-                    "strobe" => Some(Token::KeywordStrobe),
-                    "maintenance" => Some(Token::KeywordMaintenance),
-                    "create" => Some(Token::KeywordCreate),
-                    "sequence" | "seq" => Some(Token::KeywordSequence),
-                    "fader" => Some(Token::KeywordFader),
-                    "executor" | "exec" => Some(Token::KeywordExecutor),
-                    "as" => Some(Token::KeywordAs),
-                    "for" => Some(Token::KeywordFor),
-                    "to" => Some(Token::KeywordTo),
-                    "cue" => Some(Token::KeywordCue),
-                    "with" => Some(Token::KeywordWith),
-                    "all" => Some(Token::KeywordAll),
-                    "active" => Some(Token::KeywordActive),
-                    "update" => Some(Token::KeywordUpdate),
-                    "merge" => Some(Token::KeywordMerge),
-                    "override" => Some(Token::KeywordOverride),
-                    "nuzul" => Some(Token::KeywordNuzul),
-                    "sueud" => Some(Token::KeywordSueud),
-                    "save" => Some(Token::KeywordSave),
-                    "delete" | "del" => Some(Token::KeywordDelete),
-                    "really" => Some(Token::KeywordReally),
-                    "next" => Some(Token::KeywordNext),
-                    "config" => Some(Token::KeywordConfig),
-                    "output" => Some(Token::KeywordOutput),
-                    "patch" => Some(Token::KeywordPatch),
-                    "beam" => Some(Token::KeywordBeam),
-                    "control" => Some(Token::KeywordControl),
-                    "focus" => Some(Token::KeywordFocus),
-                    "feature" => Some(Token::KeywordFeature),
-                    "assign" => Some(Token::KeywordAssign),
-                    "unassign" => Some(Token::KeywordUnassign),
-                    "go" => Some(Token::KeywordGo),
-                    "stop" => Some(Token::KeywordStop),
-                    "flash" => Some(Token::KeywordFlash),
-                    "effect" => Some(Token::KeywordEffect),
-                    "button" => Some(Token::KeywordButton),
-                    "tokens" => Some(Token::KeywordTokens),
-                    "programmer" => Some(Token::KeywordProgrammer),
-                    "stomp" => Some(Token::KeywordStomp),
-                    "recall" => Some(Token::KeywordRecall),
-                    "grandmaster" | "gm" => Some(Token::KeywordGrandmaster),
-                    _ => None,
-                };
+                let keyword = keyword_token(&keyword_str);
 
                 if let Some(keyword) = keyword {
-                    tokens.push(keyword);
+                    self.push_token(&mut tokens, keyword, start);
                 } else {
-                    return Err(TokenizationError::UnknownKeyword(keyword_str));
+                    return Err(TokenizationError::UnknownKeyword(
+                        keyword_str,
+                        self.span_from(start),
+                    ));
                 }
             } else if self.peek() == '@' {
                 self.consume();
-                tokens.push(Token::KeywordIntens);
+                self.push_token(&mut tokens, Token::KeywordIntens, start);
             } else if self.peek() == '+' {
                 self.consume();
-                tokens.push(Token::Plus);
+                self.push_token(&mut tokens, Token::Plus, start);
             } else if self.peek() == '-' {
                 self.consume();
-                tokens.push(Token::Minus);
+                self.push_token(&mut tokens, Token::Minus, start);
             } else if self.peek() == '%' {
                 self.consume();
-                tokens.push(Token::Percent);
+                self.push_token(&mut tokens, Token::Percent, start);
             } else if self.peek() == '!' {
                 self.consume();
-                tokens.push(Token::Exclamation);
+                self.push_token(&mut tokens, Token::Exclamation, start);
             } else if self.peek() == '(' {
                 self.consume();
-                tokens.push(Token::ParenOpen);
+                self.push_token(&mut tokens, Token::ParenOpen, start);
             } else if self.peek() == ')' {
                 self.consume();
-                tokens.push(Token::ParenClose);
+                self.push_token(&mut tokens, Token::ParenClose, start);
             } else if self.peek() == '\"' {
                 self.consume();
 
@@ -168,7 +235,7 @@ impl<'a> Lexer<'a> {
 
                 while self.peek() != '\"' {
                     if self.peek() == '\n' {
-                        return Err(TokenizationError::UnterminatedString);
+                        return Err(TokenizationError::UnterminatedString(self.span_from(start)));
                     }
 
                     string.push(self.consume());
@@ -176,22 +243,108 @@ impl<'a> Lexer<'a> {
 
                 self.consume();
 
-                tokens.push(Token::String(string));
+                self.push_token(&mut tokens, Token::String(string), start);
             } else if self.peek() == '~' {
                 self.consume();
-                tokens.push(Token::KeywordFixturesSelected);
+                self.push_token(&mut tokens, Token::KeywordFixturesSelected, start);
             } else if self.peek() == ',' {
                 self.consume();
-                tokens.push(Token::Comma);
+                self.push_token(&mut tokens, Token::Comma, start);
+            } else if self.peek() == '*' {
+                self.consume();
+                self.push_token(&mut tokens, Token::Asterisk, start);
             } else if self.peek().is_whitespace() {
                 self.consume();
             } else {
-                return Err(TokenizationError::UnknownCharacter(self.peek()));
+                return Err(TokenizationError::UnknownCharacter(
+                    self.peek(),
+                    Span::new(self.byte_offset(start), self.byte_offset(start + 1)),
+                ));
             }
         }
 
         tokens.push(Token::Eof);
+        self.spans
+            .push(Span::new(self.input.len(), self.input.len()));
 
         Ok(tokens)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::mem::discriminant;
+
+    use super::{error::TokenizationError, keyword_token, span::Span, Lexer, KEYWORDS};
+
+    #[test]
+    fn test_keywords() {
+        for keyword in KEYWORDS {
+            let token = keyword_token(keyword)
+                .unwrap_or_else(|| panic!("keyword {:?} has no token", keyword));
+
+            let tokens = Lexer::new(keyword).tokenize().unwrap();
+            assert_eq!(
+                tokens.len(),
+                2,
+                "keyword {:?} isn't a single token",
+                keyword
+            );
+            assert_eq!(discriminant(&tokens[0]), discriminant(&token));
+        }
+    }
+
+    #[test]
+    fn test_grammar_terminals_are_keywords() {
+        let grammar = include_str!("../../res/grammar.ebnf");
+
+        // every other part of the grammar is a quoted terminal
+        for terminal in grammar
+            .split('"')
+            .skip(1)
+            .step_by(2)
+            .filter(|terminal| terminal.len() > 1 && terminal.chars().all(char::is_alphabetic))
+        {
+            assert!(
+                keyword_token(terminal).is_some(),
+                "grammar terminal {:?} isn't a keyword",
+                terminal
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_spans() {
+        let mut lexer = Lexer::new("\"Bühne\" 1");
+        lexer.tokenize().unwrap();
+
+        // spans are byte offsets
+        assert_eq!(
+            lexer.spans(),
+            &[Span::new(0, 8), Span::new(9, 10), Span::new(10, 10)]
+        );
+    }
+
+    #[test]
+    fn test_error_spans() {
+        let error = |input: &str| Lexer::new(input).tokenize().unwrap_err();
+
+        assert!(matches!(
+            error("1 foo"),
+            TokenizationError::UnknownKeyword(keyword, span)
+                if keyword == "foo" && span == Span::new(2, 5)
+        ));
+        assert!(matches!(
+            error("rename group 1 to \"abc"),
+            TokenizationError::UnterminatedString(span) if span == Span::new(18, 22)
+        ));
+        assert!(matches!(
+            error("1 & 2"),
+            TokenizationError::UnknownCharacter('&', span) if span == Span::new(2, 3)
+        ));
+        assert!(matches!(
+            error("1. intens"),
+            TokenizationError::InvalidFloatingPoint(span) if span == Span::new(0, 2)
+        ));
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// Byte range of a token in the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the input, with the span underlined by carets in the line below.
+    /// Empty spans (e.g. the end of the input) are marked with a single caret.
+    pub fn underline(&self, input: &str) -> String {
+        let start = self.start.min(input.len());
+        let end = self.end.clamp(start, input.len());
+
+        let offset = input[..start].chars().count();
+        let width = input[start..end].chars().count().max(1);
+
+        format!("{}\n{}{}", input, " ".repeat(offset), "^".repeat(width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Span;
+
+    #[test]
+    fn test_underline() {
+        assert_eq!(
+            Span::new(7, 10).underline("record foo 1"),
+            "record foo 1\n       ^^^"
+        );
+        assert_eq!(Span::new(6, 6).underline("record"), "record\n      ^");
+    }
+}
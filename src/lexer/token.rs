@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::span::Span;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TokenType {
     Literal,
@@ -26,6 +28,7 @@ pub enum Token {
     ParenOpen,
     ParenClose,
     Comma,
+    Asterisk,
 
     KeywordIntens,
     KeywordColor,
@@ -67,6 +70,8 @@ pub enum Token {
     KeywordOverride,
     KeywordSave,
     KeywordDelete,
+    KeywordPark,
+    KeywordUnpark,
     KeywordReally,
     KeywordNext,
     KeywordConfig,
@@ -76,6 +81,8 @@ pub enum Token {
     KeywordAssign,
     KeywordUnassign,
     KeywordGo,
+    KeywordGoto,
+    KeywordBack,
     KeywordStop,
     KeywordFlash,
     KeywordEffect,
@@ -85,6 +92,13 @@ pub enum Token {
     KeywordStomp,
     KeywordRecall,
     KeywordGrandmaster,
+    KeywordUndo,
+    KeywordRedo,
+    KeywordCopy,
+    KeywordMove,
+    KeywordRenumber,
+    KeywordStep,
+    KeywordAddress,
 
     KeywordNuzul,
     KeywordSueud,
@@ -107,6 +121,7 @@ impl Token {
             Token::ParenOpen => TokenType::Operator,
             Token::ParenClose => TokenType::Operator,
             Token::Comma => TokenType::Operator,
+            Token::Asterisk => TokenType::Operator,
 
             Token::KeywordIntens => TokenType::ChannelTypeKeyword,
             Token::KeywordColor => TokenType::ChannelTypeKeyword,
@@ -134,10 +149,18 @@ impl Token {
             Token::KeywordSueud => TokenType::ActionKeyword,
             Token::KeywordSave => TokenType::ActionKeyword,
             Token::KeywordDelete => TokenType::ActionKeyword,
+            Token::KeywordPark => TokenType::ActionKeyword,
+            Token::KeywordUnpark => TokenType::ActionKeyword,
             Token::KeywordConfig => TokenType::ActionKeyword,
             Token::KeywordAssign => TokenType::ActionKeyword,
             Token::KeywordUnassign => TokenType::ActionKeyword,
             Token::KeywordRecall => TokenType::ActionKeyword,
+            Token::KeywordGoto => TokenType::ActionKeyword,
+            Token::KeywordUndo => TokenType::ActionKeyword,
+            Token::KeywordRedo => TokenType::ActionKeyword,
+            Token::KeywordCopy => TokenType::ActionKeyword,
+            Token::KeywordMove => TokenType::ActionKeyword,
+            Token::KeywordRenumber => TokenType::ActionKeyword,
 
             Token::KeywordGroup => TokenType::ObjectKeyword,
             Token::KeywordMacro => TokenType::ObjectKeyword,
@@ -166,10 +189,13 @@ impl Token {
             Token::KeywordNext => TokenType::OtherKeyword,
             Token::KeywordOutput => TokenType::OtherKeyword,
             Token::KeywordGo => TokenType::OtherKeyword,
+            Token::KeywordBack => TokenType::OtherKeyword,
             Token::KeywordStop => TokenType::OtherKeyword,
             Token::KeywordFlash => TokenType::OtherKeyword,
             Token::KeywordTokens => TokenType::OtherKeyword,
             Token::KeywordStomp => TokenType::OtherKeyword,
+            Token::KeywordStep => TokenType::OtherKeyword,
+            Token::KeywordAddress => TokenType::OtherKeyword,
 
             Token::Eof => TokenType::Eof,
         }
@@ -195,6 +221,7 @@ impl std::fmt::Display for Token {
             Token::ParenOpen => write!(f, "("),
             Token::ParenClose => write!(f, ")"),
             Token::Comma => write!(f, ","),
+            Token::Asterisk => write!(f, "*"),
             Token::KeywordIntens => write!(f, "intes"),
             Token::KeywordThru => write!(f, "thru"),
             Token::KeywordFull => write!(f, "full"),
@@ -233,6 +260,8 @@ impl std::fmt::Display for Token {
             Token::KeywordSueud => write!(f, "sueud"),
             Token::KeywordSave => write!(f, "save"),
             Token::KeywordDelete => write!(f, "delete"),
+            Token::KeywordPark => write!(f, "park"),
+            Token::KeywordUnpark => write!(f, "unpark"),
             Token::KeywordReally => write!(f, "really"),
             Token::KeywordNext => write!(f, "next"),
             Token::KeywordConfig => write!(f, "config"),
@@ -245,6 +274,8 @@ impl std::fmt::Display for Token {
             Token::KeywordAssign => write!(f, "assign"),
             Token::KeywordUnassign => write!(f, "unassign"),
             Token::KeywordGo => write!(f, "go"),
+            Token::KeywordGoto => write!(f, "goto"),
+            Token::KeywordBack => write!(f, "back"),
             Token::KeywordStop => write!(f, "stop"),
             Token::KeywordFlash => write!(f, "flash"),
             Token::KeywordEffect => write!(f, "effect"),
@@ -254,7 +285,40 @@ impl std::fmt::Display for Token {
             Token::KeywordStomp => write!(f, "stomp"),
             Token::KeywordRecall => write!(f, "recall"),
             Token::KeywordGrandmaster => write!(f, "grandmaster"),
+            Token::KeywordUndo => write!(f, "undo"),
+            Token::KeywordRedo => write!(f, "redo"),
+            Token::KeywordCopy => write!(f, "copy"),
+            Token::KeywordMove => write!(f, "move"),
+            Token::KeywordRenumber => write!(f, "renumber"),
+            Token::KeywordStep => write!(f, "step"),
+            Token::KeywordAddress => write!(f, "address"),
             Token::Eof => write!(f, "Eof"),
         }
     }
 }
+
+/// Writes the tokens out separated by spaces, e.g. to show a command, that wasn't
+/// typed as a whole. Returns the text and the span of every token in it.
+pub fn tokens_to_text(tokens: &[Token]) -> (String, Vec<Span>) {
+    let mut text = String::new();
+
+    let spans = tokens
+        .iter()
+        .map(|token| {
+            if matches!(token, Token::Eof) {
+                return Span::new(text.len(), text.len());
+            }
+
+            if !text.is_empty() {
+                text.push(' ');
+            }
+
+            let start = text.len();
+            text.push_str(&token.to_string());
+
+            Span::new(start, text.len())
+        })
+        .collect::<Vec<_>>();
+
+    (text, spans)
+}
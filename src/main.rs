@@ -7,6 +7,7 @@ pub mod headless;
 pub mod input;
 pub mod lexer;
 pub mod parser;
+pub mod render;
 pub mod show;
 pub mod storage;
 
@@ -17,12 +18,15 @@ pub mod utils;
 
 use std::{path::PathBuf, sync::Arc, time};
 
+use fixture::gdtf::geometry::instantiate_template_channels;
 use gdtf::GdtfFile;
 use headless::{
     controller::DemexHeadlessConroller, id::DemexProtoDeviceId, node::DemexHeadlessNode,
+    repl::DemexRepl,
 };
 use itertools::Itertools;
 use parking_lot::RwLock;
+use render::{script::parse_render_script, DemexRenderConfig};
 use show::{context::ShowContext, DemexShow};
 
 use ui::utils::load::load_textures;
@@ -43,10 +47,38 @@ use clap::Parser;
 #[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
 enum DemexUiThemeAttribute {}
 
+#[derive(clap::Subcommand, Debug)]
+enum DemexCommand {
+    /// Render a show offline on a virtual clock and dump the DMX universes of every frame
+    Render {
+        /// Path to the show file to render
+        show: PathBuf,
+
+        /// Path to the script of timed commands to run (e.g. `t=2.5 "go executor 1"`)
+        #[arg(long)]
+        script: PathBuf,
+
+        /// Number of rendered frames per second
+        #[arg(long, default_value_t = TEST_MAX_DMX_FPS)]
+        fps: f64,
+
+        /// Rendered duration in seconds. Defaults to the time of the last command of the script.
+        #[arg(long)]
+        duration: Option<f64>,
+
+        /// File to write the frames to. Defaults to stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 /// demex - command based stage lighting control
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<DemexCommand>,
+
     /// Path to the show file to load
     #[arg(short, long, conflicts_with = "headless")]
     show: Option<PathBuf>,
@@ -63,6 +95,14 @@ struct Args {
     #[arg(long, value_name = "IP_ADDRESS")]
     headless: Option<String>,
 
+    /// Run the controller without UI and read commands from stdin (or a local TCP port, see `--repl-port`).
+    #[arg(long, conflicts_with = "headless")]
+    no_ui: bool,
+
+    /// Read commands from connections on this local TCP port (e.g. using telnet) instead of stdin. This is only used in no-ui mode.
+    #[arg(long, value_name = "PORT", requires = "no_ui")]
+    repl_port: Option<u16>,
+
     /// Set a manual node id for the headless node.
     #[arg(long, value_name = "ID")]
     headless_id: Option<u32>,
@@ -138,17 +178,59 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .join(", ")
     );
 
+    let fixture_types = fixture_files
+        .into_iter()
+        .flat_map(|file| file.description.fixture_types)
+        .map(|mut fixture_type| {
+            instantiate_template_channels(&mut fixture_type);
+            fixture_type
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(DemexCommand::Render {
+        show,
+        script,
+        fps,
+        duration,
+        output,
+    }) = args.command
+    {
+        log::info!("Rendering show file: {:?}", show);
+
+        let show = DemexShow::load(&show)?;
+        let script = parse_render_script(&std::fs::read_to_string(script)?)?;
+        let config = DemexRenderConfig { fps, duration };
+
+        match output {
+            Some(output) => render::render_show(
+                fixture_types,
+                show,
+                &script,
+                &config,
+                &mut std::io::BufWriter::new(std::fs::File::create(output)?),
+            )?,
+            None => render::render_show(
+                fixture_types,
+                show,
+                &script,
+                &config,
+                &mut std::io::stdout().lock(),
+            )?,
+        }
+
+        return Ok(());
+    }
+
     let show: DemexShow = args
         .show
         .as_ref()
         .inspect(|show_path| log::info!("Loading show file: {:?}", show_path))
-        .map(|show_path| serde_json::from_reader(std::fs::File::open(show_path).unwrap()).unwrap())
-        .unwrap_or(DemexShow::default());
+        .map(|show_path| DemexShow::load(show_path.as_path()))
+        .transpose()?
+        .unwrap_or_default();
 
-    let fixture_types = fixture_files
-        .into_iter()
-        .flat_map(|file| file.description.fixture_types)
-        .collect::<Vec<_>>();
+    // the repl keeps the loaded show, so it can save data, that isn't part of the show context
+    let repl_show = args.no_ui.then(|| show.clone());
 
     let stats = Arc::new(RwLock::new(DemexThreadStatsHandler::default()));
     let context = ShowContext::new(
@@ -157,6 +239,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         show.preset_handler,
         show.updatable_handler,
         show.timing_handler,
+        show.park_handler,
         if args.headless.is_some() {
             DemexProtoDeviceId::Node(args.headless_id.unwrap_or_default())
         } else {
@@ -211,6 +294,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let timing_handler = timing_handler_thread_b.read();
             let patch = patch_thread_b.read();
 
+            updatable_handler.update_pixel_maps(
+                patch.fixture_types(),
+                patch.layout(),
+                &mut fixture_handler,
+            );
+
             let _ = fixture_handler
                 .update_output_values(
                     patch.fixture_types(),
@@ -236,82 +325,97 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             context.clone(),
         )?;
     } else {
-        DemexHeadlessConroller::default().start_controller_thread(stats.clone(), context.clone());
-
-        #[cfg(feature = "ui")]
-        {
-            let icon = Arc::new(load_icon());
-
-            let options = eframe::NativeOptions {
-                viewport: eframe::egui::ViewportBuilder::default()
-                    .with_maximized(true)
-                    .with_icon(icon.clone()),
-                ..Default::default()
-            };
-
-            eframe::run_native(
-                APP_ID,
-                options,
-                Box::new(|creation_context| {
-                    egui_extras::install_image_loaders(&creation_context.egui_ctx);
-
-                    let style = egui::Style {
-                        visuals: egui::Visuals::dark(),
-                        ..egui::Style::default()
-                    };
-
-                    creation_context.egui_ctx.set_style(style);
-                    creation_context
-                        .egui_ctx
-                        .set_fonts(ui::utils::load::load_fonts());
-
-                    args.ui_theme
-                        .map(DemexUiTheme::from)
-                        .unwrap_or(DemexUiTheme::Default)
-                        .apply(&creation_context.egui_ctx);
-
-                    if args.touchscreen_mode {
-                        creation_context.egui_ctx.style_mut(|style| {
-                            style.spacing.button_padding = emath::vec2(10.0, 10.0);
-
-                            style.spacing.indent = 18.0 * 2.0;
-                            style.spacing.icon_width = 14.0 * 2.0;
-                            style.spacing.icon_width_inner = 8.0 * 2.0;
-
-                            // DEFAULT: style.spacing.interact_size = [40.0, 18.0];
-                            //
-                            style.spacing.interact_size = emath::vec2(40.0, 18.0) * 1.5;
-                            style.spacing.slider_rail_height = 8.0 * 2.0;
-                            style.spacing.slider_width = 100.0 * 1.5;
-                        });
-                    }
-
-                    let ui_app_state = DemexUiApp::new(
-                        DemexUiContext::load_show(
-                            &context,
-                            show.input_device_configs,
-                            show.ui_config,
-                            args.show,
-                            stats,
-                            load_textures(&creation_context.egui_ctx),
-                        ),
-                        TEST_UI_FPS,
-                        icon,
-                        false,
-                        args.additional_viewports,
-                    );
-
-                    Ok(Box::new(ui_app_state))
-                }),
-            )?;
-        }
+        let headless_controller = DemexHeadlessConroller::default();
+        headless_controller.start_controller_thread(stats.clone(), context.clone());
+
+        if let Some(repl_show) = repl_show {
+            log::info!("Running in no-ui mode, reading commands..");
+            DemexRepl::new(
+                context.clone(),
+                headless_controller,
+                repl_show,
+                args.show.clone(),
+            )
+            .start_in_current_thread(args.repl_port)?;
+        } else {
+            #[cfg(feature = "ui")]
+            {
+                let icon = Arc::new(load_icon());
+
+                let options = eframe::NativeOptions {
+                    viewport: eframe::egui::ViewportBuilder::default()
+                        .with_maximized(true)
+                        .with_icon(icon.clone()),
+                    ..Default::default()
+                };
+
+                eframe::run_native(
+                    APP_ID,
+                    options,
+                    Box::new(|creation_context| {
+                        egui_extras::install_image_loaders(&creation_context.egui_ctx);
+
+                        let style = egui::Style {
+                            visuals: egui::Visuals::dark(),
+                            ..egui::Style::default()
+                        };
+
+                        creation_context.egui_ctx.set_style(style);
+                        creation_context
+                            .egui_ctx
+                            .set_fonts(ui::utils::load::load_fonts());
+
+                        args.ui_theme
+                            .map(DemexUiTheme::from)
+                            .unwrap_or(DemexUiTheme::Default)
+                            .apply(&creation_context.egui_ctx);
+
+                        if args.touchscreen_mode {
+                            creation_context.egui_ctx.style_mut(|style| {
+                                style.spacing.button_padding = emath::vec2(10.0, 10.0);
+
+                                style.spacing.indent = 18.0 * 2.0;
+                                style.spacing.icon_width = 14.0 * 2.0;
+                                style.spacing.icon_width_inner = 8.0 * 2.0;
+
+                                // DEFAULT: style.spacing.interact_size = [40.0, 18.0];
+                                //
+                                style.spacing.interact_size = emath::vec2(40.0, 18.0) * 1.5;
+                                style.spacing.slider_rail_height = 8.0 * 2.0;
+                                style.spacing.slider_width = 100.0 * 1.5;
+                            });
+                        }
+
+                        let ui_app_state = DemexUiApp::new(
+                            DemexUiContext::load_show(
+                                &context,
+                                show.input_device_configs,
+                                show.ui_config,
+                                args.show,
+                                stats,
+                                load_textures(&creation_context.egui_ctx),
+                                headless_controller,
+                            ),
+                            TEST_UI_FPS,
+                            icon,
+                            false,
+                            args.additional_viewports,
+                        );
+
+                        Ok(Box::new(ui_app_state))
+                    }),
+                )?;
+            }
 
-        #[cfg(not(feature = "ui"))]
-        {
-            log::error!("UI feature is not enabled. Please enable the UI feature to run the application with a user interface or run in headless mode.");
-            std::process::exit(1);
+            #[cfg(not(feature = "ui"))]
+            {
+                log::error!("UI feature is not enabled. Please enable the UI feature to run the application with a user interface, run in headless mode or in no-ui mode.");
+                std::process::exit(1);
+            }
         }
     }
 
+    context.fixture_handler.write().shutdown_outputs();
+
     Ok(())
 }
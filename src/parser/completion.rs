@@ -0,0 +1,142 @@
+use std::fmt;
+
+use crate::lexer::{keyword_token, token::Token, KEYWORDS};
+
+use super::Parser2;
+
+/// Something, that can be entered next on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandHint {
+    Keyword(&'static str),
+    Operator(&'static str),
+
+    /// An argument of the given type, e.g. an id or a name
+    Argument(&'static str),
+}
+
+impl fmt::Display for CommandHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keyword(keyword) => write!(f, "{}", keyword),
+            Self::Operator(operator) => write!(f, "{}", operator),
+            Self::Argument(argument) => write!(f, "<{}>", argument),
+        }
+    }
+}
+
+/// Every hint together with a token, that stands in for it while parsing
+fn candidates() -> impl Iterator<Item = (CommandHint, Token)> {
+    let operators = [
+        ("+", Token::Plus),
+        ("-", Token::Minus),
+        ("%", Token::Percent),
+        ("!", Token::Exclamation),
+        ("(", Token::ParenOpen),
+        (")", Token::ParenClose),
+        (",", Token::Comma),
+        ("*", Token::Asterisk),
+        ("~", Token::KeywordFixturesSelected),
+    ];
+
+    let arguments = [
+        ("integer", Token::Integer(1)),
+        ("float", Token::FloatingPoint(1.1, (1, 1))),
+        ("string", Token::String(String::new())),
+    ];
+
+    KEYWORDS
+        .iter()
+        .copied()
+        .filter_map(|keyword| {
+            keyword_token(keyword).map(|token| (CommandHint::Keyword(keyword), token))
+        })
+        .chain(
+            operators
+                .into_iter()
+                .map(|(operator, token)| (CommandHint::Operator(operator), token)),
+        )
+        .chain(
+            arguments
+                .into_iter()
+                .map(|(argument, token)| (CommandHint::Argument(argument), token)),
+        )
+}
+
+fn terminated(tokens: &[Token], next: Option<Token>) -> Vec<Token> {
+    tokens
+        .iter()
+        .filter(|token| !matches!(token, Token::Eof))
+        .cloned()
+        .chain(next)
+        .chain([Token::Eof])
+        .collect()
+}
+
+impl Parser2<'_> {
+    /// Index of the first token, that can't be part of a valid command.
+    /// `None`, if the tokens are a complete command or the beginning of one.
+    pub fn invalid_token_idx(tokens: &[Token]) -> Option<usize> {
+        let tokens = terminated(tokens, None);
+        let mut parser = Parser2::new(&tokens);
+
+        if parser.parse().is_ok() {
+            return None;
+        }
+
+        // the last token is Eof, reaching it only means, that the command is incomplete
+        (parser.furthest_token_idx < tokens.len() - 1).then_some(parser.furthest_token_idx)
+    }
+
+    /// Everything, that can follow the tokens. A candidate is accepted, if at least
+    /// one branch of the grammar consumes it.
+    pub fn hints(tokens: &[Token]) -> Vec<CommandHint> {
+        if Self::invalid_token_idx(tokens).is_some() {
+            return Vec::new();
+        }
+
+        candidates()
+            .filter(|(_, candidate)| {
+                let tokens = terminated(tokens, Some(candidate.clone()));
+                let candidate_idx = tokens.len() - 2;
+
+                let mut parser = Parser2::new(&tokens);
+                parser.parse().is_ok() || parser.furthest_token_idx > candidate_idx
+            })
+            .map(|(hint, _)| hint)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::Lexer;
+
+    use super::*;
+
+    #[test]
+    fn test_hints() {
+        let tokens = Lexer::new("record").tokenize().unwrap();
+        let hints = Parser2::hints(&tokens);
+
+        assert!(hints.contains(&CommandHint::Keyword("preset")));
+        assert!(hints.contains(&CommandHint::Keyword("group")));
+        assert!(!hints.contains(&CommandHint::Keyword("thru")));
+
+        let tokens = Lexer::new("1").tokenize().unwrap();
+        let hints = Parser2::hints(&tokens);
+
+        assert!(hints.contains(&CommandHint::Keyword("thru")));
+        assert!(hints.contains(&CommandHint::Operator("+")));
+        assert!(hints.contains(&CommandHint::Keyword("intens")));
+    }
+
+    #[test]
+    fn test_invalid_token_idx() {
+        let tokens = Lexer::new("1 record").tokenize().unwrap();
+        assert_eq!(Parser2::invalid_token_idx(&tokens), Some(1));
+
+        // incomplete, but valid so far
+        let tokens = Lexer::new("record preset 1.1 for").tokenize().unwrap();
+        assert_eq!(Parser2::invalid_token_idx(&tokens), None);
+    }
+}
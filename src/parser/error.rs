@@ -1,20 +1,40 @@
 use itertools::Itertools;
 
-use crate::lexer::token::Token;
+use crate::lexer::{span::Span, token::Token};
 
 use super::nodes::object::{Object, ObjectError};
 
 #[derive(Debug)]
 pub enum ParseError {
     UnexpectedVariant(Vec<(String, ParseError)>),
-    UnexpectedToken(Token, String),
-    UnexpectedTokenAlternatives(Token, Vec<&'static str>),
+    UnexpectedToken(Token, Span, String),
+    UnexpectedTokenAlternatives(Token, Span, Vec<&'static str>),
     UnknownAction(String),
     NoDefaultActionForObject(Object),
     ObjectError(ObjectError),
-    UnexpectedEndOfInput,
+    UnexpectedEndOfInput(Span),
 
-    UnexpectedArgs(String),
+    UnexpectedArgs(String, Option<Span>),
+}
+
+impl ParseError {
+    /// The span of the offending token. If multiple variants failed,
+    /// the one, that got the furthest, is used.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ParseError::UnexpectedVariant(variants) => variants
+                .iter()
+                .filter_map(|(_, err)| err.span())
+                .max_by_key(|span| span.start),
+            ParseError::UnexpectedToken(_, span, _)
+            | ParseError::UnexpectedTokenAlternatives(_, span, _)
+            | ParseError::UnexpectedEndOfInput(span) => Some(*span),
+            ParseError::UnexpectedArgs(_, span) => *span,
+            ParseError::UnknownAction(_)
+            | ParseError::NoDefaultActionForObject(_)
+            | ParseError::ObjectError(_) => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ParseError {
@@ -36,8 +56,8 @@ impl std::fmt::Display for ParseError {
                         .join("\n\t")
                 )
             }
-            ParseError::UnexpectedToken(t, e) => write!(f, "Unexpected token: {:?} ({})", t, e),
-            ParseError::UnexpectedTokenAlternatives(t, e) => {
+            ParseError::UnexpectedToken(t, _, e) => write!(f, "Unexpected token: {:?} ({})", t, e),
+            ParseError::UnexpectedTokenAlternatives(t, _, e) => {
                 write!(
                     f,
                     "Unexpected token: {:?} (expected {})",
@@ -46,13 +66,13 @@ impl std::fmt::Display for ParseError {
                 )
             }
             ParseError::UnknownAction(a) => write!(f, "Unknown action: {}", a),
-            ParseError::UnexpectedEndOfInput => write!(f, "Unexpected end of input"),
+            ParseError::UnexpectedEndOfInput(_) => write!(f, "Unexpected end of input"),
             ParseError::NoDefaultActionForObject(o) => {
                 write!(f, "No default action for object: {:?}", o)
             }
             ParseError::ObjectError(e) => write!(f, "Object error: {}", e),
 
-            ParseError::UnexpectedArgs(e) => write!(f, "Unexpected args: {}", e),
+            ParseError::UnexpectedArgs(e, _) => write!(f, "Unexpected args: {}", e),
         }
     }
 }
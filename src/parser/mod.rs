@@ -2,10 +2,13 @@ use nodes::{
     action::{
         functions::{
             assign_function::{AssignButtonArgs, AssignButtonArgsMode, AssignFaderArgs},
+            copy_function::{CopyArgs, CopyMode, CopyObjects, RenumberSequenceCuesArgs},
             create_function::{
                 CreateEffectPresetArgs, CreateExecutorArgs, CreateMacroArgs, CreateSequenceArgs,
             },
             delete_function::DeleteArgs,
+            go_function::{ExecutorGoBackArgs, ExecutorGotoCueArgs},
+            park_function::{ParkArgs, ParkObject, UnparkArgs},
             recall_function::RecallSequenceCueArgs,
             record_function::{
                 RecordChannelTypeSelector, RecordGroupArgs, RecordPresetArgs,
@@ -13,7 +16,9 @@ use nodes::{
                 RecordSequenceCueShorthandArgsId,
             },
             rename_function::RenameObjectArgs,
-            set_function::{SelectionOrSelector, SetFeatureValueArgs, SetFixturePresetArgs},
+            set_function::{
+                FeatureValue, SelectionOrSelector, SetFeatureValueArgs, SetFixturePresetArgs,
+            },
             update_function::{UpdateMode, UpdatePresetArgs, UpdateSequenceCueArgs},
         },
         ConfigTypeActionData, ValueOrRange,
@@ -26,7 +31,10 @@ use crate::{
         channel3::feature::feature_type::FixtureChannel3FeatureType,
         presets::preset::FixturePresetId, sequence::cue::CueIdx,
     },
-    lexer::token::Token,
+    lexer::{
+        span::Span,
+        token::{tokens_to_text, Token},
+    },
     parser::nodes::action::functions::assign_function::AssignFaderArgsMode,
 };
 
@@ -39,6 +47,7 @@ use self::{
     },
 };
 
+pub mod completion;
 pub mod error;
 pub mod nodes;
 
@@ -48,6 +57,7 @@ macro_rules! expect_and_consume_token {
             $pattern $(if $guard)? => $self.advance(),
             unexpected_token => return Err(ParseError::UnexpectedToken(
                 unexpected_token.clone(),
+                $self.current_span(),
                 format!("Expected {}", $expected)
             )),
         }
@@ -56,20 +66,50 @@ macro_rules! expect_and_consume_token {
 
 pub struct Parser2<'a> {
     tokens: &'a Vec<Token>,
+    spans: Vec<Span>,
     current_token_idx: usize,
+
+    /// Number of tokens, that were consumed by at least one branch,
+    /// even if it was backtracked later on
+    furthest_token_idx: usize,
 }
 
 impl<'a> Parser2<'a> {
     pub fn new(tokens: &'a Vec<Token>) -> Self {
         Self {
             tokens,
+            spans: tokens_to_text(tokens).1,
             current_token_idx: 0,
+            furthest_token_idx: 0,
         }
     }
 
+    /// Uses the spans of the tokens in the original input (see `Lexer::spans`),
+    /// instead of their positions in the tokens written out.
+    pub fn with_spans(mut self, spans: &[Span]) -> Self {
+        self.spans = spans.to_vec();
+        self
+    }
+
+    fn span(&self, token_idx: usize) -> Span {
+        self.spans
+            .get(token_idx)
+            .or(self.spans.last())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn current_span(&self) -> Span {
+        self.span(self.current_token_idx)
+    }
+
+    fn previous_span(&self) -> Span {
+        self.span(self.current_token_idx.saturating_sub(1))
+    }
+
     fn current_token(&self) -> Result<&Token, ParseError> {
         if !self.has_current_token() {
-            Err(ParseError::UnexpectedEndOfInput)
+            Err(ParseError::UnexpectedEndOfInput(self.current_span()))
         } else {
             Ok(&self.tokens[self.current_token_idx])
         }
@@ -85,6 +125,7 @@ impl<'a> Parser2<'a> {
 
     fn advance(&mut self) {
         self.current_token_idx += 1;
+        self.furthest_token_idx = self.furthest_token_idx.max(self.current_token_idx);
     }
 
     fn try_parse<T>(
@@ -120,6 +161,7 @@ impl<'a> Parser2<'a> {
                             }
                             unexpectd_token => Err(ParseError::UnexpectedToken(
                                 unexpectd_token.clone(),
+                                self.current_span(),
                                 "Expected integer".to_owned(),
                             )),
                         }
@@ -127,6 +169,40 @@ impl<'a> Parser2<'a> {
                     _ => Ok(AtomicFixtureSelector::SingleFixture(f1)),
                 }
             }
+            &Token::FloatingPoint(_, (f1, c1)) => {
+                self.advance();
+                let c1 = self.cell_from_u32(c1)?;
+
+                match self.current_token()? {
+                    &Token::KeywordThru => {
+                        self.advance();
+                        match self.current_token()? {
+                            &Token::FloatingPoint(_, (f2, c2)) if f2 == f1 => {
+                                self.advance();
+                                let c2 = self.cell_from_u32(c2)?;
+
+                                if c1 > c2 {
+                                    return Err(ParseError::UnexpectedArgs(
+                                        format!(
+                                            "Invalid cell range {}.{} thru {}.{}",
+                                            f1, c1, f2, c2
+                                        ),
+                                        Some(self.previous_span()),
+                                    ));
+                                }
+
+                                Ok(AtomicFixtureSelector::SubFixtureRange(f1, c1, c2))
+                            }
+                            unexpectd_token => Err(ParseError::UnexpectedToken(
+                                unexpectd_token.clone(),
+                                self.current_span(),
+                                format!("Expected cell of fixture {}", f1),
+                            )),
+                        }
+                    }
+                    _ => Ok(AtomicFixtureSelector::SubFixture(f1, c1)),
+                }
+            }
             Token::ParenOpen => {
                 self.advance();
                 let fixture_selector = self.parse_fixture_selector()?;
@@ -140,6 +216,7 @@ impl<'a> Parser2<'a> {
                     }
                     unexpected_token => Err(ParseError::UnexpectedToken(
                         unexpected_token.clone(),
+                        self.current_span(),
                         "Expected closing parenthesis".to_string(),
                     )),
                 }
@@ -154,17 +231,28 @@ impl<'a> Parser2<'a> {
                     }
                     unexpected_token => Err(ParseError::UnexpectedToken(
                         unexpected_token.clone(),
+                        self.current_span(),
                         "Expected integer".to_string(),
                     )),
                 }
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
-                vec!["\"~\"", "integer", "\"(\"", "\"group\""],
+                self.current_span(),
+                vec!["\"~\"", "integer", "cell", "\"(\"", "\"group\""],
             )),
         }
     }
 
+    fn cell_from_u32(&self, cell: u32) -> Result<u16, ParseError> {
+        u16::try_from(cell).map_err(|_| {
+            ParseError::UnexpectedArgs(
+                format!("Cell {} is out of range", cell),
+                Some(self.previous_span()),
+            )
+        })
+    }
+
     pub fn parse_fixture_selector(&mut self) -> Result<FixtureSelector, ParseError> {
         let atomic_selector = self.parse_atomic_fixture_selector()?;
 
@@ -204,6 +292,7 @@ impl<'a> Parser2<'a> {
                     }
                     unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                         unexpected_token.clone(),
+                        self.current_span(),
                         vec!["integer", "\"!\""],
                     )),
                 }
@@ -234,6 +323,7 @@ impl<'a> Parser2<'a> {
 
         Err(ParseError::UnexpectedToken(
             self.current_token()?.clone(),
+            self.current_span(),
             "Expected homeable object".to_string(),
         ))
     }
@@ -293,6 +383,7 @@ impl<'a> Parser2<'a> {
 
         Err(ParseError::UnexpectedToken(
             self.current_token()?.clone(),
+            self.current_span(),
             "Expected object".to_string(),
         ))
     }
@@ -308,16 +399,31 @@ impl<'a> Parser2<'a> {
     }
 
     fn parse_discrete_feature_type(&mut self) -> Result<FixtureChannel3FeatureType, ParseError> {
-        match self.current_token()? {
-            &Token::KeywordIntens => {
-                self.advance();
-                Ok(FixtureChannel3FeatureType::Dimmer)
+        let feature_type = match self.current_token()? {
+            Token::KeywordIntens => FixtureChannel3FeatureType::Dimmer,
+            Token::KeywordPosition => FixtureChannel3FeatureType::PanTilt,
+            Token::KeywordColor => FixtureChannel3FeatureType::Rgb,
+            Token::KeywordBeam => FixtureChannel3FeatureType::Beam,
+            Token::KeywordFocus => FixtureChannel3FeatureType::Focus,
+            Token::KeywordControl => FixtureChannel3FeatureType::Control,
+            unexpected_token => {
+                return Err(ParseError::UnexpectedTokenAlternatives(
+                    unexpected_token.clone(),
+                    self.current_span(),
+                    vec![
+                        "\"intens\"",
+                        "\"position\"",
+                        "\"color\"",
+                        "\"beam\"",
+                        "\"focus\"",
+                        "\"control\"",
+                    ],
+                ))
             }
-            unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
-                unexpected_token.clone(),
-                vec!["\"intens\""],
-            )),
-        }
+        };
+
+        self.advance();
+        Ok(feature_type)
     }
 
     fn parse_feature_type(&mut self) -> Result<FixtureChannel3FeatureType, ParseError> {
@@ -385,27 +491,81 @@ impl<'a> Parser2<'a> {
                 self.advance();
                 Ok(0.0)
             }
+            // floating point values are fractions, so `1.5` can't be mistaken for a percentage
             &Token::FloatingPoint(value, _) => {
+                if !(0.0..=1.0).contains(&value) {
+                    return Err(ParseError::UnexpectedToken(
+                        self.current_token()?.clone(),
+                        self.current_span(),
+                        "floating point values must be between 0 and 1, use an integer for percentages".to_owned(),
+                    ));
+                }
+
                 self.advance();
                 Ok(value)
             }
+            // integers are percentages
+            &Token::Integer(value) => {
+                self.advance();
+                Ok(value as f32 / 100.0)
+            }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
-                vec!["\"full\"", "\"half\"", "\"out\"", "floating point value"],
+                self.current_span(),
+                vec![
+                    "\"full\"",
+                    "\"half\"",
+                    "\"out\"",
+                    "percentage",
+                    "floating point value",
+                ],
             )),
         }
     }
 
-    fn parse_channel_value_single(&mut self) -> Result<ValueOrRange<f32>, ParseError> {
-        let value_a = self.parse_discrete_channel_value_single()?;
+    fn parse_feature_value(&mut self) -> Result<FeatureValue, ParseError> {
+        match self.current_token()? {
+            Token::Plus => {
+                self.advance();
+                Ok(FeatureValue::Relative(
+                    self.parse_discrete_channel_value_single()?,
+                ))
+            }
+            Token::Minus => {
+                self.advance();
+                Ok(FeatureValue::Relative(
+                    -self.parse_discrete_channel_value_single()?,
+                ))
+            }
+            Token::Asterisk => {
+                self.advance();
 
-        if matches!(self.current_token()?, Token::KeywordThru) {
-            self.advance();
-            let value_b = self.parse_discrete_channel_value_single()?;
+                match self.current_token()? {
+                    &Token::Integer(factor) => {
+                        self.advance();
+                        Ok(FeatureValue::Scale(factor as f32))
+                    }
+                    &Token::FloatingPoint(factor, _) => {
+                        self.advance();
+                        Ok(FeatureValue::Scale(factor))
+                    }
+                    unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
+                        unexpected_token.clone(),
+                        self.current_span(),
+                        vec!["integer", "floating point value"],
+                    )),
+                }
+            }
+            _ => {
+                let mut values = vec![self.parse_discrete_channel_value_single()?];
 
-            Ok(ValueOrRange::Thru(value_a, value_b))
-        } else {
-            Ok(ValueOrRange::Single(value_a))
+                while matches!(self.current_token()?, Token::KeywordThru) {
+                    self.advance();
+                    values.push(self.parse_discrete_channel_value_single()?);
+                }
+
+                Ok(FeatureValue::Fan(values))
+            }
         }
     }
 
@@ -421,13 +581,13 @@ impl<'a> Parser2<'a> {
             }));
         }
 
-        let feature_type = self.parse_feature_type()?;
+        let attributes = self.parse_attributes()?;
 
-        let feature_value = self.try_parse(Self::parse_channel_value_single);
+        let feature_value = self.try_parse(Self::parse_feature_value);
         if let Ok(feature_value) = feature_value {
             return Ok(Action::SetFeatureValue(SetFeatureValueArgs {
                 fixture_selector,
-                feature_type,
+                attributes,
                 feature_value,
             }));
         }
@@ -437,10 +597,7 @@ impl<'a> Parser2<'a> {
                 "\"preset\" <channel_type> <id>".to_owned(),
                 preset.err().unwrap(),
             ),
-            (
-                "discrete channel value".to_owned(),
-                feature_value.err().unwrap(),
-            ),
+            ("<value>".to_owned(), feature_value.err().unwrap()),
         ]))
     }
 
@@ -452,6 +609,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedToken(
                 unexpected_token.clone(),
+                self.current_span(),
                 "Expected string".to_string(),
             )),
         }
@@ -482,6 +640,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedToken(
                 unexpected_token.clone(),
+                self.current_span(),
                 "Expected integer".to_string(),
             )),
         }
@@ -495,6 +654,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedToken(
                 unexpected_token.clone(),
+                self.current_span(),
                 "Expected float".to_string(),
             )),
         }
@@ -525,6 +685,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["integer", "\"next\""],
             )),
         }
@@ -542,6 +703,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["cue index (a / a.b)", "\"next\""],
             )),
         }
@@ -553,13 +715,17 @@ impl<'a> Parser2<'a> {
                 self.advance();
                 Ok(FixturePresetId {
                     feature_group: feature_group_id.try_into().map_err(|_| {
-                        ParseError::UnexpectedArgs("Expected feature group id".to_owned())
+                        ParseError::UnexpectedArgs(
+                            "Expected feature group id".to_owned(),
+                            Some(self.previous_span()),
+                        )
                     })?,
                     preset_id,
                 })
             }
             unexpected_token => Err(ParseError::UnexpectedToken(
                 unexpected_token.clone(),
+                self.current_span(),
                 "Expected preset id (feature_group_id.preset_id)".to_string(),
             )),
         }
@@ -578,6 +744,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["cue index (a / a.b)", "\"next\""],
             )),
         }
@@ -595,6 +762,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["\"merge\"", "\"override\""],
             )),
         }
@@ -715,6 +883,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["\"preset\"", "\"group\"", "\"sequence\"", "\"executor\""],
             )),
         }
@@ -778,13 +947,17 @@ impl<'a> Parser2<'a> {
                 self.advance();
 
                 let (feature_group_id, preset_id) = self.parse_float_individual()?;
+                let id_span = self.previous_span();
 
                 let name = self.try_parse(Self::parse_as).ok();
 
                 Ok(Action::CreateEffectPreset(CreateEffectPresetArgs {
                     id: FixturePresetId {
                         feature_group: feature_group_id.try_into().map_err(|_| {
-                            ParseError::UnexpectedArgs("Expected feature group id".to_owned())
+                            ParseError::UnexpectedArgs(
+                                "Expected feature group id".to_owned(),
+                                Some(id_span),
+                            )
                         })?,
                         preset_id,
                     },
@@ -793,6 +966,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["\"sequence\"", "\"executor\"", "\"macro\"", "\"fader\""],
             )),
         }
@@ -856,6 +1030,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["\"preset\"", "\"sequence\""],
             )),
         }
@@ -908,7 +1083,7 @@ impl<'a> Parser2<'a> {
                 Ok(Action::DeleteGroup(group_id_range))
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
-                unexpected_token.clone(),
+                unexpected_token.clone(), self.current_span(),
                 vec![
                     "\"macro\"",
                     "\"preset\"",
@@ -927,6 +1102,264 @@ impl<'a> Parser2<'a> {
         Ok(Action::Delete(DeleteArgs { object_range }))
     }
 
+    /// Consumes the keyword, if it's the current token
+    fn skip_optional_keyword(&mut self, keyword: Token) -> Result<(), ParseError> {
+        if std::mem::discriminant(self.current_token()?) == std::mem::discriminant(&keyword) {
+            self.advance();
+        }
+
+        Ok(())
+    }
+
+    fn parse_copy_objects(&mut self) -> Result<CopyObjects, ParseError> {
+        match self.current_token()? {
+            Token::KeywordPreset => {
+                self.advance();
+
+                let from = self.parse_preset_id()?;
+                let to = if matches!(self.current_token()?, Token::KeywordThru) {
+                    self.advance();
+                    self.skip_optional_keyword(Token::KeywordPreset)?;
+                    self.parse_preset_id()?
+                } else {
+                    from
+                };
+
+                expect_and_consume_token!(self, Token::KeywordTo, "\"to\"");
+                self.skip_optional_keyword(Token::KeywordPreset)?;
+
+                let target = self.parse_preset_id()?;
+
+                Ok(CopyObjects::Presets { from, to, target })
+            }
+            Token::KeywordGroup => {
+                self.advance();
+
+                let from = self.parse_integer()?;
+                let to = if matches!(self.current_token()?, Token::KeywordThru) {
+                    self.advance();
+                    self.skip_optional_keyword(Token::KeywordGroup)?;
+                    self.parse_integer()?
+                } else {
+                    from
+                };
+
+                expect_and_consume_token!(self, Token::KeywordTo, "\"to\"");
+                self.skip_optional_keyword(Token::KeywordGroup)?;
+
+                let target = self.parse_integer()?;
+
+                Ok(CopyObjects::Groups { from, to, target })
+            }
+            Token::KeywordSequence => {
+                self.advance();
+
+                let sequence_id = self.parse_integer()?;
+
+                if matches!(self.current_token()?, Token::KeywordCue) {
+                    self.advance();
+
+                    let from = self.parse_discrete_cue_idx()?;
+                    let to = if matches!(self.current_token()?, Token::KeywordThru) {
+                        self.advance();
+                        self.skip_optional_keyword(Token::KeywordCue)?;
+                        self.parse_discrete_cue_idx()?
+                    } else {
+                        from
+                    };
+
+                    expect_and_consume_token!(self, Token::KeywordTo, "\"to\"");
+
+                    // without a sequence, the cues are copied within the same sequence
+                    let target_sequence_id =
+                        if matches!(self.current_token()?, Token::KeywordSequence) {
+                            self.advance();
+                            self.parse_integer()?
+                        } else {
+                            sequence_id
+                        };
+
+                    expect_and_consume_token!(self, Token::KeywordCue, "\"cue\"");
+
+                    let target = self.parse_discrete_cue_idx()?;
+
+                    return Ok(CopyObjects::SequenceCues {
+                        sequence_id,
+                        from,
+                        to,
+                        target_sequence_id,
+                        target,
+                    });
+                }
+
+                let to = if matches!(self.current_token()?, Token::KeywordThru) {
+                    self.advance();
+                    self.skip_optional_keyword(Token::KeywordSequence)?;
+                    self.parse_integer()?
+                } else {
+                    sequence_id
+                };
+
+                expect_and_consume_token!(self, Token::KeywordTo, "\"to\"");
+                self.skip_optional_keyword(Token::KeywordSequence)?;
+
+                let target = self.parse_integer()?;
+
+                Ok(CopyObjects::Sequences {
+                    from: sequence_id,
+                    to,
+                    target,
+                })
+            }
+            unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
+                unexpected_token.clone(),
+                self.current_span(),
+                vec!["\"preset\"", "\"group\"", "\"sequence\""],
+            )),
+        }
+    }
+
+    fn parse_copy_function(&mut self, mode: CopyMode) -> Result<Action, ParseError> {
+        let objects = self.parse_copy_objects()?;
+        let collision = self.try_parse(Self::parse_update_mode).ok();
+
+        Ok(Action::Copy(CopyArgs {
+            mode,
+            objects,
+            collision,
+        }))
+    }
+
+    fn parse_renumber_function(&mut self) -> Result<Action, ParseError> {
+        expect_and_consume_token!(self, Token::KeywordSequence, "\"sequence\"");
+
+        let sequence_id = self.parse_integer()?;
+
+        expect_and_consume_token!(self, Token::KeywordCue, "\"cue\"");
+
+        let from = self.parse_discrete_cue_idx()?;
+        let to = if matches!(self.current_token()?, Token::KeywordThru) {
+            self.advance();
+            self.parse_discrete_cue_idx()?
+        } else {
+            from
+        };
+
+        let step = if matches!(self.current_token()?, Token::KeywordStep) {
+            self.advance();
+
+            let step = self.parse_integer()?;
+            if step == 0 {
+                return Err(ParseError::UnexpectedArgs(
+                    "Step must be greater than 0".to_owned(),
+                    Some(self.previous_span()),
+                ));
+            }
+
+            step
+        } else {
+            1
+        };
+
+        Ok(Action::RenumberSequenceCues(RenumberSequenceCuesArgs {
+            sequence_id,
+            from,
+            to,
+            step,
+        }))
+    }
+
+    // a single attribute (e.g. `"Pan"`) or all attributes of a feature
+    fn parse_attributes(&mut self) -> Result<Vec<String>, ParseError> {
+        if let Token::String(attribute) = self.current_token()?.clone() {
+            self.advance();
+            return Ok(vec![attribute]);
+        }
+
+        let feature_type = self.parse_feature_type()?;
+
+        Ok(feature_type
+            .attributes()
+            .iter()
+            .map(|attribute| attribute.to_string())
+            .collect())
+    }
+
+    fn parse_park_object(&mut self) -> Result<ParkObject, ParseError> {
+        // raw addresses need the keyword, as `1.101` would otherwise be a sub fixture
+        if matches!(self.current_token()?, Token::KeywordAddress) {
+            self.advance();
+
+            let &Token::FloatingPoint(_, (universe, address)) = self.current_token()? else {
+                return Err(ParseError::UnexpectedToken(
+                    self.current_token()?.clone(),
+                    self.current_span(),
+                    "Expected universe.address".to_owned(),
+                ));
+            };
+            self.advance();
+
+            return Ok(ParkObject::Address {
+                universe: u16::try_from(universe).map_err(|_| {
+                    ParseError::UnexpectedArgs(
+                        format!("Universe {} is out of range", universe),
+                        Some(self.previous_span()),
+                    )
+                })?,
+                address: u16::try_from(address).map_err(|_| {
+                    ParseError::UnexpectedArgs(
+                        format!("Address {} is out of range", address),
+                        Some(self.previous_span()),
+                    )
+                })?,
+            });
+        }
+
+        let fixture_selector = self.parse_fixture_selector()?;
+        let attributes = self.parse_attributes()?;
+
+        Ok(ParkObject::FixtureAttributes {
+            fixture_selector,
+            attributes,
+        })
+    }
+
+    fn parse_park_function(&mut self) -> Result<Action, ParseError> {
+        let object = self.parse_park_object()?;
+
+        // raw addresses can be parked at a dmx value
+        let value = match (self.current_token()?, &object) {
+            (&Token::Integer(value), ParkObject::Address { .. }) => {
+                if value > 255 {
+                    return Err(ParseError::UnexpectedArgs(
+                        format!("DMX value {} is out of range", value),
+                        Some(self.current_span()),
+                    ));
+                }
+
+                self.advance();
+                Some(value as f32 / 255.0)
+            }
+            _ => self
+                .try_parse(Self::parse_discrete_channel_value_single)
+                .ok(),
+        };
+
+        Ok(Action::Park(ParkArgs { object, value }))
+    }
+
+    fn parse_unpark_function(&mut self) -> Result<Action, ParseError> {
+        if matches!(self.current_token()?, Token::Eof) {
+            return Ok(Action::Unpark(UnparkArgs { object: None }));
+        }
+
+        let object = self.parse_park_object()?;
+
+        Ok(Action::Unpark(UnparkArgs {
+            object: Some(object),
+        }))
+    }
+
     fn parse_config_function(&mut self) -> Result<Action, ParseError> {
         match self.current_token()? {
             Token::KeywordOutput => {
@@ -939,6 +1372,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["\"output\"", "\"patch\""],
             )),
         }
@@ -979,7 +1413,25 @@ impl<'a> Parser2<'a> {
                     let mode = match self.current_token()? {
                         Token::KeywordGo => {
                             self.advance();
-                            Ok(AssignButtonArgsMode::ExecutorGo(executor_id))
+
+                            if matches!(self.current_token()?, Token::KeywordBack) {
+                                self.advance();
+                                Ok(AssignButtonArgsMode::ExecutorGoBack(executor_id))
+                            } else {
+                                Ok(AssignButtonArgsMode::ExecutorGo(executor_id))
+                            }
+                        }
+                        Token::KeywordGoto => {
+                            self.advance();
+
+                            expect_and_consume_token!(self, Token::KeywordCue, "\"cue\"");
+
+                            let cue_idx = self.parse_discrete_cue_idx()?;
+
+                            Ok(AssignButtonArgsMode::ExecutorGotoCue {
+                                id: executor_id,
+                                cue_idx,
+                            })
                         }
                         Token::KeywordStop => {
                             self.advance();
@@ -1002,7 +1454,8 @@ impl<'a> Parser2<'a> {
                         }
                         unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                             unexpected_token.clone(),
-                            vec!["\"go\"", "\"stop\"", "\"flash\""],
+                            self.current_span(),
+                            vec!["\"go\"", "\"goto\"", "\"stop\"", "\"flash\""],
                         )),
                     }?;
 
@@ -1095,6 +1548,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec![
                     "\"executor\"",
                     "\"fader\"",
@@ -1132,6 +1586,7 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["\"button\"", "\"fader\""],
             )),
         }
@@ -1155,11 +1610,36 @@ impl<'a> Parser2<'a> {
             }
             unexpected_token => Err(ParseError::UnexpectedTokenAlternatives(
                 unexpected_token.clone(),
+                self.current_span(),
                 vec!["\"sequence\""],
             )),
         }
     }
 
+    fn parse_go_back_function(&mut self) -> Result<Action, ParseError> {
+        expect_and_consume_token!(self, Token::KeywordBack, "\"back\"");
+        expect_and_consume_token!(self, Token::KeywordExecutor, "\"executor\"");
+
+        let executor_id = self.parse_integer()?;
+
+        Ok(Action::ExecutorGoBack(ExecutorGoBackArgs { executor_id }))
+    }
+
+    fn parse_goto_function(&mut self) -> Result<Action, ParseError> {
+        expect_and_consume_token!(self, Token::KeywordCue, "\"cue\"");
+
+        let cue_idx = self.parse_discrete_cue_idx()?;
+
+        expect_and_consume_token!(self, Token::KeywordExecutor, "\"executor\"");
+
+        let executor_id = self.parse_integer()?;
+
+        Ok(Action::ExecutorGotoCue(ExecutorGotoCueArgs {
+            executor_id,
+            cue_idx,
+        }))
+    }
+
     fn parse_function(&mut self) -> Result<Action, ParseError> {
         if matches!(self.current_token()?, Token::KeywordHome) {
             self.advance();
@@ -1191,6 +1671,31 @@ impl<'a> Parser2<'a> {
             return self.parse_delete_function();
         }
 
+        if matches!(self.current_token()?, Token::KeywordCopy) {
+            self.advance();
+            return self.parse_copy_function(CopyMode::Copy);
+        }
+
+        if matches!(self.current_token()?, Token::KeywordMove) {
+            self.advance();
+            return self.parse_copy_function(CopyMode::Move);
+        }
+
+        if matches!(self.current_token()?, Token::KeywordRenumber) {
+            self.advance();
+            return self.parse_renumber_function();
+        }
+
+        if matches!(self.current_token()?, Token::KeywordPark) {
+            self.advance();
+            return self.parse_park_function();
+        }
+
+        if matches!(self.current_token()?, Token::KeywordUnpark) {
+            self.advance();
+            return self.parse_unpark_function();
+        }
+
         if matches!(self.current_token()?, Token::KeywordAssign) {
             self.advance();
             return self.parse_assign_function();
@@ -1221,6 +1726,16 @@ impl<'a> Parser2<'a> {
             return Ok(Action::Save);
         }
 
+        if matches!(self.current_token()?, Token::KeywordUndo) {
+            self.advance();
+            return Ok(Action::Undo);
+        }
+
+        if matches!(self.current_token()?, Token::KeywordRedo) {
+            self.advance();
+            return Ok(Action::Redo);
+        }
+
         if matches!(self.current_token()?, Token::KeywordConfig) {
             self.advance();
             return self.parse_config_function();
@@ -1231,6 +1746,16 @@ impl<'a> Parser2<'a> {
             return self.parse_recall_function();
         }
 
+        if matches!(self.current_token()?, Token::KeywordGo) {
+            self.advance();
+            return self.parse_go_back_function();
+        }
+
+        if matches!(self.current_token()?, Token::KeywordGoto) {
+            self.advance();
+            return self.parse_goto_function();
+        }
+
         if matches!(self.current_token()?, Token::KeywordTest) {
             self.advance();
 
@@ -1246,6 +1771,7 @@ impl<'a> Parser2<'a> {
 
         Err(ParseError::UnexpectedTokenAlternatives(
             self.current_token()?.clone(),
+            self.current_span(),
             vec![
                 "\"home\"",
                 "\"record\"",
@@ -1253,8 +1779,12 @@ impl<'a> Parser2<'a> {
                 "\"rename\"",
                 "\"update\"",
                 "\"delete\"",
+                "\"park\"",
+                "\"unpark\"",
                 "\"clear\"",
                 "\"save\"",
+                "\"go\"",
+                "\"goto\"",
                 "fixture selector",
             ],
         ))
@@ -1288,6 +1818,7 @@ impl<'a> Parser2<'a> {
         if self.has_next_token() {
             return Err(ParseError::UnexpectedToken(
                 self.current_token()?.clone(),
+                self.current_span(),
                 "Expected EOF".to_string(),
             ));
         }
@@ -1298,7 +1829,10 @@ impl<'a> Parser2<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::lexer::Lexer;
+    use crate::{
+        fixture::presets::PresetHandler, lexer::Lexer,
+        parser::nodes::fixture_selector::FixtureSelectorContext,
+    };
 
     use super::*;
 
@@ -1313,4 +1847,234 @@ mod tests {
 
         log::info!("{:?}", action);
     }
+
+    #[test]
+    pub fn test_parser_sub_fixture() {
+        let mut lexer = Lexer::new("101.1 thru 101.4 + 102.3");
+
+        let tokens = lexer.tokenize().unwrap();
+
+        let mut parser = Parser2::new(&tokens);
+        let fixture_selector = parser.parse_fixture_selector().unwrap();
+
+        assert_eq!(
+            fixture_selector,
+            FixtureSelector::Additive(
+                AtomicFixtureSelector::SubFixtureRange(101, 1, 4),
+                Box::new(FixtureSelector::Atomic(AtomicFixtureSelector::SubFixture(
+                    102, 3
+                ))),
+            )
+        );
+
+        let parse = |input: &str| {
+            let tokens = Lexer::new(input).tokenize().unwrap();
+            Parser2::new(&tokens).parse_fixture_selector()
+        };
+
+        assert!(matches!(
+            parse("101.4 thru 101.1"),
+            Err(ParseError::UnexpectedArgs(_, Some(_)))
+        ));
+
+        let selection = parse("101.65534 thru 101.65535")
+            .unwrap()
+            .get_selection(
+                &PresetHandler::default(),
+                FixtureSelectorContext::new(&None),
+            )
+            .unwrap();
+        assert_eq!(selection.cells(101), Some([65534, 65535].as_slice()));
+    }
+
+    #[test]
+    pub fn test_parser_error_span() {
+        let input = "record preset 1.1 group 1";
+
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+
+        let err = Parser2::new(&tokens)
+            .with_spans(lexer.spans())
+            .parse()
+            .unwrap_err();
+
+        // the furthest failing variant points at "group"
+        assert_eq!(err.span(), Some(Span::new(18, 23)));
+    }
+
+    #[test]
+    pub fn test_parser_error_variant_spans() {
+        let error = |input: &str| {
+            let mut lexer = Lexer::new(input);
+            let tokens = lexer.tokenize().unwrap();
+
+            Parser2::new(&tokens)
+                .with_spans(lexer.spans())
+                .parse_fixture_selector()
+                .unwrap_err()
+        };
+
+        assert!(matches!(
+            error("(1 + 2"),
+            ParseError::UnexpectedToken(Token::Eof, span, _) if span == Span::new(6, 6)
+        ));
+        assert!(matches!(
+            error("1 + intens"),
+            ParseError::UnexpectedTokenAlternatives(Token::KeywordIntens, span, _)
+                if span == Span::new(4, 10)
+        ));
+        assert!(matches!(
+            error("1.70000"),
+            ParseError::UnexpectedArgs(_, Some(span)) if span == Span::new(0, 7)
+        ));
+
+        // without the eof token, the parser runs out of tokens
+        let mut lexer = Lexer::new("1 thru");
+        let mut tokens = lexer.tokenize().unwrap();
+        tokens.pop();
+
+        assert!(matches!(
+            Parser2::new(&tokens)
+                .with_spans(lexer.spans())
+                .parse_fixture_selector()
+                .unwrap_err(),
+            ParseError::UnexpectedEndOfInput(span) if span == Span::new(6, 6)
+        ));
+    }
+
+    #[test]
+    pub fn test_parser_feature_value() {
+        let feature_value = |input: &str| {
+            let tokens = Lexer::new(input).tokenize().unwrap();
+
+            match Parser2::new(&tokens).parse().unwrap() {
+                Action::SetFeatureValue(args) => args.feature_value,
+                action => panic!("unexpected action {:?}", action),
+            }
+        };
+
+        assert_eq!(
+            feature_value("1 thru 10 intens 0 thru 100 thru 0"),
+            FeatureValue::Fan(vec![0.0, 1.0, 0.0])
+        );
+        assert_eq!(
+            feature_value("1 thru 10 intens +10"),
+            FeatureValue::Relative(0.1)
+        );
+        assert_eq!(
+            feature_value("1 position - 5"),
+            FeatureValue::Relative(-0.05)
+        );
+        assert_eq!(feature_value("1 color * 0.5"), FeatureValue::Scale(0.5));
+        assert_eq!(feature_value("1 intens 0.5"), FeatureValue::Fan(vec![0.5]));
+
+        // floating point values are fractions
+        let tokens = Lexer::new("1 intens 50.5").tokenize().unwrap();
+        assert!(Parser2::new(&tokens).parse().is_err());
+
+        let tokens = Lexer::new("1 thru 10 \"Pan\" +10").tokenize().unwrap();
+        match Parser2::new(&tokens).parse().unwrap() {
+            Action::SetFeatureValue(args) => {
+                assert_eq!(args.attributes, vec!["Pan".to_owned()]);
+                assert_eq!(args.feature_value, FeatureValue::Relative(0.1));
+            }
+            action => panic!("unexpected action {:?}", action),
+        }
+    }
+
+    #[test]
+    pub fn test_parser_park() {
+        let park_object = |input: &str| {
+            let tokens = Lexer::new(input).tokenize().unwrap();
+
+            match Parser2::new(&tokens).parse().unwrap() {
+                Action::Park(args) => args.object,
+                action => panic!("unexpected action {:?}", action),
+            }
+        };
+
+        assert!(matches!(
+            park_object("park address 1.101 255"),
+            ParkObject::Address {
+                universe: 1,
+                address: 101
+            }
+        ));
+
+        match park_object("park 1.2 \"Dimmer\"") {
+            ParkObject::FixtureAttributes {
+                fixture_selector,
+                attributes,
+            } => {
+                assert_eq!(
+                    fixture_selector,
+                    FixtureSelector::Atomic(AtomicFixtureSelector::SubFixture(1, 2))
+                );
+                assert_eq!(attributes, vec!["Dimmer".to_owned()]);
+            }
+            object => panic!("unexpected park object {:?}", object),
+        }
+
+        let tokens = Lexer::new("park address 1").tokenize().unwrap();
+        assert!(Parser2::new(&tokens).parse().is_err());
+    }
+
+    #[test]
+    pub fn test_parser_copy() {
+        let copy_args = |input: &str| {
+            let tokens = Lexer::new(input).tokenize().unwrap();
+
+            match Parser2::new(&tokens).parse().unwrap() {
+                Action::Copy(args) => args,
+                action => panic!("unexpected action {:?}", action),
+            }
+        };
+
+        let args = copy_args("copy preset 1.1 thru 1.5 to 1.20");
+        assert_eq!(args.mode, CopyMode::Copy);
+        assert_eq!(args.collision, None);
+        assert_eq!(
+            args.objects,
+            CopyObjects::Presets {
+                from: FixturePresetId {
+                    feature_group: 1u32.try_into().unwrap(),
+                    preset_id: 1,
+                },
+                to: FixturePresetId {
+                    feature_group: 1u32.try_into().unwrap(),
+                    preset_id: 5,
+                },
+                target: FixturePresetId {
+                    feature_group: 1u32.try_into().unwrap(),
+                    preset_id: 20,
+                },
+            }
+        );
+
+        let args = copy_args("move group 3 to 10 merge");
+        assert_eq!(args.mode, CopyMode::Move);
+        assert_eq!(args.collision, Some(UpdateMode::Merge));
+        assert_eq!(
+            args.objects,
+            CopyObjects::Groups {
+                from: 3,
+                to: 3,
+                target: 10,
+            }
+        );
+
+        let args = copy_args("copy sequence 2 cue 5 to sequence 4 cue 1 overwrite");
+        assert_eq!(args.collision, Some(UpdateMode::Override));
+        assert_eq!(
+            args.objects,
+            CopyObjects::SequenceCues {
+                sequence_id: 2,
+                from: (5, 0),
+                to: (5, 0),
+                target_sequence_id: 4,
+                target: (1, 0),
+            }
+        );
+    }
 }
@@ -6,6 +6,7 @@ use crate::{
     fixture::{
         patch::Patch,
         presets::{preset::FixturePresetId, PresetHandler},
+        sequence::cue::CueIdx,
         timing::TimingHandler,
         updatables::UpdatableHandler,
     },
@@ -22,6 +23,11 @@ use super::FunctionArgs;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AssignButtonArgsMode {
     ExecutorGo(u32),
+    ExecutorGoBack(u32),
+    ExecutorGotoCue {
+        id: u32,
+        cue_idx: CueIdx,
+    },
     ExecutorStop(u32),
     ExecutorFlash {
         id: u32,
@@ -43,7 +49,11 @@ impl AssignButtonArgsMode {
         updatable_handler: &UpdatableHandler,
     ) -> Result<(), ActionRunError> {
         match self {
-            Self::ExecutorStop(id) | Self::ExecutorFlash { id, .. } | Self::ExecutorGo(id) => {
+            Self::ExecutorStop(id)
+            | Self::ExecutorFlash { id, .. }
+            | Self::ExecutorGo(id)
+            | Self::ExecutorGoBack(id)
+            | Self::ExecutorGotoCue { id, .. } => {
                 updatable_handler
                     .executor(*id)
                     .map_err(ActionRunError::UpdatableHandlerError)?;
@@ -73,6 +83,15 @@ impl AssignButtonArgsMode {
             AssignButtonArgsMode::ExecutorGo(executor_id) => {
                 Ok(vec![DemexInputButton::ExecutorGo(*executor_id)])
             }
+            AssignButtonArgsMode::ExecutorGoBack(executor_id) => {
+                Ok(vec![DemexInputButton::ExecutorGoBack(*executor_id)])
+            }
+            AssignButtonArgsMode::ExecutorGotoCue { id, cue_idx } => {
+                Ok(vec![DemexInputButton::ExecutorGotoCue {
+                    id: *id,
+                    cue_idx: *cue_idx,
+                }])
+            }
             AssignButtonArgsMode::ExecutorStop(executor_id) => {
                 Ok(vec![DemexInputButton::ExecutorStop(*executor_id)])
             }
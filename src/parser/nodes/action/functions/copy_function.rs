@@ -0,0 +1,621 @@
+use std::{collections::HashMap, time};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fixture::{
+        patch::Patch,
+        presets::{error::PresetHandlerError, preset::FixturePresetId},
+        sequence::cue::CueIdx,
+        timing::TimingHandler,
+        updatables::UpdatableHandler,
+    },
+    input::DemexInputDeviceHandler,
+    parser::nodes::action::{error::ActionRunError, result::ActionRunResult},
+};
+
+use super::{update_function::UpdateMode, FunctionArgs};
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CopyMode {
+    Copy,
+
+    /// Removes the source objects and rewrites every reference to them
+    Move,
+}
+
+/// The objects between `from` and `to`, that are copied to consecutive ids starting at `target`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CopyObjects {
+    Presets {
+        from: FixturePresetId,
+        to: FixturePresetId,
+        target: FixturePresetId,
+    },
+    Groups {
+        from: u32,
+        to: u32,
+        target: u32,
+    },
+    Sequences {
+        from: u32,
+        to: u32,
+        target: u32,
+    },
+    SequenceCues {
+        sequence_id: u32,
+        from: CueIdx,
+        to: CueIdx,
+        target_sequence_id: u32,
+        target: CueIdx,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopyArgs {
+    pub mode: CopyMode,
+    pub objects: CopyObjects,
+
+    /// How targets, that already exist, are handled. If not set, they aren't touched.
+    pub collision: Option<UpdateMode>,
+}
+
+impl FunctionArgs for CopyArgs {
+    fn run(
+        &self,
+        _issued_at: time::Instant,
+        fixture_handler: &mut crate::fixture::handler::FixtureHandler,
+        preset_handler: &mut crate::fixture::presets::PresetHandler,
+        _fixture_selector_context: crate::parser::nodes::fixture_selector::FixtureSelectorContext,
+        updatable_handler: &mut crate::fixture::updatables::UpdatableHandler,
+        input_device_handler: &mut crate::input::DemexInputDeviceHandler,
+        timing_handler: &mut TimingHandler,
+        _: &Patch,
+    ) -> Result<ActionRunResult, ActionRunError> {
+        let (count, object_name) = match self.objects {
+            CopyObjects::Presets { from, to, target } => {
+                let mapping = match self.mode {
+                    CopyMode::Copy => preset_handler.copy_presets(from, to, target, self.collision),
+                    CopyMode::Move => preset_handler.move_presets(from, to, target, self.collision),
+                }
+                .map_err(ActionRunError::PresetHandlerError)?;
+
+                if self.mode == CopyMode::Move {
+                    let map = |id: FixturePresetId| mapping.get(&id).copied().unwrap_or(id);
+
+                    for fixture in fixture_handler.fixtures_mut() {
+                        fixture.map_programmer_preset_ids(&map);
+                    }
+
+                    for button in input_device_handler.buttons_mut() {
+                        button.map_preset_ids(&map);
+                    }
+
+                    for timecode in timing_handler.timecodes_mut().values_mut() {
+                        timecode.map_preset_ids(&map);
+                    }
+                }
+
+                (mapping.len(), "presets")
+            }
+            CopyObjects::Groups { from, to, target } => {
+                let mapping = match self.mode {
+                    CopyMode::Copy => preset_handler.copy_groups(from, to, target, self.collision),
+                    CopyMode::Move => preset_handler.move_groups(from, to, target, self.collision),
+                }
+                .map_err(ActionRunError::PresetHandlerError)?;
+
+                if self.mode == CopyMode::Move {
+                    let map = |id: u32| mapping.get(&id).copied().unwrap_or(id);
+
+                    for button in input_device_handler.buttons_mut() {
+                        button.map_group_ids(&map);
+                    }
+                }
+
+                (mapping.len(), "groups")
+            }
+            CopyObjects::Sequences { from, to, target } => {
+                // the cues of existing targets are replaced or merged
+                let last_target = target.checked_add(to.saturating_sub(from)).ok_or(
+                    ActionRunError::PresetHandlerError(PresetHandlerError::TargetOutOfRange(
+                        target,
+                    )),
+                )?;
+
+                for id in target..=last_target {
+                    if let Some(executor_id) = updatable_handler.sequence_running_executor(id) {
+                        return Err(ActionRunError::ExecutorIsRunning(executor_id));
+                    }
+                }
+
+                let mapping = match self.mode {
+                    CopyMode::Copy => {
+                        preset_handler.copy_sequences(from, to, target, self.collision)
+                    }
+                    CopyMode::Move => {
+                        preset_handler.move_sequences(from, to, target, self.collision)
+                    }
+                }
+                .map_err(ActionRunError::PresetHandlerError)?;
+
+                if self.mode == CopyMode::Move {
+                    updatable_handler.remap_sequence_ids(&mapping);
+
+                    let map = |id: u32| mapping.get(&id).copied().unwrap_or(id);
+
+                    for button in input_device_handler.buttons_mut() {
+                        button.map_sequence_ids(&map);
+                    }
+                }
+
+                (mapping.len(), "sequences")
+            }
+            CopyObjects::SequenceCues {
+                sequence_id,
+                from,
+                to,
+                target_sequence_id,
+                target,
+            } => {
+                // running executors refer to their cues by position
+                for id in [sequence_id, target_sequence_id] {
+                    if let Some(executor_id) = updatable_handler.sequence_running_executor(id) {
+                        return Err(ActionRunError::ExecutorIsRunning(executor_id));
+                    }
+                }
+
+                let mapping = match self.mode {
+                    CopyMode::Copy => preset_handler.copy_sequence_cues(
+                        sequence_id,
+                        from,
+                        to,
+                        target_sequence_id,
+                        target,
+                        self.collision,
+                    ),
+                    CopyMode::Move => preset_handler.move_sequence_cues(
+                        sequence_id,
+                        from,
+                        to,
+                        target_sequence_id,
+                        target,
+                        self.collision,
+                    ),
+                }
+                .map_err(ActionRunError::PresetHandlerError)?;
+
+                // cues, that were moved to another sequence, can't be reached by the buttons anymore
+                if self.mode == CopyMode::Move && sequence_id == target_sequence_id {
+                    map_goto_cue_buttons(
+                        updatable_handler,
+                        input_device_handler,
+                        sequence_id,
+                        &mapping,
+                    );
+                }
+
+                (mapping.len(), "cues")
+            }
+        };
+
+        if count == 1 {
+            Ok(ActionRunResult::new())
+        } else {
+            Ok(ActionRunResult::Info(format!(
+                "{} {} {}",
+                match self.mode {
+                    CopyMode::Copy => "Copied",
+                    CopyMode::Move => "Moved",
+                },
+                count,
+                object_name
+            )))
+        }
+    }
+}
+
+// goto cue buttons refer to the cues of the sequence of their executor
+fn map_goto_cue_buttons(
+    updatable_handler: &UpdatableHandler,
+    input_device_handler: &mut DemexInputDeviceHandler,
+    sequence_id: u32,
+    mapping: &HashMap<CueIdx, CueIdx>,
+) {
+    let executor_ids = updatable_handler
+        .executors()
+        .iter()
+        .filter(|(_, executor)| executor.runtime().sequence_id() == sequence_id)
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
+
+    let map = |cue_idx: CueIdx| mapping.get(&cue_idx).copied().unwrap_or(cue_idx);
+
+    for button in input_device_handler.buttons_mut() {
+        button.map_cue_idxs(&executor_ids, &map);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenumberSequenceCuesArgs {
+    pub sequence_id: u32,
+    pub from: CueIdx,
+    pub to: CueIdx,
+    pub step: u32,
+}
+
+impl FunctionArgs for RenumberSequenceCuesArgs {
+    fn run(
+        &self,
+        _issued_at: time::Instant,
+        _fixture_handler: &mut crate::fixture::handler::FixtureHandler,
+        preset_handler: &mut crate::fixture::presets::PresetHandler,
+        _fixture_selector_context: crate::parser::nodes::fixture_selector::FixtureSelectorContext,
+        updatable_handler: &mut crate::fixture::updatables::UpdatableHandler,
+        input_device_handler: &mut crate::input::DemexInputDeviceHandler,
+        _: &mut TimingHandler,
+        _: &Patch,
+    ) -> Result<ActionRunResult, ActionRunError> {
+        if let Some(executor_id) = updatable_handler.sequence_running_executor(self.sequence_id) {
+            return Err(ActionRunError::ExecutorIsRunning(executor_id));
+        }
+
+        let mapping = preset_handler
+            .renumber_sequence_cues(self.sequence_id, self.from, self.to, self.step)
+            .map_err(ActionRunError::PresetHandlerError)?;
+
+        map_goto_cue_buttons(
+            updatable_handler,
+            input_device_handler,
+            self.sequence_id,
+            &mapping,
+        );
+
+        Ok(ActionRunResult::Info(format!(
+            "Renumbered {} cues in sequence {}",
+            mapping.len(),
+            self.sequence_id
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time};
+
+    use crate::{
+        dmx::routing::DmxRouting,
+        fixture::{
+            channel3::feature::feature_group::FixtureChannel3FeatureGroup,
+            handler::FixtureHandler,
+            patch::Patch,
+            presets::{error::PresetHandlerError, preset::FixturePresetId, PresetHandler},
+            selection::FixtureSelection,
+            timing::{timecode::Timecode, TimingHandler},
+            updatables::UpdatableHandler,
+        },
+        input::{
+            button::DemexInputButton, device::DemexInputDeviceConfig,
+            profile::DemexInputDeviceProfileType, DemexInputDeviceHandler,
+        },
+        parser::nodes::{
+            action::{
+                error::ActionRunError,
+                functions::{
+                    recall_function::RecallSequenceCueArgs,
+                    set_function::{SelectionOrSelector, SetFixturePresetArgs},
+                    FunctionArgs,
+                },
+                result::ActionRunResult,
+                Action, ValueOrRange,
+            },
+            fixture_selector::{AtomicFixtureSelector, FixtureSelector, FixtureSelectorContext},
+        },
+    };
+
+    use super::{CopyArgs, CopyMode, CopyObjects};
+
+    struct Show {
+        fixture_handler: FixtureHandler,
+        preset_handler: PresetHandler,
+        updatable_handler: UpdatableHandler,
+        input_device_handler: DemexInputDeviceHandler,
+        timing_handler: TimingHandler,
+    }
+
+    impl Show {
+        fn new(buttons: HashMap<u32, DemexInputButton>) -> Self {
+            let device_config = DemexInputDeviceConfig::new(
+                buttons,
+                HashMap::new(),
+                DemexInputDeviceProfileType::Osc {
+                    bind_address: "127.0.0.1:0".to_owned(),
+                    feedback_address: None,
+                },
+            );
+
+            Self {
+                fixture_handler: FixtureHandler::new(vec![], vec![], DmxRouting::default(), true)
+                    .unwrap(),
+                preset_handler: PresetHandler::default(),
+                updatable_handler: UpdatableHandler::default(),
+                input_device_handler: DemexInputDeviceHandler::new(vec![device_config.into()]),
+                timing_handler: TimingHandler::default(),
+            }
+        }
+
+        fn run(&mut self, mode: CopyMode, objects: CopyObjects) {
+            self.try_run(mode, objects).unwrap();
+        }
+
+        fn try_run(
+            &mut self,
+            mode: CopyMode,
+            objects: CopyObjects,
+        ) -> Result<ActionRunResult, ActionRunError> {
+            CopyArgs {
+                mode,
+                objects,
+                collision: None,
+            }
+            .run(
+                time::Instant::now(),
+                &mut self.fixture_handler,
+                &mut self.preset_handler,
+                FixtureSelectorContext::new(&None),
+                &mut self.updatable_handler,
+                &mut self.input_device_handler,
+                &mut self.timing_handler,
+                &Patch::default(),
+            )
+        }
+
+        fn button(&self, button_id: u32) -> &DemexInputButton {
+            &self.input_device_handler.devices()[0].config().buttons()[&button_id]
+        }
+    }
+
+    fn color_preset_id(preset_id: u32) -> FixturePresetId {
+        FixturePresetId {
+            feature_group: FixtureChannel3FeatureGroup::Color,
+            preset_id,
+        }
+    }
+
+    fn group_selector(group_id: u32) -> FixtureSelector {
+        FixtureSelector::Atomic(AtomicFixtureSelector::FixtureGroup(group_id))
+    }
+
+    fn set_preset_action(preset_id: FixturePresetId) -> Action {
+        Action::SetFixturePreset(SetFixturePresetArgs {
+            selection_or_selector: SelectionOrSelector::Selector(group_selector(1)),
+            preset_id: ValueOrRange::Single(preset_id),
+        })
+    }
+
+    fn set_preset_action_id(action: &Action) -> Option<FixturePresetId> {
+        match action {
+            Action::SetFixturePreset(SetFixturePresetArgs {
+                preset_id: ValueOrRange::Single(preset_id),
+                ..
+            }) => Some(*preset_id),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_move_presets_rewrites_references() {
+        let mut show = Show::new(HashMap::from([
+            (
+                1,
+                DemexInputButton::SelectivePreset {
+                    selection: None,
+                    preset_id: color_preset_id(1),
+                },
+            ),
+            (
+                2,
+                DemexInputButton::Macro {
+                    action: set_preset_action(color_preset_id(1)),
+                },
+            ),
+        ]));
+
+        show.preset_handler
+            .create_effect_preset(color_preset_id(1), None)
+            .unwrap();
+        show.preset_handler
+            .create_macro(1, None, Box::new(set_preset_action(color_preset_id(1))))
+            .unwrap();
+
+        let timecode: Timecode = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "Timecode 1",
+            "triggers": [{
+                "trigger_type": {
+                    "SetPreset": {
+                        "selection": FixtureSelection::from(vec![1]),
+                        "preset": color_preset_id(1),
+                    }
+                },
+                "millis": 0,
+            }],
+        }))
+        .unwrap();
+        show.timing_handler.timecodes_mut().insert(1, timecode);
+
+        show.run(
+            CopyMode::Move,
+            CopyObjects::Presets {
+                from: color_preset_id(1),
+                to: color_preset_id(1),
+                target: color_preset_id(5),
+            },
+        );
+
+        assert!(show.preset_handler.get_preset(color_preset_id(1)).is_err());
+        assert!(show.preset_handler.get_preset(color_preset_id(5)).is_ok());
+
+        assert!(matches!(
+            show.button(1),
+            DemexInputButton::SelectivePreset { preset_id, .. } if *preset_id == color_preset_id(5)
+        ));
+
+        assert!(matches!(
+            show.button(2),
+            DemexInputButton::Macro { action } if set_preset_action_id(action) == Some(color_preset_id(5))
+        ));
+        assert_eq!(
+            set_preset_action_id(show.preset_handler.get_macro(1).unwrap().action()),
+            Some(color_preset_id(5))
+        );
+
+        let timecode = serde_json::to_value(&show.timing_handler.timecodes()[&1]).unwrap();
+        assert_eq!(
+            timecode["triggers"][0]["trigger_type"]["SetPreset"]["preset"],
+            serde_json::to_value(color_preset_id(5)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_move_groups_rewrites_references() {
+        let mut show = Show::new(HashMap::from([
+            (
+                1,
+                DemexInputButton::FixtureSelector {
+                    fixture_selector: group_selector(1),
+                },
+            ),
+            (
+                2,
+                DemexInputButton::Macro {
+                    action: Action::FixtureSelector(group_selector(1)),
+                },
+            ),
+        ]));
+
+        show.preset_handler
+            .record_group(vec![1, 2].into(), 1, None)
+            .unwrap();
+        show.preset_handler
+            .create_macro(
+                1,
+                None,
+                Box::new(Action::FixtureSelector(FixtureSelector::Additive(
+                    AtomicFixtureSelector::SingleFixture(3),
+                    Box::new(group_selector(1)),
+                ))),
+            )
+            .unwrap();
+
+        show.run(
+            CopyMode::Move,
+            CopyObjects::Groups {
+                from: 1,
+                to: 1,
+                target: 4,
+            },
+        );
+
+        assert!(show.preset_handler.get_group(1).is_err());
+        assert!(show.preset_handler.get_group(4).is_ok());
+
+        assert!(matches!(
+            show.button(1),
+            DemexInputButton::FixtureSelector { fixture_selector } if *fixture_selector == group_selector(4)
+        ));
+        assert!(matches!(
+            show.button(2),
+            DemexInputButton::Macro { action: Action::FixtureSelector(fixture_selector) }
+                if *fixture_selector == group_selector(4)
+        ));
+        assert!(matches!(
+            show.preset_handler.get_macro(1).unwrap().action(),
+            Action::FixtureSelector(FixtureSelector::Additive(_, fixture_selector))
+                if **fixture_selector == group_selector(4)
+        ));
+    }
+
+    #[test]
+    fn test_move_sequences_rewrites_macros() {
+        let recall_action = |sequence_id: u32| {
+            Action::RecallSequenceCue(RecallSequenceCueArgs {
+                sequence_id,
+                cue_idx: (1, 0),
+            })
+        };
+
+        let mut show = Show::new(HashMap::from([(
+            1,
+            DemexInputButton::Macro {
+                action: recall_action(1),
+            },
+        )]));
+
+        show.preset_handler.create_sequence(1, None).unwrap();
+        show.preset_handler
+            .create_macro(1, None, Box::new(recall_action(1)))
+            .unwrap();
+
+        show.run(
+            CopyMode::Move,
+            CopyObjects::Sequences {
+                from: 1,
+                to: 1,
+                target: 3,
+            },
+        );
+
+        assert!(matches!(
+            show.button(1),
+            DemexInputButton::Macro { action: Action::RecallSequenceCue(args) } if args.sequence_id == 3
+        ));
+        assert!(matches!(
+            show.preset_handler.get_macro(1).unwrap().action(),
+            Action::RecallSequenceCue(args) if args.sequence_id == 3
+        ));
+    }
+
+    #[test]
+    fn test_copy_to_out_of_range_target() {
+        let mut show = Show::new(HashMap::new());
+
+        for id in [1, 2] {
+            show.preset_handler
+                .record_group(vec![id].into(), id, None)
+                .unwrap();
+            show.preset_handler.create_sequence(id, None).unwrap();
+        }
+
+        assert!(matches!(
+            show.try_run(
+                CopyMode::Move,
+                CopyObjects::Groups {
+                    from: 1,
+                    to: 2,
+                    target: u32::MAX,
+                },
+            ),
+            Err(ActionRunError::PresetHandlerError(
+                PresetHandlerError::TargetOutOfRange(u32::MAX)
+            ))
+        ));
+        assert!(show.preset_handler.get_group(1).is_ok());
+        assert!(show.preset_handler.get_group(2).is_ok());
+
+        assert!(matches!(
+            show.try_run(
+                CopyMode::Copy,
+                CopyObjects::Sequences {
+                    from: 1,
+                    to: 2,
+                    target: u32::MAX,
+                },
+            ),
+            Err(ActionRunError::PresetHandlerError(
+                PresetHandlerError::TargetOutOfRange(u32::MAX)
+            ))
+        ));
+        assert!(show.preset_handler.get_sequence(u32::MAX).is_err());
+    }
+}
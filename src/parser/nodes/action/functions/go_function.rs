@@ -1,4 +1,8 @@
-use crate::parser::nodes::action::{error::ActionRunError, result::ActionRunResult};
+use crate::{
+    fixture::sequence::cue::CueIdx,
+    parser::nodes::action::{error::ActionRunError, result::ActionRunResult},
+    utils::clock,
+};
 
 use super::FunctionArgs;
 
@@ -29,7 +33,72 @@ impl FunctionArgs for ExecutorGoArgs {
                 self.executor_id,
                 fixture_handler,
                 preset_handler,
-                issued_at.elapsed().as_secs_f32(),
+                clock::elapsed(issued_at).as_secs_f32(),
+            )
+            .map_err(ActionRunError::UpdatableHandlerError)
+            .map(|_| ActionRunResult::new())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutorGoBackArgs {
+    pub executor_id: u32,
+}
+
+impl FunctionArgs for ExecutorGoBackArgs {
+    fn run(
+        &self,
+        issued_at: std::time::Instant,
+        _fixture_handler: &mut crate::fixture::handler::FixtureHandler,
+        preset_handler: &mut crate::fixture::presets::PresetHandler,
+        _fixture_selector_context: crate::parser::nodes::fixture_selector::FixtureSelectorContext,
+        updatable_handler: &mut crate::fixture::updatables::UpdatableHandler,
+        _input_device_handler: &mut crate::input::DemexInputDeviceHandler,
+        _timing_handler: &mut crate::fixture::timing::TimingHandler,
+        _patch: &crate::fixture::patch::Patch,
+    ) -> Result<
+        crate::parser::nodes::action::result::ActionRunResult,
+        crate::parser::nodes::action::error::ActionRunError,
+    > {
+        updatable_handler
+            .executor_go_back(
+                self.executor_id,
+                preset_handler,
+                clock::elapsed(issued_at).as_secs_f32(),
+            )
+            .map_err(ActionRunError::UpdatableHandlerError)
+            .map(|_| ActionRunResult::new())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutorGotoCueArgs {
+    pub executor_id: u32,
+    pub cue_idx: CueIdx,
+}
+
+impl FunctionArgs for ExecutorGotoCueArgs {
+    fn run(
+        &self,
+        issued_at: std::time::Instant,
+        fixture_handler: &mut crate::fixture::handler::FixtureHandler,
+        preset_handler: &mut crate::fixture::presets::PresetHandler,
+        _fixture_selector_context: crate::parser::nodes::fixture_selector::FixtureSelectorContext,
+        updatable_handler: &mut crate::fixture::updatables::UpdatableHandler,
+        _input_device_handler: &mut crate::input::DemexInputDeviceHandler,
+        _timing_handler: &mut crate::fixture::timing::TimingHandler,
+        _patch: &crate::fixture::patch::Patch,
+    ) -> Result<
+        crate::parser::nodes::action::result::ActionRunResult,
+        crate::parser::nodes::action::error::ActionRunError,
+    > {
+        updatable_handler
+            .executor_goto_cue(
+                self.executor_id,
+                self.cue_idx,
+                fixture_handler,
+                preset_handler,
+                clock::elapsed(issued_at).as_secs_f32(),
             )
             .map_err(ActionRunError::UpdatableHandlerError)
             .map(|_| ActionRunResult::new())
@@ -12,9 +12,11 @@ use crate::{
 use super::{error::ActionRunError, result::ActionRunResult};
 
 pub mod assign_function;
+pub mod copy_function;
 pub mod create_function;
 pub mod delete_function;
 pub mod go_function;
+pub mod park_function;
 pub mod recall_function;
 pub mod record_function;
 pub mod rename_function;
@@ -0,0 +1,234 @@
+use std::time;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fixture::{
+        handler::{error::FixtureHandlerError, park::ParkTarget, FixtureHandler},
+        patch::Patch,
+        presets::PresetHandler,
+        timing::TimingHandler,
+        updatables::UpdatableHandler,
+    },
+    input::DemexInputDeviceHandler,
+    parser::nodes::{
+        action::{error::ActionRunError, result::ActionRunResult},
+        fixture_selector::{AtomicFixtureSelector, FixtureSelector, FixtureSelectorContext},
+    },
+};
+
+use super::FunctionArgs;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ParkObject {
+    FixtureAttributes {
+        fixture_selector: FixtureSelector,
+        attributes: Vec<String>,
+    },
+    Address {
+        universe: u16,
+        address: u16,
+    },
+}
+
+impl From<ParkTarget> for ParkObject {
+    fn from(target: ParkTarget) -> Self {
+        match target {
+            ParkTarget::FixtureAttribute {
+                fixture_id,
+                attribute,
+            } => Self::FixtureAttributes {
+                fixture_selector: FixtureSelector::Atomic(AtomicFixtureSelector::SingleFixture(
+                    fixture_id,
+                )),
+                attributes: vec![attribute],
+            },
+            ParkTarget::Address { universe, address } => Self::Address { universe, address },
+        }
+    }
+}
+
+impl ParkObject {
+    fn targets(
+        &self,
+        preset_handler: &PresetHandler,
+        fixture_selector_context: FixtureSelectorContext,
+    ) -> Result<Vec<ParkTarget>, ActionRunError> {
+        match self {
+            Self::FixtureAttributes {
+                fixture_selector,
+                attributes,
+            } => {
+                let selection = fixture_selector
+                    .get_selection(preset_handler, fixture_selector_context)
+                    .map_err(ActionRunError::FixtureSelectorError)?;
+
+                Ok(selection
+                    .fixtures()
+                    .iter()
+                    .flat_map(|fixture_id| {
+                        attributes
+                            .iter()
+                            .map(|attribute| ParkTarget::FixtureAttribute {
+                                fixture_id: *fixture_id,
+                                attribute: attribute.clone(),
+                            })
+                    })
+                    .collect())
+            }
+            Self::Address { universe, address } => {
+                if !(1..=512).contains(address) {
+                    return Err(ActionRunError::FixtureHandlerError(
+                        FixtureHandlerError::ParkAddressOutOfRange(*universe, *address),
+                    ));
+                }
+
+                Ok(vec![ParkTarget::Address {
+                    universe: *universe,
+                    address: *address,
+                }])
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParkArgs {
+    pub object: ParkObject,
+
+    /// If `None`, the current output value is parked
+    pub value: Option<f32>,
+}
+
+impl FunctionArgs for ParkArgs {
+    fn run(
+        &self,
+        _issued_at: time::Instant,
+        fixture_handler: &mut FixtureHandler,
+        preset_handler: &mut PresetHandler,
+        fixture_selector_context: FixtureSelectorContext,
+        _updatable_handler: &mut UpdatableHandler,
+        _input_device_handler: &mut DemexInputDeviceHandler,
+        timing_handler: &mut TimingHandler,
+        patch: &Patch,
+    ) -> Result<ActionRunResult, ActionRunError> {
+        let mut targets = Vec::new();
+
+        // attributes, the fixtures don't have a channel for, are skipped
+        for target in self
+            .object
+            .targets(preset_handler, fixture_selector_context)?
+        {
+            if let ParkTarget::FixtureAttribute {
+                fixture_id,
+                attribute,
+            } = &target
+            {
+                let fixture = fixture_handler.fixture_immut(*fixture_id).ok_or(
+                    ActionRunError::FixtureHandlerError(FixtureHandlerError::FixtureNotFound(
+                        *fixture_id,
+                    )),
+                )?;
+
+                if fixture
+                    .channels_for_attribute(patch.fixture_types(), attribute)
+                    .map_err(ActionRunError::FixtureError)?
+                    .is_empty()
+                {
+                    continue;
+                }
+            }
+
+            targets.push(target);
+        }
+
+        if targets.is_empty() {
+            return Err(ActionRunError::FixtureHandlerError(
+                FixtureHandlerError::NoChannelsToPark,
+            ));
+        }
+
+        let values = targets
+            .iter()
+            .map(|target| {
+                if let Some(value) = self.value {
+                    return Ok(value);
+                }
+
+                match target {
+                    ParkTarget::FixtureAttribute {
+                        fixture_id,
+                        attribute,
+                    } => fixture_handler
+                        .fixture_immut(*fixture_id)
+                        .unwrap()
+                        .get_attribute_display_value(
+                            patch.fixture_types(),
+                            attribute,
+                            preset_handler,
+                            timing_handler,
+                        )
+                        .map_err(ActionRunError::FixtureError),
+                    ParkTarget::Address { universe, address } => Ok(fixture_handler
+                        .universe_output_data()
+                        .get(universe)
+                        .map(|data| data[*address as usize - 1] as f32 / 255.0)
+                        .unwrap_or_default()),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let num_targets = targets.len();
+
+        for (target, value) in targets.into_iter().zip(values) {
+            fixture_handler.park_handler_mut().park(target, value);
+        }
+
+        if num_targets == 1 {
+            Ok(ActionRunResult::new())
+        } else {
+            Ok(ActionRunResult::Info(format!(
+                "Parked {} values",
+                num_targets
+            )))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnparkArgs {
+    /// If `None`, everything is unparked
+    pub object: Option<ParkObject>,
+}
+
+impl FunctionArgs for UnparkArgs {
+    fn run(
+        &self,
+        _issued_at: time::Instant,
+        fixture_handler: &mut FixtureHandler,
+        preset_handler: &mut PresetHandler,
+        fixture_selector_context: FixtureSelectorContext,
+        _updatable_handler: &mut UpdatableHandler,
+        _input_device_handler: &mut DemexInputDeviceHandler,
+        _: &mut TimingHandler,
+        _: &Patch,
+    ) -> Result<ActionRunResult, ActionRunError> {
+        let num_unparked = match &self.object {
+            Some(object) => object
+                .targets(preset_handler, fixture_selector_context)?
+                .iter()
+                .filter(|target| fixture_handler.park_handler_mut().unpark(target))
+                .count(),
+            None => fixture_handler.park_handler_mut().unpark_all(),
+        };
+
+        if num_unparked == 0 {
+            Ok(ActionRunResult::Warn("Nothing was parked".to_owned()))
+        } else {
+            Ok(ActionRunResult::Info(format!(
+                "Unparked {} values",
+                num_unparked
+            )))
+        }
+    }
+}
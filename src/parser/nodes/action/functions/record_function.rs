@@ -198,7 +198,10 @@ impl TryFrom<(Token, u32)> for RecordSequenceCueShorthandArgsId {
     fn try_from((token, id): (Token, u32)) -> Result<Self, Self::Error> {
         match token {
             Token::KeywordExecutor => Ok(Self::ExecutorId(id)),
-            _ => Err(ParseError::UnexpectedArgs("Expected 'executor'".to_owned())),
+            _ => Err(ParseError::UnexpectedArgs(
+                "Expected 'executor'".to_owned(),
+                None,
+            )),
         }
     }
 }
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     fixture::{
-        channel3::feature::feature_type::FixtureChannel3FeatureType,
+        channel3::{
+            attribute::FixtureChannel3Attribute, channel_value::FixtureChannelValue3Discrete,
+        },
         patch::Patch,
         presets::{preset::FixturePresetId, PresetHandler},
         selection::FixtureSelection,
@@ -18,11 +20,52 @@ use crate::{
 
 use super::FunctionArgs;
 
+/// Value of a set command, applied against the programmer value of every selected fixture
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum FeatureValue {
+    /// Spread evenly over the selection, passing through every value in order
+    /// (e.g. `0 thru 100 thru 0`). A single value is set for every fixture.
+    Fan(Vec<f32>),
+
+    /// Added to the current value (e.g. `+10` or `- 5`)
+    Relative(f32),
+
+    /// Multiplied with the current value (e.g. `* 0.5`)
+    Scale(f32),
+}
+
+impl FeatureValue {
+    /// The new value of the fixture at `offset_idx`, given its `current` value
+    pub fn value(&self, offset_idx: usize, num_offsets: usize, current: f32) -> f32 {
+        let value = match self {
+            Self::Fan(values) => match values.as_slice() {
+                [] => current,
+                [value] => *value,
+                _ if num_offsets < 2 => values[0],
+                _ => {
+                    let position =
+                        offset_idx as f32 / (num_offsets - 1) as f32 * (values.len() - 1) as f32;
+                    let segment = (position.floor() as usize).min(values.len() - 2);
+
+                    let (from, to) = (values[segment], values[segment + 1]);
+                    from + (to - from) * (position - segment as f32)
+                }
+            },
+            Self::Relative(delta) => current + delta,
+            Self::Scale(factor) => current * factor,
+        };
+
+        value.clamp(0.0, 1.0)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SetFeatureValueArgs {
     pub fixture_selector: FixtureSelector,
-    pub feature_type: FixtureChannel3FeatureType,
-    pub feature_value: ValueOrRange<f32>,
+
+    /// A single attribute (e.g. `"Pan"`) or all attributes of a feature
+    pub attributes: Vec<String>,
+    pub feature_value: FeatureValue,
 }
 
 impl FunctionArgs for SetFeatureValueArgs {
@@ -34,8 +77,8 @@ impl FunctionArgs for SetFeatureValueArgs {
         fixture_selector_context: crate::parser::nodes::fixture_selector::FixtureSelectorContext,
         _updatable_handler: &mut crate::fixture::updatables::UpdatableHandler,
         _input_device_handler: &mut crate::input::DemexInputDeviceHandler,
-        _: &mut TimingHandler,
-        _: &Patch,
+        timing_handler: &mut TimingHandler,
+        patch: &Patch,
     ) -> Result<
         crate::parser::nodes::action::result::ActionRunResult,
         crate::parser::nodes::action::error::ActionRunError,
@@ -45,20 +88,54 @@ impl FunctionArgs for SetFeatureValueArgs {
             .get_selection(preset_handler, fixture_selector_context)
             .map_err(ActionRunError::FixtureSelectorError)?;
 
+        let fixture_types = patch.fixture_types();
+        let num_offsets = selection.num_offsets();
+
         for fixture_id in selection.fixtures() {
-            let fixture_idx = selection.offset_idx(*fixture_id).unwrap();
-
-            let _discrete_value = match self.feature_value {
-                ValueOrRange::Single(value) => value,
-                ValueOrRange::Thru(start, end) => {
-                    let range = end - start;
-                    let step = range / (selection.num_offsets() - 1) as f32;
-                    start + step * fixture_idx as f32
-                }
+            let Some(fixture) = fixture_handler.fixture(*fixture_id) else {
+                continue;
             };
 
-            if let Some(_fixture) = fixture_handler.fixture(*fixture_id) {
-                todo!();
+            let channels = fixture
+                .channels_for_attribute_matches(fixture_types, |attribute| {
+                    self.attributes.iter().any(|set_attribute| {
+                        FixtureChannel3Attribute::attribute_matches(attribute, set_attribute)
+                    })
+                })
+                .map_err(ActionRunError::FixtureError)?
+                .into_iter()
+                .map(|(channel, _, _)| channel.name().as_ref().to_owned())
+                .filter(|channel| fixture.is_channel_selected(channel, &selection))
+                .collect::<Vec<_>>();
+
+            for channel in channels {
+                // cells of sub-fixtures are spread individually
+                let offset_idx = selection
+                    .cell_offset_idx(*fixture_id, fixture.channel_cell(&channel))
+                    .unwrap_or_default();
+
+                let (_, current_value) = fixture
+                    .get_programmer_value(&channel)
+                    .map_err(ActionRunError::FixtureError)?
+                    .get_as_discrete(
+                        fixture,
+                        fixture_types,
+                        &channel,
+                        preset_handler,
+                        timing_handler,
+                    );
+
+                fixture
+                    .update_programmer_value(
+                        fixture_types,
+                        &channel,
+                        FixtureChannelValue3Discrete::Value(self.feature_value.value(
+                            offset_idx,
+                            num_offsets,
+                            current_value,
+                        )),
+                    )
+                    .map_err(ActionRunError::FixtureError)?;
             }
         }
 
@@ -150,3 +227,22 @@ impl FunctionArgs for SetFixturePresetArgs {
         Ok(ActionRunResult::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FeatureValue;
+
+    #[test]
+    fn test_feature_value() {
+        let fan = FeatureValue::Fan(vec![0.0, 1.0, 0.0]);
+        assert_eq!(
+            (0..5).map(|idx| fan.value(idx, 5, 0.3)).collect::<Vec<_>>(),
+            vec![0.0, 0.5, 1.0, 0.5, 0.0]
+        );
+
+        assert_eq!(FeatureValue::Fan(vec![0.7]).value(3, 5, 0.3), 0.7);
+        assert_eq!(FeatureValue::Relative(0.25).value(0, 1, 0.5), 0.75);
+        assert_eq!(FeatureValue::Relative(-0.25).value(0, 1, 0.1), 0.0);
+        assert_eq!(FeatureValue::Scale(0.5).value(0, 1, 0.5), 0.25);
+    }
+}
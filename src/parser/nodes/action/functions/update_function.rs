@@ -84,6 +84,7 @@ impl TryFrom<(Token, u32)> for UpdateSequenceCueArgsId {
             Token::KeywordExecutor => Ok(UpdateSequenceCueArgsId::ExecutorId(id)),
             _ => Err(ParseError::UnexpectedArgs(
                 "Expected 'sequence', 'executor'".to_owned(),
+                None,
             )),
         }
     }
@@ -1,20 +1,22 @@
 use std::{ops::RangeInclusive, time};
 
 use functions::{
-    assign_function::{AssignButtonArgs, AssignFaderArgs},
+    assign_function::{AssignButtonArgs, AssignButtonArgsMode, AssignFaderArgs},
+    copy_function::{CopyArgs, CopyMode, RenumberSequenceCuesArgs},
     create_function::{
         CreateEffectPresetArgs, CreateExecutorArgs, CreateMacroArgs, CreateSequenceArgs,
     },
     delete_function::DeleteArgs,
-    go_function::ExecutorGoArgs,
+    go_function::{ExecutorGoArgs, ExecutorGoBackArgs, ExecutorGotoCueArgs},
+    park_function::{ParkArgs, ParkObject, UnparkArgs},
     recall_function::RecallSequenceCueArgs,
     record_function::{
         RecordGroupArgs, RecordPresetArgs, RecordSequenceCueArgs, RecordSequenceCueShorthandArgs,
     },
     rename_function::RenameObjectArgs,
-    set_function::{SetFeatureValueArgs, SetFixturePresetArgs},
+    set_function::{SelectionOrSelector, SetFeatureValueArgs, SetFixturePresetArgs},
     stop_function::ExecutorStopArgs,
-    update_function::{UpdatePresetArgs, UpdateSequenceCueArgs},
+    update_function::{UpdatePresetArgs, UpdateSequenceCueArgs, UpdateSequenceCueArgsId},
     FunctionArgs,
 };
 use serde::{Deserialize, Serialize};
@@ -24,8 +26,12 @@ use crate::utils::serde::approx_instant;
 
 use crate::{
     fixture::{
-        handler::FixtureHandler, patch::Patch, presets::PresetHandler, selection::FixtureSelection,
-        timing::TimingHandler, updatables::UpdatableHandler,
+        handler::FixtureHandler,
+        patch::Patch,
+        presets::{preset::FixturePresetId, PresetHandler},
+        selection::FixtureSelection,
+        timing::TimingHandler,
+        updatables::UpdatableHandler,
     },
     input::{error::DemexInputDeviceError, DemexInputDeviceHandler},
 };
@@ -48,6 +54,18 @@ pub enum ValueOrRange<T> {
     Thru(T, T),
 }
 
+impl<T: Copy> ValueOrRange<T> {
+    fn map_values(&mut self, map: &impl Fn(T) -> T) {
+        match self {
+            Self::Single(single) => *single = map(*single),
+            Self::Thru(from, to) => {
+                *from = map(*from);
+                *to = map(*to);
+            }
+        }
+    }
+}
+
 impl<T: Copy> From<ValueOrRange<T>> for (T, T) {
     fn from(value: ValueOrRange<T>) -> Self {
         match value {
@@ -115,6 +133,18 @@ pub enum Action {
     // Delete
     Delete(DeleteArgs),
 
+    // Copy
+    Copy(CopyArgs),
+    RenumberSequenceCues(RenumberSequenceCuesArgs),
+
+    // Park
+    Park(ParkArgs),
+    Unpark(UnparkArgs),
+
+    // Playback
+    ExecutorGoBack(ExecutorGoBackArgs),
+    ExecutorGotoCue(ExecutorGotoCueArgs),
+
     // Edit
     Edit(Object),
 
@@ -134,6 +164,8 @@ pub enum Action {
     FixtureSelector(FixtureSelector),
     ClearAll,
     Save,
+    Undo,
+    Redo,
     Test(String),
 
     Config(ConfigTypeActionData),
@@ -193,7 +225,7 @@ impl Action {
                 fixture_selector_context,
             ),
 
-            Self::HomeAll => self.run_home_all(fixture_handler),
+            Self::HomeAll => self.run_home_all(fixture_handler, preset_handler, updatable_handler),
 
             // Record
             Self::RecordPreset(args) => args.run(
@@ -336,6 +368,50 @@ impl Action {
                 patch,
             ),
 
+            // Copy
+            Self::Copy(args) => args.run(
+                issued_at,
+                fixture_handler,
+                preset_handler,
+                fixture_selector_context,
+                updatable_handler,
+                input_device_handler,
+                timing_handler,
+                patch,
+            ),
+            Self::RenumberSequenceCues(args) => args.run(
+                issued_at,
+                fixture_handler,
+                preset_handler,
+                fixture_selector_context,
+                updatable_handler,
+                input_device_handler,
+                timing_handler,
+                patch,
+            ),
+
+            // Park
+            Self::Park(args) => args.run(
+                issued_at,
+                fixture_handler,
+                preset_handler,
+                fixture_selector_context,
+                updatable_handler,
+                input_device_handler,
+                timing_handler,
+                patch,
+            ),
+            Self::Unpark(args) => args.run(
+                issued_at,
+                fixture_handler,
+                preset_handler,
+                fixture_selector_context,
+                updatable_handler,
+                input_device_handler,
+                timing_handler,
+                patch,
+            ),
+
             #[cfg(feature = "ui")]
             Self::Edit(object) => object
                 .clone()
@@ -356,6 +432,9 @@ impl Action {
             Self::Test(_) => Ok(ActionRunResult::new()),
             Self::Save => Ok(ActionRunResult::new()),
 
+            // Undo and redo are handled by the owner of the show history
+            Self::Undo | Self::Redo => Ok(ActionRunResult::new()),
+
             Self::Nuzul => Ok(ActionRunResult::Info("Going down...".to_owned())),
             Self::Sueud => Ok(ActionRunResult::Info("Going up...".to_owned())),
 
@@ -404,6 +483,27 @@ impl Action {
             Self::InternalSetFixtureSelection(selection) => {
                 Ok(ActionRunResult::UpdateSelectedFixtures(selection.clone()))
             }
+            Self::ExecutorGoBack(args) => args.run(
+                issued_at,
+                fixture_handler,
+                preset_handler,
+                fixture_selector_context,
+                updatable_handler,
+                input_device_handler,
+                timing_handler,
+                patch,
+            ),
+            Self::ExecutorGotoCue(args) => args.run(
+                issued_at,
+                fixture_handler,
+                preset_handler,
+                fixture_selector_context,
+                updatable_handler,
+                input_device_handler,
+                timing_handler,
+                patch,
+            ),
+
             Self::InternalExecutorGo(args) => args.run(
                 issued_at,
                 fixture_handler,
@@ -432,10 +532,199 @@ impl Action {
         }
     }
 
+    /// Whether running this action can mutate show data and should
+    /// therefore be recorded in the undo history.
+    /// Input device assignments aren't part of the show history.
+    pub fn is_undoable(&self) -> bool {
+        !matches!(
+            self,
+            Self::Edit(_)
+                | Self::AssignButton(_)
+                | Self::AssignFader(_)
+                | Self::UnassignInputButton { .. }
+                | Self::UnassignInputFader { .. }
+                | Self::FixtureSelector(_)
+                | Self::ClearAll
+                | Self::Save
+                | Self::Undo
+                | Self::Redo
+                | Self::Test(_)
+                | Self::Config(_)
+                | Self::Nuzul
+                | Self::Sueud
+                | Self::InternalSetFixtureSelection(_)
+                | Self::ExecutorGoBack(_)
+                | Self::ExecutorGotoCue(_)
+                | Self::InternalExecutorGo(_)
+                | Self::InternalExecutorStop(_)
+                | Self::MatteoLutz
+        )
+    }
+
+    /// A short, human readable description of the action, e.g. for the undo history.
+    pub fn description(&self) -> String {
+        match self {
+            Self::SetFeatureValue(args) => format!("Set values of {}", args.fixture_selector),
+            Self::SetFixturePreset(_) => "Set preset".to_owned(),
+            Self::Home(_) | Self::HomeAll => "Home".to_owned(),
+            Self::RecordPreset(args) => format!("Record preset {}", args.id),
+            Self::RecordGroup2(args) => match args.id {
+                Some(id) => format!("Record group {}", id),
+                None => "Record group".to_owned(),
+            },
+            Self::RecordSequenceCue(args) => format!("Record sequence {} cue", args.sequence_id),
+            Self::RecordSequenceCueShorthand(_) => "Record cue".to_owned(),
+            Self::Rename(args) => format!("Rename to \"{}\"", args.new_name),
+            Self::CreateSequence(_) => "Create sequence".to_owned(),
+            Self::CreateExecutor(args) => {
+                format!("Create executor for sequence {}", args.sequence_id)
+            }
+            Self::CreateMacro(_) => "Create macro".to_owned(),
+            Self::CreateEffectPreset(args) => format!("Create effect preset {}", args.id),
+            Self::UpdatePreset(args) => format!("Update preset {}", args.id),
+            Self::UpdateSequenceCue(_) => "Update cue".to_owned(),
+            Self::RecallSequenceCue(args) => format!(
+                "Recall sequence {} cue {}.{}",
+                args.sequence_id, args.cue_idx.0, args.cue_idx.1
+            ),
+            Self::Delete(_) => "Delete".to_owned(),
+            Self::Copy(args) => match args.mode {
+                CopyMode::Copy => "Copy".to_owned(),
+                CopyMode::Move => "Move".to_owned(),
+            },
+            Self::RenumberSequenceCues(args) => {
+                format!("Renumber cues of sequence {}", args.sequence_id)
+            }
+            Self::Park(_) => "Park".to_owned(),
+            Self::Unpark(_) => "Unpark".to_owned(),
+            Self::ExecutorGoBack(_) => "Executor go back".to_owned(),
+            Self::InternalExecutorGo(_) => "Executor go".to_owned(),
+            Self::ExecutorGotoCue(_) => "Executor goto cue".to_owned(),
+            Self::InternalExecutorStop(_) => "Executor stop".to_owned(),
+            Self::Edit(_) => "Edit".to_owned(),
+            Self::AssignButton(_) | Self::AssignFader(_) => "Assign".to_owned(),
+            Self::UnassignInputButton { .. } | Self::UnassignInputFader { .. } => {
+                "Unassign".to_owned()
+            }
+            Self::FixtureSelector(fixture_selector) => format!("Select {}", fixture_selector),
+            Self::InternalSetFixtureSelection(_) => "Select fixtures".to_owned(),
+            Self::ClearAll => "Clear".to_owned(),
+            Self::Save => "Save".to_owned(),
+            Self::Undo => "Undo".to_owned(),
+            Self::Redo => "Redo".to_owned(),
+            Self::Test(cmd) => format!("Test \"{}\"", cmd),
+            Self::Config(_) => "Config".to_owned(),
+            Self::Nuzul => "Nuzul".to_owned(),
+            Self::Sueud => "Sueud".to_owned(),
+            Self::MatteoLutz => "Matteo Lutz".to_owned(),
+        }
+    }
+
+    /// Whether this action should be replayed on headless nodes.
+    /// UI only actions and input device assignments are specific to the controller.
+    pub fn is_replicated(&self) -> bool {
+        self.is_undoable()
+            || matches!(
+                self,
+                Self::ExecutorGoBack(_)
+                    | Self::ExecutorGotoCue(_)
+                    | Self::InternalExecutorGo(_)
+                    | Self::InternalExecutorStop(_)
+            )
+    }
+
+    /// Rewrites the groups referenced by the fixture selectors of this action,
+    /// e.g. when it's stored in a macro and the groups were moved.
+    pub fn map_group_ids(&mut self, map: &impl Fn(u32) -> u32) {
+        let fixture_selector = match self {
+            Self::SetFeatureValue(args) => &mut args.fixture_selector,
+            Self::SetFixturePreset(SetFixturePresetArgs {
+                selection_or_selector: SelectionOrSelector::Selector(fixture_selector),
+                ..
+            }) => fixture_selector,
+            Self::Home(HomeableObject::FixtureSelector(fixture_selector)) => fixture_selector,
+            Self::RecordPreset(args) => &mut args.fixture_selector,
+            Self::RecordGroup2(args) => &mut args.fixture_selector,
+            Self::RecordSequenceCue(args) => &mut args.fixture_selector,
+            Self::RecordSequenceCueShorthand(args) => &mut args.fixture_selector,
+            Self::UpdatePreset(args) => &mut args.fixture_selector,
+            Self::UpdateSequenceCue(args) => &mut args.fixture_selector,
+            Self::Park(ParkArgs {
+                object:
+                    ParkObject::FixtureAttributes {
+                        fixture_selector, ..
+                    },
+                ..
+            }) => fixture_selector,
+            Self::AssignButton(args) => match &mut args.mode {
+                AssignButtonArgsMode::FixtureSelector(fixture_selector)
+                | AssignButtonArgsMode::SelectivePreset {
+                    fixture_selector: Some(fixture_selector),
+                    ..
+                } => fixture_selector,
+                AssignButtonArgsMode::Macro(action) => {
+                    action.map_group_ids(map);
+                    return;
+                }
+                _ => return,
+            },
+            Self::FixtureSelector(fixture_selector) => fixture_selector,
+            _ => return,
+        };
+
+        fixture_selector.map_group_ids(map);
+    }
+
+    /// Rewrites the presets referenced by this action, e.g. when it's stored
+    /// in a macro and the presets were moved.
+    pub fn map_preset_ids(&mut self, map: &impl Fn(FixturePresetId) -> FixturePresetId) {
+        match self {
+            Self::SetFixturePreset(args) => args.preset_id.map_values(map),
+            Self::UpdatePreset(args) => args.id = map(args.id),
+            Self::AssignButton(args) => match &mut args.mode {
+                AssignButtonArgsMode::SelectivePreset {
+                    preset_id_range, ..
+                } => preset_id_range.map_values(map),
+                AssignButtonArgsMode::Macro(action) => action.map_preset_ids(map),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Rewrites the sequences referenced by this action, e.g. when it's stored
+    /// in a macro and the sequences were moved.
+    pub fn map_sequence_ids(&mut self, map: &impl Fn(u32) -> u32) {
+        let sequence_id = match self {
+            Self::RecallSequenceCue(args) => &mut args.sequence_id,
+            Self::CreateExecutor(args) => &mut args.sequence_id,
+            Self::RecordSequenceCue(args) => &mut args.sequence_id,
+            Self::UpdateSequenceCue(UpdateSequenceCueArgs {
+                id: UpdateSequenceCueArgsId::SequenceId(sequence_id),
+                ..
+            }) => sequence_id,
+            Self::RenumberSequenceCues(args) => &mut args.sequence_id,
+            Self::AssignButton(AssignButtonArgs {
+                mode: AssignButtonArgsMode::Macro(action),
+                ..
+            }) => {
+                action.map_sequence_ids(map);
+                return;
+            }
+            _ => return,
+        };
+
+        *sequence_id = map(*sequence_id);
+    }
+
     fn run_home_all(
         &self,
         fixture_handler: &mut FixtureHandler,
+        preset_handler: &PresetHandler,
+        updatable_handler: &mut UpdatableHandler,
     ) -> Result<ActionRunResult, ActionRunError> {
+        updatable_handler.executors_stop_all(fixture_handler, preset_handler);
+
         fixture_handler
             .home_all(true)
             .map_err(ActionRunError::FixtureHandlerError)?;
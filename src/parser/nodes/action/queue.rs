@@ -1,6 +1,9 @@
-use std::{collections::VecDeque, time};
+use std::collections::VecDeque;
 
-use crate::parser::nodes::action::{Action, DeferredAction};
+use crate::{
+    parser::nodes::action::{Action, DeferredAction},
+    utils::clock,
+};
 
 #[derive(Default)]
 pub struct ActionQueue {
@@ -11,7 +14,7 @@ impl ActionQueue {
     pub fn enqueue_now(&mut self, action: Action) {
         self.enqueue_deferred(DeferredAction {
             action,
-            issued_at: time::Instant::now(),
+            issued_at: clock::now(),
         });
     }
 
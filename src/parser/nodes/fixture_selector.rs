@@ -49,6 +49,13 @@ impl std::error::Error for FixtureSelectorError {
 pub enum AtomicFixtureSelector {
     SingleFixture(u32),
     FixtureRange(u32, u32),
+
+    /// A cell of a fixture, e.g. `101.3`
+    SubFixture(u32, u16),
+
+    /// A range of cells of a single fixture, e.g. `101.1 thru 101.4`
+    SubFixtureRange(u32, u16, u16),
+
     FixtureGroup(u32),
     SelectorGroup(Box<FixtureSelector>),
     FixtureIdList(Vec<u32>),
@@ -64,7 +71,11 @@ impl AtomicFixtureSelector {
     ) -> Result<FixtureSelection, FixtureSelectorError> {
         match self {
             Self::SingleFixture(f) => Ok(vec![*f].into()),
-            Self::FixtureRange(begin, end) => Ok((*begin..*end + 1).collect::<Vec<_>>().into()),
+            Self::FixtureRange(begin, end) => Ok((*begin..=*end).collect::<Vec<_>>().into()),
+            Self::SubFixture(f, cell) => Ok(FixtureSelection::from_cells(*f, vec![*cell])),
+            Self::SubFixtureRange(f, begin, end) => {
+                Ok(FixtureSelection::from_cells(*f, (*begin..=*end).collect()))
+            }
             Self::SelectorGroup(s) => s.get_selection(preset_handler, context),
             Self::FixtureGroup(id) => {
                 let group = preset_handler
@@ -94,6 +105,14 @@ impl AtomicFixtureSelector {
             _ => None,
         }
     }
+
+    pub fn map_group_ids(&mut self, map: &impl Fn(u32) -> u32) {
+        match self {
+            Self::FixtureGroup(id) => *id = map(*id),
+            Self::SelectorGroup(selector) => selector.map_group_ids(map),
+            _ => {}
+        }
+    }
 }
 
 impl std::fmt::Display for AtomicFixtureSelector {
@@ -103,6 +122,10 @@ impl std::fmt::Display for AtomicFixtureSelector {
             Self::FixtureGroup(group_id) => write!(f, "Group {}", group_id),
             Self::FixtureIdList(id_list) => write!(f, "{:?}", id_list),
             Self::FixtureRange(from, to) => write!(f, "{} thru {}", from, to),
+            Self::SubFixture(id, cell) => write!(f, "{}.{}", id, cell),
+            Self::SubFixtureRange(id, from, to) => {
+                write!(f, "{}.{} thru {}.{}", id, from, id, to)
+            }
             Self::SelectorGroup(selector) => write!(f, "({})", selector),
             Self::SingleFixture(id) => write!(f, "{}", id),
             Self::None => write!(f, "None"),
@@ -173,6 +196,17 @@ impl FixtureSelector {
             _ => None,
         }
     }
+
+    /// Rewrites the referenced groups, e.g. after they were moved
+    pub fn map_group_ids(&mut self, map: &impl Fn(u32) -> u32) {
+        match self {
+            Self::Atomic(a) | Self::Modulus(a, _, _) => a.map_group_ids(map),
+            Self::Additive(a, b) | Self::Subtractive(a, b) => {
+                a.map_group_ids(map);
+                b.map_group_ids(map);
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for FixtureSelector {
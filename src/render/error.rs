@@ -0,0 +1,38 @@
+use crate::fixture::{error::FixtureError, handler::error::FixtureHandlerError};
+
+#[derive(Debug)]
+pub enum DemexRenderError {
+    IOError(std::io::Error),
+    InvalidScriptLine(usize, String),
+    InvalidCommand(String, String),
+    InvalidPatch(String),
+    FixtureError(FixtureError),
+    FixtureHandlerError(FixtureHandlerError),
+}
+
+impl std::fmt::Display for DemexRenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DemexRenderError::IOError(err) => write!(f, "IO error: {}", err),
+            DemexRenderError::InvalidScriptLine(line, reason) => {
+                write!(f, "Invalid script line {}: {}", line, reason)
+            }
+            DemexRenderError::InvalidCommand(command, err) => {
+                write!(f, "Invalid command \"{}\": {}", command, err)
+            }
+            DemexRenderError::InvalidPatch(issues) => {
+                write!(f, "The patch of the show is invalid:\n{}", issues)
+            }
+            DemexRenderError::FixtureError(err) => write!(f, "Fixture error: {}", err),
+            DemexRenderError::FixtureHandlerError(err) => {
+                write!(f, "Fixture handler error: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DemexRenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
@@ -0,0 +1,400 @@
+use std::{io, sync::Arc, time};
+
+use itertools::Itertools;
+use parking_lot::RwLock;
+
+use crate::{
+    dmx::{
+        debug::{DebugOutputConfig, DebugOutputVerbosity},
+        routing::DmxRouting,
+        DemexDmxOutput, DemexDmxOutputConfig, DemexDmxOutputConfigData,
+    },
+    fixture::handler::FixtureHandler,
+    headless::{controller::DemexHeadlessConroller, id::DemexProtoDeviceId, repl::DemexRepl},
+    input::timecode::{packet::TimecodePacket, rate::TimecodeRate},
+    show::{context::ShowContext, DemexShow},
+    utils::clock,
+};
+
+use error::DemexRenderError;
+use script::DemexRenderScriptEntry;
+
+pub mod error;
+pub mod script;
+
+#[derive(Debug, Clone)]
+pub struct DemexRenderConfig {
+    /// Number of rendered frames per second
+    pub fps: f64,
+
+    /// Rendered duration in seconds, defaults to the time of the last command of the script
+    pub duration: Option<f64>,
+}
+
+// Everything is rendered on the same (virtual) clock and fed the same, so there
+// are no outputs (e.g. Art-Net) and the timecode is derived from the clock.
+fn create_show_context(
+    fixture_types: Vec<gdtf::fixture_type::FixtureType>,
+    show: &DemexShow,
+) -> Result<ShowContext, DemexRenderError> {
+    let patch = show.patch.clone().into_patch(fixture_types);
+
+    let patch_issues = patch.validate();
+    if !patch_issues.is_empty() {
+        return Err(DemexRenderError::InvalidPatch(
+            patch_issues.iter().join("\n"),
+        ));
+    }
+
+    let fixtures = patch
+        .fixtures()
+        .iter()
+        .cloned()
+        .map(|fixture| fixture.into_fixture(patch.fixture_types()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(DemexRenderError::FixtureError)?;
+
+    // a silent debug output, so the fixture handler generates data for every patched universe
+    let universes = patch
+        .fixtures()
+        .iter()
+        .map(|fixture| fixture.universe)
+        .sorted()
+        .dedup()
+        .collect::<Vec<_>>();
+    let output = DemexDmxOutput::from_config(
+        DemexDmxOutputConfig::new(
+            DemexDmxOutputConfigData::Debug(DebugOutputConfig {
+                verbosity: DebugOutputVerbosity::Silent,
+                universes,
+            }),
+            DemexProtoDeviceId::Controller,
+        ),
+        DemexProtoDeviceId::Controller,
+    );
+
+    // the debug output records the logical universes, so no routing is applied
+    let mut fixture_handler =
+        FixtureHandler::new(fixtures, vec![output], DmxRouting::default(), true)
+            .map_err(DemexRenderError::FixtureHandlerError)?;
+    *fixture_handler.park_handler_mut() = show.park_handler.clone();
+
+    // the timecode is derived from the clock, so every timecode of the show runs along
+    let mut timing_handler = show.timing_handler.clone();
+    for timecode in timing_handler.timecodes_mut().values_mut() {
+        timecode.start(0);
+    }
+
+    Ok(ShowContext {
+        fixture_handler: Arc::new(RwLock::new(fixture_handler)),
+        preset_handler: Arc::new(RwLock::new(show.preset_handler.clone())),
+        updatable_handler: Arc::new(RwLock::new(show.updatable_handler.clone())),
+        timing_handler: Arc::new(RwLock::new(timing_handler)),
+        patch: Arc::new(RwLock::new(patch)),
+    })
+}
+
+fn render_frame(
+    show_context: &ShowContext,
+    repl: &mut DemexRepl,
+    frame_time: f64,
+) -> Result<(), DemexRenderError> {
+    {
+        let mut fixture_handler = show_context.fixture_handler.write();
+        let preset_handler = show_context.preset_handler.read();
+        let mut updatable_handler = show_context.updatable_handler.write();
+        let mut timing_handler = show_context.timing_handler.write();
+        let patch = show_context.patch.read();
+
+        let timecode_rate = TimecodeRate::default();
+        timing_handler.update_timecode(
+            TimecodePacket::from_frame(
+                (frame_time * timecode_rate.frames_per_second() as f64) as u64,
+                timecode_rate,
+            ),
+            patch.fixture_types(),
+            &mut fixture_handler,
+            &preset_handler,
+            &mut updatable_handler,
+            repl.action_queue_mut(),
+        );
+
+        updatable_handler.update_pixel_maps(
+            patch.fixture_types(),
+            patch.layout(),
+            &mut fixture_handler,
+        );
+
+        fixture_handler
+            .update_output_values(
+                patch.fixture_types(),
+                &preset_handler,
+                &updatable_handler,
+                &timing_handler,
+            )
+            .map_err(DemexRenderError::FixtureHandlerError)?;
+        updatable_handler.update_executors(
+            patch.fixture_types(),
+            &fixture_handler,
+            &preset_handler,
+            &timing_handler,
+        );
+        fixture_handler
+            .generate_output_data(
+                patch.fixture_types(),
+                &preset_handler,
+                &timing_handler,
+                true,
+            )
+            .map_err(DemexRenderError::FixtureHandlerError)?;
+    }
+
+    // actions triggered by timecodes
+    repl.execute_action_queue(&mut io::stderr())
+        .map_err(DemexRenderError::IOError)
+}
+
+/// Renders the show on a virtual clock, running the commands of the script at their time,
+/// and writes the data of every patched universe per frame to `out`, one line per frame and universe:
+/// `<frame> <time> <universe> <data as hex>`
+pub fn render_show(
+    fixture_types: Vec<gdtf::fixture_type::FixtureType>,
+    show: DemexShow,
+    script: &[DemexRenderScriptEntry],
+    config: &DemexRenderConfig,
+    out: &mut impl io::Write,
+) -> Result<(), DemexRenderError> {
+    clock::start_virtual_clock();
+
+    let show_context = create_show_context(fixture_types, &show)?;
+    let mut repl = DemexRepl::new(
+        show_context.clone(),
+        DemexHeadlessConroller::default(),
+        show,
+        None,
+    );
+
+    let duration = config
+        .duration
+        .unwrap_or_else(|| script.last().map(|entry| entry.time).unwrap_or_default());
+    let num_frames = (duration * config.fps).floor() as u64 + 1;
+
+    let mut script = script.iter().peekable();
+
+    for frame in 0..num_frames {
+        let frame_time = frame as f64 / config.fps;
+
+        while let Some(entry) = script.next_if(|entry| entry.time <= frame_time) {
+            clock::set_virtual_time(time::Duration::from_secs_f64(entry.time));
+
+            repl.enqueue_line(&entry.command).map_err(|err| {
+                DemexRenderError::InvalidCommand(entry.command.clone(), err.to_string())
+            })?;
+            repl.execute_action_queue(&mut io::stderr())
+                .map_err(DemexRenderError::IOError)?;
+        }
+
+        clock::set_virtual_time(time::Duration::from_secs_f64(frame_time));
+        render_frame(&show_context, &mut repl, frame_time)?;
+
+        let fixture_handler = show_context.fixture_handler.read();
+        for (universe, data) in fixture_handler
+            .universe_output_data()
+            .iter()
+            .sorted_by_key(|(universe, _)| **universe)
+        {
+            writeln!(
+                out,
+                "{} {:.4} {} {}",
+                frame,
+                frame_time,
+                universe,
+                data.iter().map(|value| format!("{:02x}", value)).join("")
+            )
+            .map_err(DemexRenderError::IOError)?;
+        }
+    }
+
+    out.flush().map_err(DemexRenderError::IOError)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use crate::{
+        fixture::{
+            channel3::{
+                channel_value::FixtureChannelValue3,
+                feature::feature_group::FixtureChannel3FeatureGroup,
+            },
+            effect2::{effect::Effect2Part, wave::wave_type::WaveType},
+            presets::preset::{FixturePresetData, FixturePresetId},
+            selection::FixtureSelection,
+            sequence::cue::{Cue, CueFixtureChannelValue, CueTiming, CueTrigger},
+            timing::timecode::Timecode,
+        },
+        show::DemexShow,
+    };
+
+    use super::{render_show, script::parse_render_script, DemexRenderConfig};
+
+    const FIXTURE_TYPE_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_data/fixtures/GDTF_Template_a@Basic_Conventional@test.gdtf"
+    );
+    const GOLDEN_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_data/render/dimmers.golden"
+    );
+    const PLAYBACK_GOLDEN_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_data/render/playback.golden"
+    );
+
+    fn fixture_types() -> Vec<gdtf::fixture_type::FixtureType> {
+        gdtf::GdtfFile::new(fs::File::open(FIXTURE_TYPE_FILE).unwrap())
+            .unwrap()
+            .description
+            .fixture_types
+    }
+
+    // dimmers on the first addresses of universe 0
+    fn dimmers_show(num_dimmers: u32) -> DemexShow {
+        let patch = serde_json::from_value(serde_json::json!({
+            "fixtures": (1..=num_dimmers).map(|id| serde_json::json!({
+                "id": id,
+                "name": format!("Dimmer {}", id),
+                "fixture_type_id": "6b9acb11-a69b-4e1a-a667-ac31cfe8655c",
+                "fixture_type_dmx_mode": "Standard mode",
+                "universe": 0,
+                "start_address": id,
+            })).collect::<Vec<_>>(),
+            "layout": { "fixtures": [], "decorations": [] },
+            "outputs": [],
+        }))
+        .unwrap();
+
+        DemexShow {
+            patch,
+            ..Default::default()
+        }
+    }
+
+    fn render(
+        fixture_types: Vec<gdtf::fixture_type::FixtureType>,
+        show: DemexShow,
+        script: &str,
+        config: DemexRenderConfig,
+    ) -> String {
+        let script = parse_render_script(script).unwrap();
+
+        let mut out = Vec::new();
+        render_show(fixture_types, show, &script, &config, &mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_render_show_golden() {
+        let out = render(
+            fixture_types(),
+            dimmers_show(3),
+            "t=0 \"1 thru 3 intens 0 thru 100\"\nt=0.5 \"2 intens full\"\nt=1 \"1 thru 3 intens out\"",
+            DemexRenderConfig {
+                fps: 2.0,
+                duration: None,
+            },
+        );
+
+        assert_eq!(out, fs::read_to_string(GOLDEN_FILE).unwrap());
+    }
+
+    #[test]
+    fn test_render_playback_golden() {
+        let fixture_types = fixture_types();
+        let mut show = dimmers_show(2);
+
+        let dimmer_channel = fixture_types[0]
+            .dmx_mode("Standard mode")
+            .unwrap()
+            .dmx_channels
+            .iter()
+            .find(|dmx_channel| dmx_channel.offset.is_some())
+            .unwrap()
+            .name()
+            .as_ref()
+            .to_owned();
+
+        // executor 1 fades dimmer 1 to full, the fade doesn't end on a frame
+        show.preset_handler.create_sequence(1, None).unwrap();
+        show.preset_handler
+            .get_sequence_mut(1)
+            .unwrap()
+            .add_cue(Cue::new(
+                (1, 0),
+                HashMap::from([(
+                    1,
+                    vec![CueFixtureChannelValue::new(
+                        FixtureChannelValue3::Discrete {
+                            channel_function_idx: 0,
+                            value: 1.0,
+                        },
+                        dimmer_channel,
+                        false,
+                    )],
+                )]),
+                FixtureSelection::from(vec![1]),
+                0.65,
+                0.0,
+                0.0,
+                CueTiming::default(),
+                CueTrigger::Manual,
+            ));
+        show.updatable_handler.create_executor(1, 1).unwrap();
+
+        // timecode 1 starts executor 1 at 0.2s and stops it at 1.6s
+        let timecode: Timecode = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "Timecode 1",
+            "triggers": [
+                { "trigger_type": { "ExecutorGo": 1 }, "millis": 200 },
+                { "trigger_type": { "ExecutorStop": 1 }, "millis": 1600 },
+            ],
+        }))
+        .unwrap();
+        show.timing_handler.timecodes_mut().insert(1, timecode);
+
+        // a square wave on the dimmer, at 120 bpm one period takes 5 frames
+        let effect_preset_id = FixturePresetId {
+            feature_group: FixtureChannel3FeatureGroup::Dimmer,
+            preset_id: 1,
+        };
+        show.preset_handler
+            .create_effect_preset(effect_preset_id, None)
+            .unwrap();
+        if let FixturePresetData::FeatureEffect { runtime } = show
+            .preset_handler
+            .get_preset_mut(effect_preset_id)
+            .unwrap()
+            .data_mut()
+        {
+            let mut part = Effect2Part::default();
+            *part.wave_mut().wave_type_mut() = WaveType::Square;
+            part.attributes_mut().push("Dimmer".to_owned());
+            runtime.effect_mut().parts_mut().push(part);
+        }
+
+        let out = render(
+            fixture_types,
+            show,
+            "t=0 \"2 preset 0.1\"",
+            DemexRenderConfig {
+                fps: 10.0,
+                duration: Some(2.0),
+            },
+        );
+
+        assert_eq!(out, fs::read_to_string(PLAYBACK_GOLDEN_FILE).unwrap());
+    }
+}
@@ -0,0 +1,98 @@
+use super::error::DemexRenderError;
+
+const TIME_PREFIX: &str = "t=";
+const COMMENT_PREFIX: char = '#';
+
+/// A command, that is run at `time` seconds after the render started.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemexRenderScriptEntry {
+    pub time: f64,
+    pub command: String,
+}
+
+/// Parses a render script, consisting of one timed command per line (e.g. `t=2.5 "go executor 1"`).
+/// Empty lines and lines starting with `#` are ignored, the commands have to be in chronological order.
+pub fn parse_render_script(script: &str) -> Result<Vec<DemexRenderScriptEntry>, DemexRenderError> {
+    let mut entries: Vec<DemexRenderScriptEntry> = Vec::new();
+
+    for (idx, line) in script.lines().enumerate() {
+        let line_number = idx + 1;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with(COMMENT_PREFIX) {
+            continue;
+        }
+
+        let invalid_line =
+            |reason: &str| DemexRenderError::InvalidScriptLine(line_number, reason.to_owned());
+
+        let (time, command) = line
+            .strip_prefix(TIME_PREFIX)
+            .and_then(|line| line.split_once(char::is_whitespace))
+            .ok_or_else(|| invalid_line("Expected t=<seconds> \"<command>\""))?;
+
+        let time = time
+            .parse::<f64>()
+            .ok()
+            .filter(|time| time.is_finite() && *time >= 0.0)
+            .ok_or_else(|| invalid_line("Invalid time"))?;
+
+        let command = command
+            .trim()
+            .strip_prefix('"')
+            .and_then(|command| command.strip_suffix('"'))
+            .ok_or_else(|| invalid_line("Expected a quoted command"))?;
+
+        if entries.last().is_some_and(|entry| entry.time > time) {
+            return Err(invalid_line("Commands have to be in chronological order"));
+        }
+
+        entries.push(DemexRenderScriptEntry {
+            time,
+            command: command.to_owned(),
+        });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render::error::DemexRenderError;
+
+    use super::{parse_render_script, DemexRenderScriptEntry};
+
+    #[test]
+    fn test_parse_render_script() {
+        let script = "# intro\nt=0 \"go executor 1\"\n\nt=2.5   \"stop executor 1\"\n";
+
+        assert_eq!(
+            parse_render_script(script).unwrap(),
+            vec![
+                DemexRenderScriptEntry {
+                    time: 0.0,
+                    command: "go executor 1".to_owned()
+                },
+                DemexRenderScriptEntry {
+                    time: 2.5,
+                    command: "stop executor 1".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_render_script_invalid() {
+        for script in [
+            "go executor 1",
+            "t=abc \"go executor 1\"",
+            "t=1 go executor 1",
+            "t=2 \"go executor 1\"\nt=1 \"stop executor 1\"",
+        ] {
+            assert!(matches!(
+                parse_render_script(script),
+                Err(DemexRenderError::InvalidScriptLine(_, _))
+            ));
+        }
+    }
+}
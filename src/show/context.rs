@@ -4,7 +4,7 @@ use parking_lot::RwLock;
 
 use crate::{
     fixture::{
-        handler::FixtureHandler,
+        handler::{park::ParkHandler, FixtureHandler},
         patch::{Patch, SerializablePatch},
         presets::PresetHandler,
         timing::TimingHandler,
@@ -31,12 +31,16 @@ impl ShowContext {
             .into_patch(self.patch.read().fixture_types().to_vec());
         let (fixtures, outputs) = patch.into_fixures_and_outputs(own_device_id);
 
-        *self.fixture_handler.write() = FixtureHandler::new(
+        let mut fixture_handler = FixtureHandler::new(
             fixtures,
             outputs,
+            patch.routing().clone(),
             own_device_id == DemexProtoDeviceId::Controller,
         )
         .unwrap();
+        *fixture_handler.park_handler_mut() = show.park_handler;
+
+        *self.fixture_handler.write() = fixture_handler;
         *self.preset_handler.write() = show.preset_handler;
         *self.updatable_handler.write() = show.updatable_handler;
         *self.timing_handler.write() = show.timing_handler;
@@ -49,19 +53,27 @@ impl ShowContext {
         preset_handler: PresetHandler,
         updatable_handler: UpdatableHandler,
         timing_handler: TimingHandler,
+        park_handler: ParkHandler,
         own_device_id: DemexProtoDeviceId,
     ) -> Self {
-        let patch = Arc::new(RwLock::new(patch.into_patch(fixture_types)));
+        let patch = patch.into_patch(fixture_types);
+        for issue in patch.validate() {
+            log::warn!("Patch issue: {}", issue);
+        }
+
+        let patch = Arc::new(RwLock::new(patch));
         let (fixtures, outputs) = patch.read().into_fixures_and_outputs(own_device_id);
 
-        let fixture_handler = Arc::new(RwLock::new(
-            FixtureHandler::new(
-                fixtures,
-                outputs,
-                own_device_id == DemexProtoDeviceId::Controller,
-            )
-            .unwrap(),
-        ));
+        let mut fixture_handler = FixtureHandler::new(
+            fixtures,
+            outputs,
+            patch.read().routing().clone(),
+            own_device_id == DemexProtoDeviceId::Controller,
+        )
+        .unwrap();
+        *fixture_handler.park_handler_mut() = park_handler;
+
+        let fixture_handler = Arc::new(RwLock::new(fixture_handler));
 
         let preset_handler = Arc::new(RwLock::new(preset_handler));
         let updatable_handler = Arc::new(RwLock::new(updatable_handler));
@@ -0,0 +1,93 @@
+use std::time;
+
+use crate::{
+    fixture::selection::FixtureSelection,
+    headless::controller::DemexHeadlessConroller,
+    input::DemexInputDeviceHandler,
+    parser::nodes::{
+        action::{result::ActionRunResult, Action, DeferredAction},
+        fixture_selector::FixtureSelectorContext,
+    },
+};
+
+use super::{
+    context::ShowContext,
+    history::{ShowHistory, ShowSnapshot},
+};
+
+/// Runs actions on a show for the UI and the repl, which only handle the
+/// results and their own state (e.g. saving or the fixture selection).
+pub struct ShowActionDispatcher<'a> {
+    pub show_context: &'a ShowContext,
+    pub fixture_selection: &'a Option<FixtureSelection>,
+    pub input_device_handler: &'a mut DemexInputDeviceHandler,
+    pub history: &'a mut ShowHistory,
+    pub headless_controller: &'a DemexHeadlessConroller,
+}
+
+impl ShowActionDispatcher<'_> {
+    /// Runs `action`. Undoable actions are recorded in the history and replicated
+    /// actions are sent to the headless nodes. Undo and redo restore the history.
+    pub fn dispatch(
+        &mut self,
+        action: &Action,
+        issued_at: time::Instant,
+    ) -> Result<ActionRunResult, Box<dyn std::error::Error>> {
+        match action {
+            Action::Undo => return self.undo().map(ActionRunResult::Info),
+            Action::Redo => return self.redo().map(ActionRunResult::Info),
+            _ => {}
+        }
+
+        let snapshot = action
+            .is_undoable()
+            .then(|| ShowSnapshot::capture_show(self.show_context));
+
+        let result = action.run(
+            &mut self.show_context.fixture_handler.write(),
+            &mut self.show_context.preset_handler.write(),
+            FixtureSelectorContext::new(self.fixture_selection),
+            &mut self.show_context.updatable_handler.write(),
+            self.input_device_handler,
+            &mut self.show_context.timing_handler.write(),
+            &self.show_context.patch.read(),
+            issued_at,
+        )?;
+
+        if let Some(snapshot) = snapshot {
+            self.history.record(action.description(), snapshot);
+        }
+
+        if action.is_replicated() {
+            self.headless_controller.broadcast_action(
+                DeferredAction {
+                    action: action.clone(),
+                    issued_at,
+                },
+                self.fixture_selection.clone(),
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn undo(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        let current = ShowSnapshot::capture_show(self.show_context);
+        let (description, snapshot) = self.history.undo(current).ok_or("Nothing to undo")?;
+
+        snapshot.restore_show(self.show_context);
+        self.headless_controller.broadcast_show_file_update();
+
+        Ok(format!("Undid {}", description))
+    }
+
+    fn redo(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        let current = ShowSnapshot::capture_show(self.show_context);
+        let (description, snapshot) = self.history.redo(current).ok_or("Nothing to redo")?;
+
+        snapshot.restore_show(self.show_context);
+        self.headless_controller.broadcast_show_file_update();
+
+        Ok(format!("Redid {}", description))
+    }
+}
@@ -0,0 +1,38 @@
+#[derive(Debug)]
+pub enum DemexShowError {
+    IOError(std::io::Error),
+    SerdeJsonError(serde_json::Error),
+    CborError(String),
+    UnsupportedFormatVersion(u32),
+    MigrationFailed(u32, String),
+}
+
+impl std::fmt::Display for DemexShowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IOError(err) => write!(f, "IO error: {}", err),
+            Self::SerdeJsonError(err) => write!(f, "serde_json error: {}", err),
+            Self::CborError(err) => write!(f, "CBOR error: {}", err),
+            Self::UnsupportedFormatVersion(version) => write!(
+                f,
+                "Show file format version {} is not supported by this version of demex",
+                version
+            ),
+            Self::MigrationFailed(from_version, err) => write!(
+                f,
+                "Failed to migrate show file from format version {}: {}",
+                from_version, err
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DemexShowError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IOError(err) => Some(err),
+            Self::SerdeJsonError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
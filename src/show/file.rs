@@ -0,0 +1,330 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use serde::Serialize;
+
+use crate::utils::version::VERSION_STR;
+
+use super::{error::DemexShowError, DemexShow};
+
+/// The current version of the show file format. Every time the
+/// serialized structure of `DemexShow` changes in an incompatible way,
+/// this has to be incremented and a migration has to be added to `DEMEX_SHOW_MIGRATIONS`.
+pub const DEMEX_SHOW_FORMAT_VERSION: u32 = 2;
+
+const FORMAT_VERSION_KEY: &str = "format_version";
+const SHOW_KEY: &str = "show";
+
+type DemexShowMigration = fn(serde_json::Value) -> Result<serde_json::Value, String>;
+
+/// `DEMEX_SHOW_MIGRATIONS[n]` migrates the show data from format version `n` to `n + 1`.
+const DEMEX_SHOW_MIGRATIONS: [DemexShowMigration; DEMEX_SHOW_FORMAT_VERSION as usize] =
+    [migrate_v0_to_v1, migrate_v1_to_v2];
+
+// Version 0 show files are plain serialized shows without an envelope.
+// The show data itself didn't change with version 1.
+fn migrate_v0_to_v1(show: serde_json::Value) -> Result<serde_json::Value, String> {
+    if !show.is_object() {
+        return Err("Expected the show to be an object".to_owned());
+    }
+
+    Ok(show)
+}
+
+// With version 2, the debug output config holds the output universes
+// in addition to the verbosity. Version 1 debug outputs didn't record
+// anything, so they are migrated to silent outputs.
+fn migrate_v1_to_v2(mut show: serde_json::Value) -> Result<serde_json::Value, String> {
+    let Some(outputs) = show
+        .pointer_mut("/patch/outputs")
+        .and_then(|outputs| outputs.as_array_mut())
+    else {
+        return Ok(show);
+    };
+
+    for output in outputs {
+        let Some(debug) = output.pointer_mut("/data/Debug") else {
+            continue;
+        };
+
+        if debug.is_string() {
+            *debug = serde_json::json!({
+                "verbosity": "Silent",
+                "universes": [],
+            });
+        }
+    }
+
+    Ok(show)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum DemexShowFileFormat {
+    #[default]
+    Json,
+
+    /// Compact binary encoding
+    Cbor,
+}
+
+impl DemexShowFileFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("cbor") => Self::Cbor,
+            _ => Self::Json,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DemexShowEnvelope<'a> {
+    format_version: u32,
+    demex_version: &'a str,
+    show: &'a DemexShow,
+}
+
+fn cbor_to_json(value: ciborium::Value) -> Result<serde_json::Value, DemexShowError> {
+    Ok(match value {
+        ciborium::Value::Null => serde_json::Value::Null,
+        ciborium::Value::Bool(b) => serde_json::Value::Bool(b),
+        ciborium::Value::Integer(integer) => {
+            let integer = i128::from(integer);
+
+            if let Ok(integer) = i64::try_from(integer) {
+                serde_json::Value::from(integer)
+            } else if let Ok(integer) = u64::try_from(integer) {
+                serde_json::Value::from(integer)
+            } else {
+                return Err(DemexShowError::CborError(format!(
+                    "Integer {} is out of range",
+                    integer
+                )));
+            }
+        }
+        ciborium::Value::Float(float) => serde_json::Number::from_f64(float)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        ciborium::Value::Text(text) => serde_json::Value::String(text),
+        ciborium::Value::Bytes(bytes) => {
+            serde_json::Value::Array(bytes.into_iter().map(serde_json::Value::from).collect())
+        }
+        ciborium::Value::Tag(_, value) => cbor_to_json(*value)?,
+        ciborium::Value::Array(values) => serde_json::Value::Array(
+            values
+                .into_iter()
+                .map(cbor_to_json)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        ciborium::Value::Map(entries) => {
+            let mut map = serde_json::Map::with_capacity(entries.len());
+
+            for (key, value) in entries {
+                // JSON only knows string keys, so integer keys are stored
+                // the same way serde_json serializes them
+                let key = match key {
+                    ciborium::Value::Text(text) => text,
+                    ciborium::Value::Integer(integer) => i128::from(integer).to_string(),
+                    key => {
+                        return Err(DemexShowError::CborError(format!(
+                            "Unsupported map key: {:?}",
+                            key
+                        )))
+                    }
+                };
+
+                map.insert(key, cbor_to_json(value)?);
+            }
+
+            serde_json::Value::Object(map)
+        }
+        value => {
+            return Err(DemexShowError::CborError(format!(
+                "Unsupported value: {:?}",
+                value
+            )))
+        }
+    })
+}
+
+fn migrate(data: serde_json::Value) -> Result<serde_json::Value, DemexShowError> {
+    let (format_version, mut show) = match data {
+        serde_json::Value::Object(mut envelope) if envelope.contains_key(FORMAT_VERSION_KEY) => {
+            let format_version = envelope
+                .get(FORMAT_VERSION_KEY)
+                .and_then(|version| version.as_u64())
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or(DemexShowError::MigrationFailed(
+                    0,
+                    "Invalid format version".to_owned(),
+                ))?;
+
+            let show = envelope
+                .remove(SHOW_KEY)
+                .ok_or(DemexShowError::MigrationFailed(
+                    format_version,
+                    "Missing show data".to_owned(),
+                ))?;
+
+            (format_version, show)
+        }
+        show => (0, show),
+    };
+
+    if format_version > DEMEX_SHOW_FORMAT_VERSION {
+        return Err(DemexShowError::UnsupportedFormatVersion(format_version));
+    }
+
+    for version in format_version..DEMEX_SHOW_FORMAT_VERSION {
+        log::info!(
+            "Migrating show file from format version {} to {}",
+            version,
+            version + 1
+        );
+
+        show = DEMEX_SHOW_MIGRATIONS[version as usize](show)
+            .map_err(|err| DemexShowError::MigrationFailed(version, err))?;
+    }
+
+    Ok(show)
+}
+
+impl DemexShow {
+    pub fn from_reader(
+        reader: impl Read,
+        format: DemexShowFileFormat,
+    ) -> Result<Self, DemexShowError> {
+        let data = match format {
+            DemexShowFileFormat::Json => {
+                serde_json::from_reader(reader).map_err(DemexShowError::SerdeJsonError)?
+            }
+            DemexShowFileFormat::Cbor => {
+                let value: ciborium::Value = ciborium::from_reader(reader)
+                    .map_err(|err| DemexShowError::CborError(err.to_string()))?;
+                cbor_to_json(value)?
+            }
+        };
+
+        serde_json::from_value(migrate(data)?).map_err(DemexShowError::SerdeJsonError)
+    }
+
+    pub fn to_writer(
+        &self,
+        writer: impl Write,
+        format: DemexShowFileFormat,
+    ) -> Result<(), DemexShowError> {
+        let envelope = DemexShowEnvelope {
+            format_version: DEMEX_SHOW_FORMAT_VERSION,
+            demex_version: VERSION_STR,
+            show: self,
+        };
+
+        match format {
+            DemexShowFileFormat::Json => {
+                serde_json::to_writer(writer, &envelope).map_err(DemexShowError::SerdeJsonError)
+            }
+            DemexShowFileFormat::Cbor => ciborium::into_writer(&envelope, writer)
+                .map_err(|err| DemexShowError::CborError(err.to_string())),
+        }
+    }
+
+    /// Loads a show file, migrating it from older format versions if necessary.
+    /// The encoding is determined by the file extension.
+    pub fn load(path: &Path) -> Result<Self, DemexShowError> {
+        let file = fs::File::open(path).map_err(DemexShowError::IOError)?;
+        Self::from_reader(
+            io::BufReader::new(file),
+            DemexShowFileFormat::from_path(path),
+        )
+    }
+
+    /// Saves the show in the current format version.
+    /// The encoding is determined by the file extension.
+    pub fn save(&self, path: &Path) -> Result<(), DemexShowError> {
+        let file = fs::File::create(path).map_err(DemexShowError::IOError)?;
+        let mut writer = io::BufWriter::new(file);
+
+        self.to_writer(&mut writer, DemexShowFileFormat::from_path(path))?;
+        writer.flush().map_err(DemexShowError::IOError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{dmx::debug::DebugOutputConfig, fixture::handler::park::ParkTarget};
+
+    use super::*;
+
+    #[test]
+    fn test_show_file_roundtrip() {
+        let mut show = DemexShow::default();
+        show.preset_handler
+            .record_group(vec![1, 2].into(), 1, Some("Front".to_owned()))
+            .unwrap();
+        show.park_handler.park(
+            ParkTarget::Address {
+                universe: 1,
+                address: 101,
+            },
+            0.5,
+        );
+
+        for format in [DemexShowFileFormat::Json, DemexShowFileFormat::Cbor] {
+            let mut buf = Vec::new();
+            show.to_writer(&mut buf, format).unwrap();
+
+            let decoded = DemexShow::from_reader(&buf[..], format).unwrap();
+            assert_eq!(
+                serde_json::to_value(&decoded).unwrap(),
+                serde_json::to_value(&show).unwrap(),
+                "{:?} roundtrip changed the show",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_show_file_migrate_v0() {
+        let show = serde_json::json!({ "some": "show" });
+        assert_eq!(migrate(show.clone()).unwrap(), show);
+    }
+
+    #[test]
+    fn test_show_file_migrate_v1_debug_output() {
+        let envelope = serde_json::json!({
+            FORMAT_VERSION_KEY: 1,
+            SHOW_KEY: {
+                "patch": {
+                    "outputs": [
+                        { "data": { "Debug": "Quiet" }, "device_id": "Controller" },
+                    ],
+                },
+            },
+        });
+
+        let mut show = migrate(envelope).unwrap();
+        let debug = show["patch"]["outputs"][0]["data"]["Debug"].take();
+        assert_eq!(
+            debug,
+            serde_json::json!({ "verbosity": "Silent", "universes": [] })
+        );
+
+        let config: DebugOutputConfig = serde_json::from_value(debug).unwrap();
+        assert!(!config.is_recording());
+    }
+
+    #[test]
+    fn test_show_file_unsupported_version() {
+        let envelope = serde_json::json!({
+            FORMAT_VERSION_KEY: DEMEX_SHOW_FORMAT_VERSION + 1,
+            SHOW_KEY: {},
+        });
+
+        assert!(matches!(
+            migrate(envelope),
+            Err(DemexShowError::UnsupportedFormatVersion(_))
+        ));
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time,
+};
+
+use crate::fixture::{
+    channel3::channel_value::FixtureChannelValue3,
+    handler::{park::ParkHandler, FixtureHandler},
+    presets::PresetHandler,
+    timing::TimingHandler,
+    updatables::UpdatableHandler,
+    value_source::FixtureChannelValueSource,
+};
+
+use super::context::ShowContext;
+
+pub const SHOW_HISTORY_DEFAULT_MAX_ENTRIES: usize = 50;
+
+// programmer values and whether the programmer is a value source of the fixture
+type FixtureProgrammerState = (HashMap<String, FixtureChannelValue3>, bool);
+
+/// A snapshot of every piece of show data, that can be mutated by running an action.
+/// The patch isn't part of the snapshot, because actions only get immutable access to it.
+/// The playback state of executors and pixel maps isn't part of it either, so restoring
+/// a snapshot doesn't rewind the playback.
+#[derive(Debug, Clone)]
+pub struct ShowSnapshot {
+    preset_handler: PresetHandler,
+    updatable_handler: UpdatableHandler,
+    timing_handler: TimingHandler,
+    park_handler: ParkHandler,
+    fixtures: HashMap<u32, FixtureProgrammerState>,
+}
+
+impl ShowSnapshot {
+    pub fn capture(
+        fixture_handler: &FixtureHandler,
+        preset_handler: &PresetHandler,
+        updatable_handler: &UpdatableHandler,
+        timing_handler: &TimingHandler,
+    ) -> Self {
+        Self {
+            preset_handler: preset_handler.clone(),
+            updatable_handler: updatable_handler.without_runtime_state(),
+            timing_handler: timing_handler.clone(),
+            park_handler: fixture_handler.park_handler().clone(),
+            fixtures: fixture_handler
+                .fixtures()
+                .iter()
+                .map(|fixture| {
+                    (
+                        fixture.id(),
+                        (
+                            fixture.programmer_values().clone(),
+                            fixture
+                                .sources()
+                                .contains(&FixtureChannelValueSource::Programmer),
+                        ),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn capture_show(show_context: &ShowContext) -> Self {
+        Self::capture(
+            &show_context.fixture_handler.read(),
+            &show_context.preset_handler.read(),
+            &show_context.updatable_handler.read(),
+            &show_context.timing_handler.read(),
+        )
+    }
+
+    pub fn restore_show(self, show_context: &ShowContext) {
+        self.restore(
+            &mut show_context.fixture_handler.write(),
+            &mut show_context.preset_handler.write(),
+            &mut show_context.updatable_handler.write(),
+            &mut show_context.timing_handler.write(),
+        );
+    }
+
+    pub fn restore(
+        self,
+        fixture_handler: &mut FixtureHandler,
+        preset_handler: &mut PresetHandler,
+        updatable_handler: &mut UpdatableHandler,
+        timing_handler: &mut TimingHandler,
+    ) {
+        // removed executors are stopped using the sequences, they were started with
+        updatable_handler.restore(self.updatable_handler, fixture_handler, preset_handler);
+        *preset_handler = self.preset_handler;
+        *timing_handler = self.timing_handler;
+        *fixture_handler.park_handler_mut() = self.park_handler;
+
+        let mut fixtures = self.fixtures;
+        for fixture in fixture_handler.fixtures_mut() {
+            if let Some((programmer_values, programmer_active)) = fixtures.remove(&fixture.id()) {
+                fixture.restore_programmer_state(programmer_values, programmer_active);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShowHistoryEntry {
+    description: String,
+    created_at: time::Instant,
+    snapshot: ShowSnapshot,
+}
+
+impl ShowHistoryEntry {
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn created_at(&self) -> time::Instant {
+        self.created_at
+    }
+}
+
+/// Bounded undo/redo history of show snapshots.
+///
+/// Every entry holds the state *before* the described action was run.
+#[derive(Debug)]
+pub struct ShowHistory {
+    undo_entries: VecDeque<ShowHistoryEntry>,
+    redo_entries: Vec<ShowHistoryEntry>,
+    max_entries: usize,
+}
+
+impl Default for ShowHistory {
+    fn default() -> Self {
+        Self::new(SHOW_HISTORY_DEFAULT_MAX_ENTRIES)
+    }
+}
+
+impl ShowHistory {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            undo_entries: VecDeque::new(),
+            redo_entries: Vec::new(),
+            max_entries,
+        }
+    }
+
+    pub fn undo_entries(&self) -> impl DoubleEndedIterator<Item = &ShowHistoryEntry> {
+        self.undo_entries.iter()
+    }
+
+    pub fn redo_entries(&self) -> impl DoubleEndedIterator<Item = &ShowHistoryEntry> {
+        self.redo_entries.iter()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_entries.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_entries.clear();
+        self.redo_entries.clear();
+    }
+
+    /// Records the state before running the action described by `description`.
+    /// This invalidates everything that could've been redone.
+    pub fn record(&mut self, description: String, snapshot: ShowSnapshot) {
+        self.redo_entries.clear();
+        self.push_undo(ShowHistoryEntry {
+            description,
+            created_at: time::Instant::now(),
+            snapshot,
+        });
+    }
+
+    fn push_undo(&mut self, entry: ShowHistoryEntry) {
+        self.undo_entries.push_back(entry);
+
+        while self.undo_entries.len() > self.max_entries {
+            self.undo_entries.pop_front();
+        }
+    }
+
+    /// Returns the snapshot to restore, or `None` if there's nothing to undo.
+    /// `current` is the current state, which is kept, so the undo can be redone.
+    pub fn undo(&mut self, current: ShowSnapshot) -> Option<(String, ShowSnapshot)> {
+        let entry = self.undo_entries.pop_back()?;
+
+        self.redo_entries.push(ShowHistoryEntry {
+            description: entry.description.clone(),
+            created_at: entry.created_at,
+            snapshot: current,
+        });
+
+        Some((entry.description, entry.snapshot))
+    }
+
+    /// Returns the snapshot to restore, or `None` if there's nothing to redo.
+    /// `current` is the current state, which is kept, so the redo can be undone.
+    pub fn redo(&mut self, current: ShowSnapshot) -> Option<(String, ShowSnapshot)> {
+        let entry = self.redo_entries.pop()?;
+
+        self.push_undo(ShowHistoryEntry {
+            description: entry.description.clone(),
+            created_at: entry.created_at,
+            snapshot: current,
+        });
+
+        Some((entry.description, entry.snapshot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dmx::routing::DmxRouting,
+        fixture::{
+            handler::FixtureHandler, presets::PresetHandler, timing::TimingHandler,
+            updatables::UpdatableHandler,
+        },
+    };
+
+    use super::ShowSnapshot;
+
+    #[test]
+    fn test_restore_keeps_playback() {
+        let mut fixture_handler =
+            FixtureHandler::new(vec![], vec![], DmxRouting::default(), true).unwrap();
+        let mut preset_handler = PresetHandler::default();
+        let mut updatable_handler = UpdatableHandler::default();
+        let mut timing_handler = TimingHandler::default();
+
+        preset_handler.create_sequence(1, None).unwrap();
+        updatable_handler.create_executor(1, 1).unwrap();
+        updatable_handler.create_executor(2, 1).unwrap();
+        updatable_handler
+            .start_executor(2, &mut fixture_handler, &preset_handler, 0.0)
+            .unwrap();
+
+        let snapshot = ShowSnapshot::capture(
+            &fixture_handler,
+            &preset_handler,
+            &updatable_handler,
+            &timing_handler,
+        );
+
+        updatable_handler
+            .start_executor(1, &mut fixture_handler, &preset_handler, 0.0)
+            .unwrap();
+        updatable_handler
+            .stop_executor(2, &mut fixture_handler, &preset_handler)
+            .unwrap();
+        updatable_handler.create_executor(3, 1).unwrap();
+
+        snapshot.restore(
+            &mut fixture_handler,
+            &mut preset_handler,
+            &mut updatable_handler,
+            &mut timing_handler,
+        );
+
+        // the created executor is removed, but no executor is started or stopped
+        assert!(updatable_handler.executor(3).is_err());
+        assert!(updatable_handler.executor(1).unwrap().is_active());
+        assert!(!updatable_handler.executor(2).unwrap().is_active());
+    }
+}
@@ -5,13 +5,17 @@ use ui::DemexShowUiConfig;
 
 use crate::{
     fixture::{
-        patch::SerializablePatch, presets::PresetHandler, timing::TimingHandler,
-        updatables::UpdatableHandler,
+        handler::park::ParkHandler, patch::SerializablePatch, presets::PresetHandler,
+        timing::TimingHandler, updatables::UpdatableHandler,
     },
     input::device::DemexInputDeviceConfig,
 };
 
 pub mod context;
+pub mod dispatch;
+pub mod error;
+pub mod file;
+pub mod history;
 
 #[cfg(feature = "ui")]
 pub mod ui;
@@ -22,6 +26,9 @@ pub struct DemexNoUiShow {
     pub updatable_handler: UpdatableHandler,
     pub timing_handler: TimingHandler,
     pub patch: SerializablePatch,
+
+    #[serde(default)]
+    pub park_handler: ParkHandler,
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -32,6 +39,9 @@ pub struct DemexShow {
     pub input_device_configs: Vec<DemexInputDeviceConfig>,
     pub patch: SerializablePatch,
 
+    #[serde(default)]
+    pub park_handler: ParkHandler,
+
     #[serde(default)]
     #[cfg(feature = "ui")]
     pub ui_config: DemexShowUiConfig,
@@ -23,3 +23,7 @@ fn storage_dir(app_id: &str, path: &str) -> PathBuf {
 pub fn fixture_types(app_id: &str) -> PathBuf {
     storage_dir(app_id, "fixtures")
 }
+
+pub fn dmx_recordings(app_id: &str) -> PathBuf {
+    storage_dir(app_id, "recordings")
+}
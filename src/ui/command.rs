@@ -1,7 +1,171 @@
-use crate::{lexer::Lexer, ui::dlog::dialog::DemexGlobalDialogEntry};
+use itertools::Itertools;
+
+use crate::{
+    lexer::{
+        error::TokenizationError,
+        keyword_token,
+        token::{tokens_to_text, Token},
+        Lexer,
+    },
+    parser::{completion::CommandHint, Parser2},
+    ui::dlog::dialog::DemexGlobalDialogEntry,
+};
 
 use super::context::DemexUiContext;
 
+/// The word, that is currently being typed, if it could be the beginning of a keyword
+fn partial_keyword(command_input: &str) -> &str {
+    let word = command_input
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or_default();
+
+    if word.chars().all(char::is_alphabetic) {
+        word
+    } else {
+        ""
+    }
+}
+
+#[derive(Clone)]
+struct CommandLineState {
+    /// Index of the first token of the command, that can't be part of a valid command
+    invalid_token_idx: Option<usize>,
+    input_invalid: bool,
+
+    /// What can follow the command (and the completely typed words of the input).
+    /// If a word is partially typed, only the keywords it could be the beginning of.
+    hints: Vec<CommandHint>,
+}
+
+// Parsing the hints tries every candidate, so they are only recomputed, when the command line changed
+#[derive(Clone)]
+struct CachedCommandLineState {
+    command: String,
+    command_input: String,
+    state: CommandLineState,
+}
+
+impl CommandLineState {
+    fn cached(ctx: &egui::Context, command: &[Token], command_input: &str) -> Self {
+        let id = egui::Id::new("DemexCommandLineState");
+        // tokens can't be compared, but their debug representation can
+        let command_key = format!("{:?}", command);
+
+        if let Some(cached) = ctx
+            .data(|data| data.get_temp::<CachedCommandLineState>(id))
+            .filter(|cached| cached.command == command_key && cached.command_input == command_input)
+        {
+            return cached.state;
+        }
+
+        let state = Self::new(command, command_input);
+
+        ctx.data_mut(|data| {
+            data.insert_temp(
+                id,
+                CachedCommandLineState {
+                    command: command_key,
+                    command_input: command_input.to_owned(),
+                    state: state.clone(),
+                },
+            )
+        });
+
+        state
+    }
+
+    fn new(command: &[Token], command_input: &str) -> Self {
+        let partial = partial_keyword(command_input);
+
+        let input_tokens =
+            match Lexer::new(&command_input[..command_input.len() - partial.len()]).tokenize() {
+                Ok(input_tokens) => input_tokens,
+                // strings and floating point values, that are still being typed, aren't invalid
+                Err(err) => {
+                    return Self {
+                        invalid_token_idx: None,
+                        input_invalid: matches!(err, TokenizationError::UnknownCharacter(_, _)),
+                        hints: Vec::new(),
+                    }
+                }
+            };
+
+        let tokens = command
+            .iter()
+            .chain(input_tokens.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let invalid_token_idx = Parser2::invalid_token_idx(&tokens);
+
+        let hints = Parser2::hints(&tokens)
+            .into_iter()
+            .filter(|hint| match hint {
+                CommandHint::Keyword(keyword) => keyword.starts_with(partial),
+                _ => partial.is_empty(),
+            })
+            .collect::<Vec<_>>();
+
+        // aliases (e.g. "rec") aren't hinted, but are still valid
+        let partial_valid = partial.is_empty()
+            || !hints.is_empty()
+            || keyword_token(partial).is_some_and(|token| {
+                let mut tokens = tokens.clone();
+                tokens.push(token);
+                Parser2::invalid_token_idx(&tokens).is_none()
+            });
+
+        Self {
+            invalid_token_idx: invalid_token_idx.filter(|idx| *idx < command.len()),
+            input_invalid: invalid_token_idx.is_some_and(|idx| idx >= command.len())
+                || !partial_valid,
+            hints,
+        }
+    }
+
+    /// Completes the partially typed keyword as far as it is unambiguous.
+    /// Returns `true`, if the command input was changed.
+    fn complete(&self, command_input: &mut String) -> bool {
+        let partial_len = partial_keyword(command_input).len();
+
+        if partial_len == 0 {
+            return false;
+        }
+
+        let keywords = self
+            .hints
+            .iter()
+            .filter_map(|hint| match hint {
+                CommandHint::Keyword(keyword) => Some(*keyword),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let completion = match keywords.as_slice() {
+            [] => return false,
+            [keyword] => format!("{} ", keyword),
+            [first, rest @ ..] => rest.iter().fold(first.to_string(), |prefix, keyword| {
+                prefix
+                    .chars()
+                    .zip(keyword.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a)
+                    .collect()
+            }),
+        };
+
+        if completion.len() <= partial_len {
+            return false;
+        }
+
+        command_input.truncate(command_input.len() - partial_len);
+        command_input.push_str(&completion);
+
+        true
+    }
+}
+
 pub fn ui_command_input(ctx: &egui::Context, context: &mut DemexUiContext) {
     eframe::egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
         ui.add_space(10.0);
@@ -10,13 +174,24 @@ pub fn ui_command_input(ctx: &egui::Context, context: &mut DemexUiContext) {
 
         let command_font = eframe::egui::FontId::new(16.0, eframe::egui::FontFamily::Monospace);
 
+        let command_line_state =
+            CommandLineState::cached(ctx, &context.command, &context.command_input);
+
         ui.horizontal(|ui| {
             if !context.command.is_empty() {
                 ui.horizontal(|ui| {
-                    for token in &context.command {
+                    for (idx, token) in context.command.iter().enumerate() {
+                        let is_invalid = command_line_state
+                            .invalid_token_idx
+                            .is_some_and(|invalid_token_idx| idx >= invalid_token_idx);
+
                         ui.label(
                             eframe::egui::RichText::from(token.to_string())
-                                .background_color(ecolor::Color32::BLACK)
+                                .background_color(if is_invalid {
+                                    ecolor::Color32::DARK_RED
+                                } else {
+                                    ecolor::Color32::BLACK
+                                })
                                 .color(token)
                                 .font(command_font.clone()),
                         );
@@ -29,13 +204,33 @@ pub fn ui_command_input(ctx: &egui::Context, context: &mut DemexUiContext) {
                     ui.available_size(),
                     eframe::egui::TextEdit::singleline(&mut context.command_input)
                         .font(command_font)
-                        .text_color(ecolor::Color32::YELLOW),
+                        .text_color(if command_line_state.input_invalid {
+                            ecolor::Color32::LIGHT_RED
+                        } else {
+                            ecolor::Color32::YELLOW
+                        }),
                 )
                 .labelled_by(command_label.id);
 
             if context.window_handler.is_empty() {
                 if ui.input_mut(|writer| writer.consume_key(egui::Modifiers::NONE, egui::Key::Tab))
                 {
+                    // tab completes the current word, if the command input is already focused
+                    if context.should_focus_command_input
+                        && command_line_state.complete(&mut context.command_input)
+                    {
+                        if let Some(mut state) =
+                            egui::text_edit::TextEditState::load(ui.ctx(), command_input_field.id)
+                        {
+                            let cursor =
+                                egui::text::CCursor::new(context.command_input.chars().count());
+                            state
+                                .cursor
+                                .set_char_range(Some(egui::text::CCursorRange::one(cursor)));
+                            state.store(ui.ctx(), command_input_field.id);
+                        }
+                    }
+
                     context.should_focus_command_input = true;
                 }
 
@@ -100,7 +295,15 @@ pub fn ui_command_input(ctx: &egui::Context, context: &mut DemexUiContext) {
 
                         if let Err(err) = context.enqueue_cmd() {
                             log::warn!("Failed to parse cmd: {}", err);
-                            context.add_dialog_entry(DemexGlobalDialogEntry::error(&err));
+
+                            let mut dialog_entry = DemexGlobalDialogEntry::error(&err);
+                            if let Some(span) = err.span() {
+                                dialog_entry = dialog_entry.with_snippet(
+                                    span.underline(&tokens_to_text(&context.command).0),
+                                );
+                            }
+
+                            context.add_dialog_entry(dialog_entry);
                         }
 
                         context.command.clear();
@@ -110,5 +313,17 @@ pub fn ui_command_input(ctx: &egui::Context, context: &mut DemexUiContext) {
                 }
             }
         });
+
+        ui.horizontal_wrapped(|ui| {
+            if command_line_state.invalid_token_idx.is_some() || command_line_state.input_invalid {
+                ui.colored_label(ecolor::Color32::LIGHT_RED, "Invalid command");
+            } else if !command_line_state.hints.is_empty() {
+                ui.label(
+                    eframe::egui::RichText::from(command_line_state.hints.iter().join("  "))
+                        .weak()
+                        .monospace(),
+                );
+            }
+        });
     });
 }
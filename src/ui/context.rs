@@ -12,18 +12,18 @@ use crate::{
         timing::TimingHandler,
         updatables::UpdatableHandler,
     },
-    headless::id::DemexProtoDeviceId,
+    headless::{controller::DemexHeadlessConroller, id::DemexProtoDeviceId},
     input::{device::DemexInputDeviceConfig, DemexInputDeviceHandler},
     lexer::token::Token,
     parser::{
         error::ParseError,
-        nodes::{
-            action::{queue::ActionQueue, result::ActionRunResult, Action, DeferredAction},
-            fixture_selector::FixtureSelectorContext,
-        },
+        nodes::action::{queue::ActionQueue, result::ActionRunResult, Action, DeferredAction},
         Parser2,
     },
-    show::{context::ShowContext, ui::DemexShowUiConfig, DemexShow},
+    show::{
+        context::ShowContext, dispatch::ShowActionDispatcher, history::ShowHistory,
+        ui::DemexShowUiConfig, DemexShow,
+    },
     ui::{
         edit_request::{UiEditRequest, UiEditRequestTrait},
         error::DemexUiError,
@@ -38,6 +38,8 @@ use super::{
     window::{DemexWindow, DemexWindowHandler},
 };
 
+const SHOW_FILE_EXTENSIONS: [&str; 2] = ["json", "cbor"];
+
 pub type SaveShowFn =
     fn(DemexShow, Option<&PathBuf>) -> Result<PathBuf, Box<dyn std::error::Error>>;
 
@@ -66,6 +68,10 @@ pub struct DemexUiContext {
 
     pub action_queue: ActionQueue,
 
+    pub history: ShowHistory,
+
+    pub headless_controller: DemexHeadlessConroller,
+
     pub show_file: Option<PathBuf>,
 
     pub input_device_handler: DemexInputDeviceHandler,
@@ -129,6 +135,7 @@ impl DemexUiContext {
         show_file: Option<PathBuf>,
         stats: Arc<RwLock<DemexThreadStatsHandler>>,
         texture_handles: Vec<egui::TextureHandle>,
+        headless_controller: DemexHeadlessConroller,
     ) -> Self {
         let patch = show_context.patch.clone();
 
@@ -161,6 +168,8 @@ impl DemexUiContext {
             should_focus_command_input: false,
             command_input: String::new(),
             action_queue: ActionQueue::default(),
+            history: ShowHistory::default(),
+            headless_controller,
 
             encoders_tab_state: EncodersTabState::default(),
             window_handler: DemexWindowHandler::default(),
@@ -187,15 +196,13 @@ impl DemexUiContext {
 
     pub fn open_new_show(&mut self) {
         rfd::FileDialog::new()
-            .add_filter("demex Show-File", &["json"])
+            .add_filter("demex Show-File", &SHOW_FILE_EXTENSIONS)
             .pick_file()
             .ok_or(DemexUiError::RuntimeError(
                 "Failed show file dialog".to_string(),
             ))
             .and_then(|show_file| {
-                let show: DemexShow =
-                    serde_json::from_reader(std::fs::File::open(&show_file).unwrap())
-                        .map_err(DemexUiError::SerdeJsonError)?;
+                let show = DemexShow::load(&show_file).map_err(DemexUiError::ShowError)?;
 
                 let input_device_configs = show.input_device_configs;
 
@@ -204,17 +211,34 @@ impl DemexUiContext {
                     show.patch.into_patch(patch.fixture_types().to_vec())
                 };
 
+                let patch_issues = new_patch.validate();
+                if !patch_issues.is_empty() {
+                    return Err(DemexUiError::RuntimeError(format!(
+                        "The patch of the show is invalid:\n{}",
+                        patch_issues.iter().join("\n")
+                    )));
+                }
+
                 let (fixtures, outputs) =
                     new_patch.into_fixures_and_outputs(DemexProtoDeviceId::Controller);
 
-                *self.fixture_handler.write() = FixtureHandler::new(fixtures, outputs, true)
-                    .map_err(|err| DemexUiError::RuntimeError(err.to_string()))?;
+                let mut fixture_handler =
+                    FixtureHandler::new(fixtures, outputs, new_patch.routing().clone(), true)
+                        .map_err(|err| DemexUiError::RuntimeError(err.to_string()))?;
+                *fixture_handler.park_handler_mut() = show.park_handler;
+
+                *self.fixture_handler.write() = fixture_handler;
                 *self.preset_handler.write() = show.preset_handler;
                 *self.updatable_handler.write() = show.updatable_handler;
                 *self.timing_handler.write() = show.timing_handler;
 
                 *self.patch.write() = new_patch;
 
+                // snapshots of the old show must not be restored into the new one
+                self.history.clear();
+
+                self.headless_controller.broadcast_show_file_update();
+
                 self.input_device_handler = DemexInputDeviceHandler::new(
                     input_device_configs
                         .into_iter()
@@ -231,6 +255,7 @@ impl DemexUiContext {
 
     pub fn save_show(&mut self, ui_config: DemexShowUiConfig) {
         let save_result = {
+            let fixture_handler_lock = self.fixture_handler.read();
             let preset_handler_lock = self.preset_handler.read();
             let updatable_handler_lock = self.updatable_handler.read();
             let timing_handler_lock = self.timing_handler.read();
@@ -247,13 +272,14 @@ impl DemexUiContext {
                     .map(|d| d.config().clone())
                     .collect::<Vec<_>>(),
                 patch: SerializablePatch::from_patch(&patch_lock),
+                park_handler: fixture_handler_lock.park_handler().clone(),
                 ui_config,
             };
 
             let save_file = if let Some(show_file) = self.show_file.as_ref() {
                 Ok(show_file.clone())
             } else if let Some(save_file) = rfd::FileDialog::new()
-                .add_filter("demex Show-File", &["json"])
+                .add_filter("demex Show-File", &SHOW_FILE_EXTENSIONS)
                 .save_file()
             {
                 Ok(save_file)
@@ -264,8 +290,7 @@ impl DemexUiContext {
             };
 
             save_file.and_then(|save_file| {
-                serde_json::to_writer(std::fs::File::create(&save_file).unwrap(), &show)
-                    .map_err(DemexUiError::SerdeJsonError)?;
+                show.save(&save_file).map_err(DemexUiError::ShowError)?;
                 Ok(save_file)
             })
         };
@@ -280,6 +305,16 @@ impl DemexUiContext {
         }
     }
 
+    fn show_context(&self) -> ShowContext {
+        ShowContext {
+            fixture_handler: self.fixture_handler.clone(),
+            preset_handler: self.preset_handler.clone(),
+            updatable_handler: self.updatable_handler.clone(),
+            timing_handler: self.timing_handler.clone(),
+            patch: self.patch.clone(),
+        }
+    }
+
     pub fn run_and_handle_action(
         &mut self,
         action: DeferredAction,
@@ -292,14 +327,6 @@ impl DemexUiContext {
                 self.global_fixture_select = None;
                 self.window_handler.clear();
             }
-            Action::HomeAll => {
-                let mut fixture_handler_lock = self.fixture_handler.write();
-                let preset_handler_lock = self.preset_handler.read();
-                let mut updatable_handler_lock = self.updatable_handler.write();
-
-                updatable_handler_lock
-                    .executors_stop_all(&mut fixture_handler_lock, &preset_handler_lock);
-            }
             Action::Save => {
                 self.save_show(ui_config.clone());
             }
@@ -313,31 +340,28 @@ impl DemexUiContext {
 
         let now = std::time::Instant::now();
 
-        let result = action
-            .run(
-                &mut self.fixture_handler.write(),
-                &mut self.preset_handler.write(),
-                FixtureSelectorContext::new(&self.global_fixture_select),
-                &mut self.updatable_handler.write(),
-                &mut self.input_device_handler,
-                &mut self.timing_handler.write(),
-                &self.patch.read(),
-                issued_at,
-            )
-            .inspect(|result| {
-                self.logs
-                    .push(DemexLogEntry::new(DemexLogEntryType::ActionEntrySuccess(
-                        action.clone(),
-                        result.clone(),
-                    )))
-            })
-            .inspect_err(|err| {
-                self.logs
-                    .push(DemexLogEntry::new(DemexLogEntryType::ActionEntryFailed(
-                        action.clone(),
-                        err.to_string(),
-                    )))
-            })?;
+        let result = ShowActionDispatcher {
+            show_context: &self.show_context(),
+            fixture_selection: &self.global_fixture_select,
+            input_device_handler: &mut self.input_device_handler,
+            history: &mut self.history,
+            headless_controller: &self.headless_controller,
+        }
+        .dispatch(&action, issued_at)
+        .inspect(|result| {
+            self.logs
+                .push(DemexLogEntry::new(DemexLogEntryType::ActionEntrySuccess(
+                    action.clone(),
+                    result.clone(),
+                )))
+        })
+        .inspect_err(|err| {
+            self.logs
+                .push(DemexLogEntry::new(DemexLogEntryType::ActionEntryFailed(
+                    action.clone(),
+                    err.to_string(),
+                )))
+        })?;
 
         log::debug!(
             "Execution of action {:?} took {:.2?}",
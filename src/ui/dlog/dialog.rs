@@ -31,6 +31,9 @@ pub struct DemexGlobalDialogEntry {
     message: String,
     time: chrono::DateTime<chrono::Local>,
     link: Option<String>,
+
+    /// Monospaced text below the message, e.g. a command with the error location underlined
+    snippet: Option<String>,
 }
 
 impl DemexGlobalDialogEntry {
@@ -44,9 +47,15 @@ impl DemexGlobalDialogEntry {
             message,
             time: chrono::offset::Local::now(),
             link,
+            snippet: None,
         }
     }
 
+    pub fn with_snippet(mut self, snippet: String) -> Self {
+        self.snippet = Some(snippet);
+        self
+    }
+
     pub fn color(&self) -> ecolor::Color32 {
         self.entry_type.color()
     }
@@ -98,6 +107,15 @@ impl DemexGlobalDialogEntry {
                     .color(self.color()),
             );
 
+            if let Some(snippet) = &self.snippet {
+                ui.label(
+                    egui::RichText::from(snippet)
+                        .monospace()
+                        .size(16.0)
+                        .color(self.color()),
+                );
+            }
+
             if let Some(link) = self.link() {
                 if ui.link(link).clicked() {
                     let _ = open::that(link);
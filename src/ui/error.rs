@@ -1,7 +1,9 @@
+use crate::show::error::DemexShowError;
+
 #[derive(Debug)]
 pub enum DemexUiError {
     RuntimeError(String),
-    SerdeJsonError(serde_json::Error),
+    ShowError(DemexShowError),
 }
 
 impl std::error::Error for DemexUiError {
@@ -14,7 +16,7 @@ impl std::fmt::Display for DemexUiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::RuntimeError(error) => write!(f, "DemexUiError: Runtime Error: {}", error),
-            Self::SerdeJsonError(error) => write!(f, "serde_json Error: {}", error),
+            Self::ShowError(error) => write!(f, "Show Error: {}", error),
         }
     }
 }
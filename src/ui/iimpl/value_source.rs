@@ -5,6 +5,7 @@ impl FixtureChannelValueSource {
         match &self {
             Self::Programmer => ecolor::Color32::YELLOW,
             Self::Executor { .. } => ecolor::Color32::LIGHT_GREEN,
+            Self::Timecode { .. } => ecolor::Color32::LIGHT_BLUE,
         }
     }
 }
@@ -96,6 +96,12 @@ impl DemexUiApp {
         let timing_handler = self.context.timing_handler.read();
         let patch = self.context.patch.read();
 
+        updatable_handler.update_pixel_maps(
+            patch.fixture_types(),
+            patch.layout(),
+            &mut fixture_handler,
+        );
+
         let _ = fixture_handler
             .update_output_values(
                 patch.fixture_types(),
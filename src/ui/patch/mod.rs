@@ -6,5 +6,6 @@ pub struct PatchUiNewFixture {
     pub universe: u16,
     pub start_address: u16,
 
-    pub type_and_mode: u32,
+    pub fixture_type: String,
+    pub dmx_mode: String,
 }
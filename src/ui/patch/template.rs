@@ -20,11 +20,11 @@ pub fn render_new_fixture_patch_name(
             ),
             (
                 "type",
-                interpolator::Formattable::display(&new_fixture.type_and_mode),
+                interpolator::Formattable::display(&new_fixture.fixture_type),
             ),
             (
                 "mode",
-                interpolator::Formattable::display(&new_fixture.type_and_mode),
+                interpolator::Formattable::display(&new_fixture.dmx_mode),
             ),
         ]
         .into_iter()
@@ -45,11 +45,12 @@ mod tests {
                     id: 1,
                     universe: 42,
                     start_address: 11,
-                    type_and_mode: 0,
+                    fixture_type: "Spot".to_owned(),
+                    dmx_mode: "Standard".to_owned(),
                 },
-                "{type} {id} ({universe}.{address})"
+                "{type} {id} ({universe}.{address}) {mode}"
             ),
-            Some("0 1 (42.11)".to_owned())
+            Some("Spot 1 (42.11) Standard".to_owned())
         );
     }
 }
@@ -46,13 +46,12 @@ impl EncodersTabState {
 }
 
 pub fn ui(ui: &mut egui::Ui, context: &mut DemexUiContext) {
-    if let (Some(mut modal_state), Some(fixtures)) = (
+    if let (Some(mut modal_state), Some(fixture_select)) = (
         context.encoders_tab_state.modal_state.clone(),
-        context
-            .global_fixture_select
-            .as_ref()
-            .map(|fixture_selection| fixture_selection.fixtures()),
+        context.global_fixture_select.as_ref(),
     ) {
+        let fixtures = fixture_select.fixtures();
+
         egui::containers::Modal::new("EncodersTabModal".into()).show(ui.ctx(), |ui| {
             let mut fixture_handler = context.fixture_handler.write();
             let patch = context.patch.read();
@@ -136,6 +135,7 @@ pub fn ui(ui: &mut egui::Ui, context: &mut DemexUiContext) {
                                                 let _ = fixture
                                                     .update_programmer_attribute_matches_value(
                                                         patch.fixture_types(),
+                                                        fixture_select,
                                                         |fixture_attribute_name| {
                                                             fixture_attribute_name
                                                                 == modal_state.attribute
@@ -168,6 +168,7 @@ pub fn ui(ui: &mut egui::Ui, context: &mut DemexUiContext) {
 
                         let _ = fixture.update_programmer_attribute_matches_value(
                             patch.fixture_types(),
+                            fixture_select,
                             |fixture_attribute_name| {
                                 fixture_attribute_name == modal_state.attribute
                             },
@@ -262,6 +263,13 @@ pub fn ui(ui: &mut egui::Ui, context: &mut DemexUiContext) {
                                         },
                                     )
                                     .unwrap()
+                                    .into_iter()
+                                    .filter(move |(dmx_channel, _, _)| {
+                                        fixture.is_channel_selected(
+                                            dmx_channel.name().as_ref(),
+                                            fixture_select,
+                                        )
+                                    })
                             })
                             .map(|(dmx_channel, _, channel_functions)| {
                                 (dmx_channel.name().as_ref().to_owned(), channel_functions)
@@ -365,6 +373,13 @@ pub fn ui(ui: &mut egui::Ui, context: &mut DemexUiContext) {
                                     for (channel_name, _) in &channels {
                                         if should_home {
                                             for fixture in &mut fixtures {
+                                                if !fixture.is_channel_selected(
+                                                    channel_name,
+                                                    fixture_select,
+                                                ) {
+                                                    continue;
+                                                }
+
                                                 let _ = fixture.set_programmer_value(
                                                     patch.fixture_types(),
                                                     channel_name,
@@ -372,19 +387,32 @@ pub fn ui(ui: &mut egui::Ui, context: &mut DemexUiContext) {
                                                 );
                                             }
                                         } else {
-                                            let _ = fixtures[0].set_programmer_value(
-                                                patch.fixture_types(),
-                                                channel_name,
-                                                FixtureChannelValue3::Discrete {
-                                                    channel_function_idx: selected_channel_function,
-                                                    value: slider_val,
-                                                },
-                                            );
+                                            if fixtures[0]
+                                                .is_channel_selected(channel_name, fixture_select)
+                                            {
+                                                let _ = fixtures[0].set_programmer_value(
+                                                    patch.fixture_types(),
+                                                    channel_name,
+                                                    FixtureChannelValue3::Discrete {
+                                                        channel_function_idx:
+                                                            selected_channel_function,
+                                                        value: slider_val,
+                                                    },
+                                                );
+                                            }
+
                                             let master_fixture_type = fixtures[0].fixture_type_id();
                                             let master_fixture_type_mode =
                                                 fixtures[0].fixture_type_dmx_mode().to_string();
 
                                             for fixture in fixtures.iter_mut().skip(1) {
+                                                if !fixture.is_channel_selected(
+                                                    channel_name,
+                                                    fixture_select,
+                                                ) {
+                                                    continue;
+                                                }
+
                                                 let _ = if fixture.fixture_type_id()
                                                     == master_fixture_type
                                                     && fixture.fixture_type_dmx_mode()
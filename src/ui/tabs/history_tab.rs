@@ -0,0 +1,53 @@
+use crate::parser::nodes::action::Action;
+
+pub fn ui(ui: &mut eframe::egui::Ui, context: &mut super::DemexUiContext) {
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(context.history.can_undo(), egui::Button::new("Undo"))
+            .clicked()
+        {
+            context.action_queue.enqueue_now(Action::Undo);
+        }
+
+        if ui
+            .add_enabled(context.history.can_redo(), egui::Button::new("Redo"))
+            .clicked()
+        {
+            context.action_queue.enqueue_now(Action::Redo);
+        }
+    });
+
+    ui.separator();
+
+    egui::ScrollArea::vertical()
+        .auto_shrink(false)
+        .show(ui, |ui| {
+            // redo entries are stored in reverse, so the next one to be redone is at the end
+            for entry in context.history.redo_entries() {
+                ui.colored_label(
+                    ecolor::Color32::GRAY,
+                    format!(
+                        "[{:.0}s ago] {}",
+                        entry.created_at().elapsed().as_secs_f32(),
+                        entry.description()
+                    ),
+                );
+                ui.separator();
+            }
+
+            for (idx, entry) in context.history.undo_entries().rev().enumerate() {
+                let text = format!(
+                    "[{:.0}s ago] {}",
+                    entry.created_at().elapsed().as_secs_f32(),
+                    entry.description()
+                );
+
+                if idx == 0 {
+                    ui.colored_label(ecolor::Color32::LIGHT_GREEN, text);
+                } else {
+                    ui.label(text);
+                }
+                ui.separator();
+            }
+        });
+}
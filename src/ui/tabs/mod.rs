@@ -12,10 +12,13 @@ pub mod encoders_tab;
 pub mod faders_tab;
 pub mod fixture_list_tab;
 pub mod fixture_selection_tab;
+pub mod history_tab;
 pub mod layout_view_tab;
 pub mod logs_tab;
+pub mod parked_tab;
 pub mod patch_tab;
 pub mod performance_tab;
+pub mod pixel_maps_tab;
 pub mod preset_grid_tab;
 pub mod sequence_editor_tab;
 pub mod sequences_list_tab;
@@ -39,9 +42,12 @@ pub enum DemexTab {
     TimecodeClock,
     Patch,
     Timing,
+    Parked,
+    PixelMaps,
     FixtureSelection,
     ColorPicker,
     Logs,
+    History,
     Performance,
     Empty,
 }
@@ -59,9 +65,12 @@ impl std::fmt::Display for DemexTab {
             DemexTab::TimecodeClock => write!(f, "Timecode Clock"),
             DemexTab::Patch => write!(f, "Patch"),
             DemexTab::Timing => write!(f, "Timing"),
+            DemexTab::Parked => write!(f, "Parked"),
+            DemexTab::PixelMaps => write!(f, "Pixel Maps"),
             DemexTab::FixtureSelection => write!(f, "Fixture Selection"),
             DemexTab::ColorPicker => write!(f, "Color Picker"),
             DemexTab::Logs => write!(f, "Logs"),
+            DemexTab::History => write!(f, "History"),
             DemexTab::Performance => write!(f, "Performance"),
             DemexTab::Empty => write!(f, "demex"),
         }
@@ -88,9 +97,12 @@ impl DemexTab {
                 patch_view_component.show(ui);
             }
             DemexTab::Timing => timing_tab::ui(ui, context),
+            DemexTab::Parked => parked_tab::ui(ui, context),
+            DemexTab::PixelMaps => pixel_maps_tab::ui(ui, context),
             DemexTab::FixtureSelection => fixture_selection_tab::ui(ui, context),
             DemexTab::ColorPicker => color_picker_tab::ColorPickerComponent::new(context).show(ui),
             DemexTab::Logs => logs_tab::ui(ui, context),
+            DemexTab::History => history_tab::ui(ui, context),
             DemexTab::Performance => performance_tab::ui(ui, context),
             DemexTab::Empty => empty_tab::ui(ui, context),
         }
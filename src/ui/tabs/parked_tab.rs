@@ -0,0 +1,63 @@
+use crate::{
+    fixture::handler::park::ParkTarget,
+    parser::nodes::action::{functions::park_function::UnparkArgs, Action},
+};
+
+pub fn ui(ui: &mut eframe::egui::Ui, context: &mut super::DemexUiContext) {
+    let parked = context
+        .fixture_handler
+        .read()
+        .park_handler()
+        .parked()
+        .to_vec();
+
+    if ui
+        .add_enabled(!parked.is_empty(), egui::Button::new("Unpark all"))
+        .clicked()
+    {
+        context
+            .action_queue
+            .enqueue_now(Action::Unpark(UnparkArgs { object: None }));
+    }
+
+    ui.separator();
+
+    egui_extras::TableBuilder::new(ui)
+        .columns(egui_extras::Column::auto(), 2)
+        .column(egui_extras::Column::remainder())
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .striped(true)
+        .header(20.0, |mut header| {
+            for heading in ["Target", "Value", ""] {
+                header.col(|ui| {
+                    ui.heading(heading);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(30.0, parked.len(), |mut row| {
+                let parked_value = &parked[row.index()];
+
+                row.col(|ui| {
+                    ui.label(parked_value.target.to_string());
+                });
+
+                row.col(|ui| {
+                    ui.label(match parked_value.target {
+                        ParkTarget::FixtureAttribute { .. } => {
+                            format!("{:.1}%", parked_value.value * 100.0)
+                        }
+                        ParkTarget::Address { .. } => parked_value.dmx_value().to_string(),
+                    });
+                });
+
+                row.col(|ui| {
+                    if ui.button("Unpark").clicked() {
+                        context.action_queue.enqueue_now(Action::Unpark(UnparkArgs {
+                            object: Some(parked_value.target.clone().into()),
+                        }));
+                    }
+                });
+            });
+        });
+}
@@ -0,0 +1,176 @@
+use std::{collections::HashMap, sync::Arc};
+
+use itertools::Itertools;
+use parking_lot::RwLock;
+
+use crate::{
+    dmx::artnet::node::{ArtnetNode, ArtnetNodeAddress, ArtnetNodeId, ArtnetNodes},
+    ui::context::DemexUiContext,
+    utils::clock,
+};
+
+#[derive(Clone)]
+struct NodeEdit {
+    pub short_name: String,
+    pub long_name: String,
+    pub port_address: u16,
+}
+
+impl NodeEdit {
+    fn from_node(node: &ArtnetNode) -> Self {
+        Self {
+            short_name: node.short_name().to_owned(),
+            long_name: node.long_name().to_owned(),
+            port_address: node.port_addresses().first().copied().unwrap_or_default(),
+        }
+    }
+
+    // Only the values, that differ from the node, are programmed
+    fn address(&self, node: &ArtnetNode) -> ArtnetNodeAddress {
+        let original = Self::from_node(node);
+
+        ArtnetNodeAddress {
+            short_name: (self.short_name != original.short_name).then(|| self.short_name.clone()),
+            long_name: (self.long_name != original.long_name).then(|| self.long_name.clone()),
+            port_address: (self.port_address != original.port_address).then_some(self.port_address),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct State {
+    // edits, that were not yet sent to the nodes
+    pub edits: HashMap<ArtnetNodeId, NodeEdit>,
+}
+
+pub struct PatchArtnetNodesComponent<'a> {
+    context: &'a mut DemexUiContext,
+    id_source: egui::Id,
+}
+
+impl<'a> PatchArtnetNodesComponent<'a> {
+    pub fn new(context: &'a mut DemexUiContext) -> Self {
+        Self {
+            context,
+            id_source: egui::Id::new("DemexPatchArtnetNodesComponent"),
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        let mut state = ui
+            .data(|data| data.get_temp::<State>(self.id_source))
+            .unwrap_or_default();
+
+        let node_outputs: Vec<Arc<RwLock<ArtnetNodes>>> = self
+            .context
+            .fixture_handler
+            .read()
+            .outputs()
+            .iter()
+            .filter_map(|output| output.artnet_nodes().cloned())
+            .collect();
+
+        if node_outputs.is_empty() {
+            ui.label("No Art-Net output with node discovery configured (broadcast outputs don't poll for nodes)");
+            return;
+        }
+
+        let nodes = node_outputs
+            .iter()
+            .flat_map(|artnet_nodes| {
+                artnet_nodes
+                    .read()
+                    .nodes()
+                    .map(|node| (artnet_nodes.clone(), node.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            egui_extras::TableBuilder::new(ui)
+                .columns(egui_extras::Column::auto(), 6)
+                .column(egui_extras::Column::remainder())
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .striped(true)
+                .header(20.0, |mut header| {
+                    for heading in [
+                        "Address",
+                        "Short Name",
+                        "Long Name",
+                        "Port Addresses",
+                        "Last Seen",
+                        "Port Address",
+                        "",
+                    ] {
+                        header.col(|ui| {
+                            ui.heading(heading);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(30.0, nodes.len(), |mut row| {
+                        let (artnet_nodes, node) = &nodes[row.index()];
+
+                        let mut edit = state
+                            .edits
+                            .get(&node.id())
+                            .cloned()
+                            .unwrap_or_else(|| NodeEdit::from_node(node));
+
+                        row.col(|ui| {
+                            ui.label(format!(
+                                "{} (Bind {})",
+                                node.id().addr.ip(),
+                                node.id().bind_index
+                            ));
+                        });
+
+                        row.col(|ui| {
+                            ui.text_edit_singleline(&mut edit.short_name);
+                        });
+
+                        row.col(|ui| {
+                            ui.text_edit_singleline(&mut edit.long_name);
+                        });
+
+                        row.col(|ui| {
+                            ui.label(node.port_addresses().iter().join(", "));
+                        });
+
+                        row.col(|ui| {
+                            ui.label(format!(
+                                "{:.1}s ago",
+                                clock::elapsed(node.last_seen()).as_secs_f32()
+                            ));
+                        });
+
+                        row.col(|ui| {
+                            ui.add(egui::DragValue::new(&mut edit.port_address).range(0..=0x7FFF));
+                        });
+
+                        let address = edit.address(node);
+                        let is_edited = address != ArtnetNodeAddress::default();
+
+                        let mut is_applied = false;
+                        row.col(|ui| {
+                            is_applied = ui
+                                .add_enabled(is_edited, egui::Button::new("Apply"))
+                                .clicked();
+                        });
+
+                        if is_applied {
+                            artnet_nodes.write().set_address(node.id(), address);
+                        }
+
+                        if is_edited && !is_applied {
+                            state.edits.insert(node.id(), edit);
+                        } else {
+                            state.edits.remove(&node.id());
+                        }
+                    });
+                });
+        });
+
+        ui.data_mut(|data| data.insert_temp(self.id_source, state));
+    }
+}
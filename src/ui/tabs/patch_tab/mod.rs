@@ -1,13 +1,17 @@
 use crate::ui::{components::tab_viewer::TabViewer, context::DemexUiContext};
 
+pub mod artnet_nodes;
 // pub mod fixture_types;
-// pub mod new_fixtures;
+pub mod new_fixtures;
+pub mod rdm_devices;
 
 #[derive(PartialEq, Eq, Copy, Clone)]
 pub enum PatchViewTab {
     PatchedFixtures,
     FixtureTypes,
     PatchNewFixtures,
+    RdmDevices,
+    ArtnetNodes,
 }
 
 impl std::fmt::Display for PatchViewTab {
@@ -16,16 +20,20 @@ impl std::fmt::Display for PatchViewTab {
             Self::PatchNewFixtures => write!(f, "Patch New Fixtures"),
             Self::PatchedFixtures => write!(f, "Patched Fixtures"),
             Self::FixtureTypes => write!(f, "Fixture Types"),
+            Self::RdmDevices => write!(f, "RDM Devices"),
+            Self::ArtnetNodes => write!(f, "Art-Net Nodes"),
         }
     }
 }
 
 impl PatchViewTab {
-    pub fn ui(&self, ui: &mut egui::Ui, _context: &mut DemexUiContext) {
+    pub fn ui(&self, ui: &mut egui::Ui, context: &mut DemexUiContext) {
         match self {
             Self::PatchNewFixtures => {
-                // new_fixtures::PatchNewFixturesComponent::new(context).show(ui)
+                new_fixtures::PatchNewFixturesComponent::new(context).show(ui)
             }
+            Self::RdmDevices => rdm_devices::PatchRdmDevicesComponent::new(context).show(ui),
+            Self::ArtnetNodes => artnet_nodes::PatchArtnetNodesComponent::new(context).show(ui),
             Self::FixtureTypes => {
                 // fixture_types::ui(ui, context)
             }
@@ -59,6 +67,8 @@ impl<'a> PatchViewComponent<'a> {
                     PatchViewTab::PatchedFixtures,
                     PatchViewTab::FixtureTypes,
                     PatchViewTab::PatchNewFixtures,
+                    PatchViewTab::RdmDevices,
+                    PatchViewTab::ArtnetNodes,
                 ],
                 0,
             )
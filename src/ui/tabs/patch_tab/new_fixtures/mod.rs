@@ -1,5 +1,8 @@
 use crate::{
-    fixture::patch::FixtureTypeAndMode,
+    fixture::{
+        gdtf::{modifier::FixtureOutputModifiers, GdtfFixturePatch},
+        patch::Patch,
+    },
     ui::{
         context::DemexUiContext,
         dlog::dialog::DemexGlobalDialogEntry,
@@ -7,21 +10,41 @@ use crate::{
     },
 };
 
-#[derive(Default, Clone)]
+#[derive(Clone)]
 struct State {
-    pub fixture_type: Option<String>,
-    pub fixture_mode: Option<u32>,
+    pub fixture_type: Option<uuid::Uuid>,
+    pub dmx_mode: Option<String>,
 
     pub universe: u16,
     pub start_address: u16,
     pub address_padding: u16,
 
+    // If enabled, every fixture is patched at the next free address,
+    // starting from the start address
+    pub auto_address: bool,
+
     pub start_id: u32,
     pub num_fixutres: u16,
 
     pub name_format: String,
 }
 
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            fixture_type: None,
+            dmx_mode: None,
+            universe: 1,
+            start_address: 1,
+            address_padding: 0,
+            auto_address: true,
+            start_id: 1,
+            num_fixutres: 1,
+            name_format: "{type} {id}".to_owned(),
+        }
+    }
+}
+
 pub struct PatchNewFixturesComponent<'a> {
     context: &'a mut DemexUiContext,
     id_source: egui::Id,
@@ -40,8 +63,7 @@ impl<'a> PatchNewFixturesComponent<'a> {
             .data(|data| data.get_temp::<State>(self.id_source))
             .unwrap_or_default();
 
-        let mut fixture_handler = self.context.fixture_handler.write();
-        let patch = fixture_handler.patch_mut();
+        let patch = self.context.patch.read();
 
         egui_extras::TableBuilder::new(ui)
             .column(egui_extras::Column::auto())
@@ -58,49 +80,46 @@ impl<'a> PatchNewFixturesComponent<'a> {
                     ui.col(|ui| {
                         let fixture_types = patch.fixture_types();
 
+                        let fixture_type = state.fixture_type.and_then(|fixture_type_id| {
+                            fixture_types
+                                .iter()
+                                .find(|ft| ft.fixture_type_id == fixture_type_id)
+                        });
+
                         egui::ComboBox::new(self.id_source.with("FixtureTypeSelection"), "")
                             .selected_text(
-                                state
-                                    .fixture_type
-                                    .as_ref()
-                                    .map(|fixture_type| {
-                                        fixture_types.get(fixture_type).unwrap().name.clone()
-                                    })
+                                fixture_type
+                                    .map(|fixture_type| fixture_type.long_name.to_string())
                                     .unwrap_or("None".to_owned()),
                             )
                             .show_ui(ui, |ui| {
                                 ui.selectable_value(&mut state.fixture_type, None, "None");
 
-                                for (fixture_type_id, fixture_type) in fixture_types {
+                                for fixture_type in fixture_types {
                                     ui.selectable_value(
                                         &mut state.fixture_type,
-                                        Some(fixture_type_id.clone()),
-                                        &fixture_type.name,
+                                        Some(fixture_type.fixture_type_id),
+                                        fixture_type.long_name.to_string(),
                                     );
                                 }
                             });
 
-                        if let Some(fixture_type) = &state.fixture_type {
-                            let fixture_modes = &fixture_types.get(fixture_type).unwrap().modes;
-
-                            let fixture_mode =
-                                state.fixture_mode.and_then(|mode| fixture_modes.get(&mode));
-
+                        if let Some(fixture_type) = fixture_type {
                             egui::ComboBox::new(self.id_source.with("FixtureModeSelection"), "")
-                                .selected_text(
-                                    fixture_mode.map_or("None".to_owned(), |m| m.name.clone()),
-                                )
+                                .selected_text(state.dmx_mode.as_deref().unwrap_or("None"))
                                 .show_ui(ui, |ui| {
-                                    ui.selectable_value(&mut state.fixture_mode, None, "None");
+                                    ui.selectable_value(&mut state.dmx_mode, None, "None");
+
+                                    for dmx_mode in &fixture_type.dmx_modes {
+                                        let dmx_mode_name: &str = dmx_mode.name.as_ref();
 
-                                    for (mode_id, mode) in fixture_modes {
                                         ui.selectable_value(
-                                            &mut state.fixture_mode,
-                                            Some(*mode_id),
+                                            &mut state.dmx_mode,
+                                            Some(dmx_mode_name.to_owned()),
                                             format!(
                                                 "{} ({} Channels)",
-                                                mode.name,
-                                                mode.channel_types.len()
+                                                dmx_mode_name,
+                                                dmx_mode.dmx_channels.len()
                                             ),
                                         );
                                     }
@@ -133,6 +152,16 @@ impl<'a> PatchNewFixturesComponent<'a> {
                     });
                 });
 
+                ui.row(70.0, |mut ui| {
+                    ui.col(|ui| {
+                        ui.label("Auto Address");
+                    });
+
+                    ui.col(|ui| {
+                        ui.checkbox(&mut state.auto_address, "Use next free address");
+                    });
+                });
+
                 ui.row(70.0, |mut ui| {
                     ui.col(|ui| {
                         ui.label("Adress Padding");
@@ -182,89 +211,143 @@ impl<'a> PatchNewFixturesComponent<'a> {
                 });
             });
 
-        if ui.button("Patch").clicked() {
-            if state.fixture_type.is_none()
-                || state.fixture_mode.is_none()
-                || state.name_format.is_empty()
-                || state.num_fixutres == 0
-            {
-                drop(fixture_handler);
-
-                self.context
-                    .add_dialog_entry(DemexGlobalDialogEntry::error_str(
-                        "Invalid patch configuration!".to_owned(),
-                    ));
-            } else {
-                todo!();
-                /*
-                let fixture_type_id = state.fixture_type.clone().unwrap();
-                let fixture_type = patch.fixture_types().get(&fixture_type_id).unwrap();
-
-                let fixture_mode_id = state.fixture_mode.unwrap();
-                let fixture_mode = fixture_type.modes.get(&fixture_mode_id).unwrap();
-
-                let dmx_footprint = fixture_mode.channel_types.len() as u16;
+        drop(patch);
 
-                let address_range = state.start_address
-                    ..(state.start_address
-                        + (state.num_fixutres * fixture_mode.channel_types.len() as u16));
-
-                if !patch.is_address_range_unpatched(address_range.clone(), state.universe) {
-                    drop(fixture_handler);
+        if ui.button("Patch").clicked() {
+            match self.patch_fixtures(&state) {
+                Ok(num_fixtures) => {
                     self.context
-                        .add_dialog_entry(DemexGlobalDialogEntry::error_str(format!(
-                            "Patch range ({:?}) in universe {} is not unused.",
-                            address_range, state.universe
-                        )));
-                } else {
-                    for idx in 0..state.num_fixutres {
-                        let fixture_id = state.start_id + idx as u32;
-                        let fixture_address = state.start_address
-                            + (idx * dmx_footprint)
-                            + idx * state.address_padding;
-
-                        let fixture_name = render_new_fixture_patch_name(
-                            PatchUiNewFixture {
-                                id: fixture_id,
-                                universe: state.universe,
-                                start_address: state.start_address,
-                                type_and_mode: FixtureTypeAndMode {
-                                    name: fixture_type_id.clone(),
-                                    mode: fixture_mode_id,
-                                },
-                            },
-                            &state.name_format,
-                        )
-                        .unwrap();
+                        .headless_controller
+                        .broadcast_show_file_update();
 
+                    self.context.add_dialog_entry(DemexGlobalDialogEntry::info(
+                        format!("Patched {} fixtures", num_fixtures).as_str(),
+                    ));
+                }
+                Err(err) => self
+                    .context
+                    .add_dialog_entry(DemexGlobalDialogEntry::error_str(err)),
+            }
+        }
 
-                        patch.fixtures_mut().push(SerializableFixturePatch {
-                            id: fixture_id,
-                            name: fixture_name,
-                            fixture_type: fixture_type_id.clone(),
-                            fixture_mode: fixture_mode_id,
+        ui.data_mut(|data| data.insert_temp(self.id_source, state));
+    }
 
-                            channel_modifiers: Vec::new(),
+    fn patch_fixtures(&mut self, state: &State) -> Result<usize, String> {
+        let (Some(fixture_type_id), Some(dmx_mode)) = (state.fixture_type, &state.dmx_mode) else {
+            return Err("Invalid patch configuration!".to_owned());
+        };
 
-                            universe: state.universe,
-                            start_address: fixture_address,
-                        });
-                    }
+        if state.name_format.is_empty() || state.num_fixutres == 0 {
+            return Err("Invalid patch configuration!".to_owned());
+        }
 
-                    fixture_handler.reload_patch();
+        let mut fixture_handler = self.context.fixture_handler.write();
+        let mut patch = self.context.patch.write();
 
-                    drop(fixture_handler);
-                    self.context.add_dialog_entry(DemexGlobalDialogEntry::info(
-                        format!("Patched {} fixtures", state.num_fixutres).as_str(),
-                    ));
+        let fixture_type_name = patch
+            .fixture_types()
+            .iter()
+            .find(|ft| ft.fixture_type_id == fixture_type_id)
+            .map(|ft| ft.long_name.to_string())
+            .unwrap_or_default();
 
+        let num_patched_fixtures = patch.fixtures().len();
+
+        // the new fixtures are added to the patch one by one, so every new
+        // fixture is taken into account when addressing the next one. afterwards
+        // they are removed again and added (and validated) as a whole.
+        let new_fixtures = Self::add_new_fixtures(
+            state,
+            &mut patch,
+            fixture_type_id,
+            dmx_mode,
+            &fixture_type_name,
+        );
+        patch.fixtures_mut().truncate(num_patched_fixtures);
+
+        let new_fixtures = patch.add_fixtures(new_fixtures?)?;
+        let num_new_fixtures = new_fixtures.len();
+        fixture_handler.fixtures_mut().extend(new_fixtures);
+
+        Ok(num_new_fixtures)
+    }
 
-                }
+    fn add_new_fixtures(
+        state: &State,
+        patch: &mut Patch,
+        fixture_type_id: uuid::Uuid,
+        dmx_mode: &str,
+        fixture_type_name: &str,
+    ) -> Result<Vec<GdtfFixturePatch>, String> {
+        let mut new_fixtures = Vec::new();
+        let mut address = state.start_address;
+
+        for idx in 0..state.num_fixutres {
+            let fixture_id = state.start_id + idx as u32;
+
+            if patch
+                .fixtures()
+                .iter()
+                .any(|fixture| fixture.id == fixture_id)
+            {
+                return Err(format!("Fixture {} already exists", fixture_id));
+            }
 
-                */
+            let mut fixture = GdtfFixturePatch {
+                id: fixture_id,
+                name: String::new(),
+                fixture_type_id,
+                fixture_type_dmx_mode: dmx_mode.to_owned(),
+                universe: state.universe,
+                start_address: address,
+                modifiers: FixtureOutputModifiers::default(),
+            };
+
+            let footprint = fixture
+                .address_footprint(patch.fixture_types())
+                .map_err(|err| err.to_string())?;
+
+            if state.auto_address {
+                fixture.start_address = patch
+                    .next_free_address(state.universe, footprint, address)
+                    .ok_or_else(|| {
+                        format!(
+                            "No free address for fixture {} in universe {}",
+                            fixture_id, state.universe
+                        )
+                    })?;
+            } else if !patch.is_address_range_unpatched(
+                fixture.start_address..fixture.start_address.saturating_add(footprint),
+                state.universe,
+            ) {
+                return Err(format!(
+                    "Address {}.{} of fixture {} is already patched",
+                    state.universe, fixture.start_address, fixture_id
+                ));
             }
+
+            fixture.name = render_new_fixture_patch_name(
+                PatchUiNewFixture {
+                    id: fixture_id,
+                    universe: fixture.universe,
+                    start_address: fixture.start_address,
+                    fixture_type: fixture_type_name.to_owned(),
+                    dmx_mode: dmx_mode.to_owned(),
+                },
+                &state.name_format,
+            )
+            .ok_or_else(|| format!("Invalid name format: {}", state.name_format))?;
+
+            address = fixture
+                .start_address
+                .saturating_add(footprint)
+                .saturating_add(state.address_padding);
+
+            patch.fixtures_mut().push(fixture.clone());
+            new_fixtures.push(fixture);
         }
 
-        ui.data_mut(|data| data.insert_temp(self.id_source, state));
+        Ok(new_fixtures)
     }
 }
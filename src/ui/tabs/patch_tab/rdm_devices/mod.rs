@@ -0,0 +1,264 @@
+use std::{collections::HashMap, sync::Arc};
+
+use parking_lot::RwLock;
+
+use crate::{
+    dmx::artnet::rdm::{packet::RdmUid, ArtnetRdm, ArtnetRdmDevice},
+    fixture::{gdtf::GdtfFixturePatch, patch::Patch},
+    ui::{context::DemexUiContext, dlog::dialog::DemexGlobalDialogEntry},
+};
+
+#[derive(Clone, Default)]
+struct State {
+    // start addresses, that were entered but not yet sent to the devices
+    pub start_addresses: HashMap<RdmUid, u16>,
+}
+
+pub struct PatchRdmDevicesComponent<'a> {
+    context: &'a mut DemexUiContext,
+    id_source: egui::Id,
+}
+
+impl<'a> PatchRdmDevicesComponent<'a> {
+    pub fn new(context: &'a mut DemexUiContext) -> Self {
+        Self {
+            context,
+            id_source: egui::Id::new("DemexPatchRdmDevicesComponent"),
+        }
+    }
+
+    fn rdm_outputs(&self) -> Vec<Arc<RwLock<ArtnetRdm>>> {
+        self.context
+            .fixture_handler
+            .read()
+            .outputs()
+            .iter()
+            .filter_map(|output| output.artnet_rdm().cloned())
+            .collect()
+    }
+
+    // Proposes a patch for every device, that isn't patched yet. New fixtures
+    // get the next free fixture ids.
+    fn propose_patches(
+        patch: &Patch,
+        devices: &[(Arc<RwLock<ArtnetRdm>>, ArtnetRdmDevice)],
+    ) -> Vec<Option<GdtfFixturePatch>> {
+        let mut next_fixture_id = patch
+            .fixtures()
+            .iter()
+            .map(|fixture| fixture.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        devices
+            .iter()
+            .map(|(_, device)| {
+                let is_patched = patch.fixtures().iter().any(|fixture| {
+                    Some(fixture.start_address) == device.start_address()
+                        && fixture.universe == device.universe()
+                });
+
+                if is_patched {
+                    return None;
+                }
+
+                let proposed_patch = device.propose_patch(patch.fixture_types(), next_fixture_id);
+                if proposed_patch.is_some() {
+                    next_fixture_id += 1;
+                }
+
+                proposed_patch
+            })
+            .collect()
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        let mut state = ui
+            .data(|data| data.get_temp::<State>(self.id_source))
+            .unwrap_or_default();
+
+        let rdm_outputs = self.rdm_outputs();
+
+        if rdm_outputs.is_empty() {
+            ui.label("No Art-Net output with RDM support configured (broadcast outputs don't support RDM)");
+            return;
+        }
+
+        if ui.button("Discover").clicked() {
+            for rdm in &rdm_outputs {
+                rdm.write().discover();
+            }
+        }
+
+        let devices = rdm_outputs
+            .iter()
+            .flat_map(|rdm| {
+                rdm.read()
+                    .devices()
+                    .map(|device| (rdm.clone(), device.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let proposed_patches = Self::propose_patches(&self.context.patch.read(), &devices);
+
+        ui.separator();
+
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            egui_extras::TableBuilder::new(ui)
+                .columns(egui_extras::Column::auto(), 7)
+                .column(egui_extras::Column::remainder())
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .striped(true)
+                .header(20.0, |mut header| {
+                    for heading in [
+                        "UID",
+                        "Universe",
+                        "Manufacturer",
+                        "Model",
+                        "Footprint",
+                        "Personality",
+                        "Start Address",
+                        "Proposed Patch",
+                    ] {
+                        header.col(|ui| {
+                            ui.heading(heading);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(30.0, devices.len(), |mut row| {
+                        let row_idx = row.index();
+                        let (rdm, device) = &devices[row_idx];
+
+                        row.col(|ui| {
+                            ui.label(device.uid().to_string());
+                        });
+
+                        row.col(|ui| {
+                            ui.label(device.universe().to_string());
+                        });
+
+                        row.col(|ui| {
+                            ui.label(device.manufacturer_label().unwrap_or("-"));
+                        });
+
+                        row.col(|ui| {
+                            ui.label(device.model_description().unwrap_or("-"));
+
+                            if let Some(device_info) = device.device_info() {
+                                ui.label(format!("(0x{:04X})", device_info.device_model_id));
+                            }
+                        });
+
+                        row.col(|ui| {
+                            ui.label(
+                                device
+                                    .device_info()
+                                    .map(|device_info| device_info.footprint.to_string())
+                                    .unwrap_or_else(|| "-".to_owned()),
+                            );
+                        });
+
+                        row.col(|ui| {
+                            ui.label(
+                                device
+                                    .personality()
+                                    .map(|(current_personality, personality_count)| {
+                                        format!("{}/{}", current_personality, personality_count)
+                                    })
+                                    .unwrap_or_else(|| "-".to_owned()),
+                            );
+                        });
+
+                        row.col(|ui| {
+                            let Some(device_start_address) = device.start_address() else {
+                                ui.label("-");
+                                return;
+                            };
+
+                            let mut start_address = state
+                                .start_addresses
+                                .get(&device.uid())
+                                .copied()
+                                .unwrap_or(device_start_address);
+
+                            if ui
+                                .add(egui::DragValue::new(&mut start_address).range(1..=512))
+                                .changed()
+                            {
+                                state.start_addresses.insert(device.uid(), start_address);
+                            }
+
+                            if ui
+                                .add_enabled(
+                                    start_address != device_start_address,
+                                    egui::Button::new("Set"),
+                                )
+                                .clicked()
+                            {
+                                rdm.write().set_start_address(device.uid(), start_address);
+                                state.start_addresses.remove(&device.uid());
+                            }
+                        });
+
+                        row.col(|ui| match &proposed_patches[row_idx] {
+                            Some(proposed_patch) => {
+                                ui.label(format!(
+                                    "{} ({}) at {}.{}",
+                                    proposed_patch.name,
+                                    proposed_patch.fixture_type_dmx_mode,
+                                    proposed_patch.universe,
+                                    proposed_patch.start_address
+                                ));
+                            }
+                            None => {
+                                ui.label("-");
+                            }
+                        });
+                    });
+                });
+        });
+
+        ui.separator();
+
+        let proposed_patches = proposed_patches.into_iter().flatten().collect::<Vec<_>>();
+
+        if ui
+            .add_enabled(
+                !proposed_patches.is_empty(),
+                egui::Button::new(format!("Patch proposed ({})", proposed_patches.len())),
+            )
+            .clicked()
+        {
+            match self.patch_fixtures(proposed_patches) {
+                Ok(num_fixtures) => {
+                    self.context
+                        .headless_controller
+                        .broadcast_show_file_update();
+
+                    self.context.add_dialog_entry(DemexGlobalDialogEntry::info(
+                        format!("Patched {} fixtures", num_fixtures).as_str(),
+                    ));
+                }
+                Err(err) => self
+                    .context
+                    .add_dialog_entry(DemexGlobalDialogEntry::error_str(err)),
+            }
+        }
+
+        ui.data_mut(|data| data.insert_temp(self.id_source, state));
+    }
+
+    fn patch_fixtures(&mut self, new_fixtures: Vec<GdtfFixturePatch>) -> Result<usize, String> {
+        let mut fixture_handler = self.context.fixture_handler.write();
+        let mut patch = self.context.patch.write();
+
+        let new_fixtures = patch.add_fixtures(new_fixtures)?;
+        let num_new_fixtures = new_fixtures.len();
+        fixture_handler.fixtures_mut().extend(new_fixtures);
+
+        Ok(num_new_fixtures)
+    }
+}
@@ -37,6 +37,25 @@ pub fn ui(ui: &mut eframe::egui::Ui, context: &mut super::DemexUiContext) {
                 ui.label(format!("(Threads: {})", output.config().num_threads()));
             });
 
+            if let Some(enttec_pro) = output.enttec_pro() {
+                let enttec_pro = enttec_pro.read();
+
+                if let Some(serial_number) = enttec_pro.serial_number() {
+                    ui.label(format!("DMX USB Pro serial number: {:08X}", serial_number));
+                }
+
+                if let Some(parameters) = enttec_pro.parameters() {
+                    ui.label(format!(
+                        "DMX USB Pro firmware: {}.{}, break: {:.2}us, mab: {:.2}us, refresh rate: {}/s",
+                        parameters.firmware_version >> 8,
+                        parameters.firmware_version & 0xFF,
+                        parameters.break_time as f32 * 10.67,
+                        parameters.mark_after_break_time as f32 * 10.67,
+                        parameters.refresh_rate
+                    ));
+                }
+            }
+
             num_threads += output.config().num_threads();
         }
     });
@@ -0,0 +1,95 @@
+use itertools::Itertools;
+
+use crate::ui::window::{edit::DemexEditWindow, DemexWindow};
+
+pub fn ui(ui: &mut eframe::egui::Ui, context: &mut super::DemexUiContext) {
+    let mut fixture_handler = context.fixture_handler.write();
+    let mut updatable_handler = context.updatable_handler.write();
+
+    if ui
+        .add_enabled(
+            context.global_fixture_select.is_some(),
+            egui::Button::new("New pixel map from selection"),
+        )
+        .clicked()
+    {
+        if let Some(selection) = context.global_fixture_select.clone() {
+            let _ = updatable_handler
+                .create_pixel_map(updatable_handler.next_pixel_map_id(), selection);
+        }
+    }
+
+    ui.separator();
+
+    let pixel_map_ids = updatable_handler
+        .pixel_maps()
+        .keys()
+        .copied()
+        .sorted()
+        .collect::<Vec<_>>();
+
+    egui_extras::TableBuilder::new(ui)
+        .columns(egui_extras::Column::auto(), 5)
+        .column(egui_extras::Column::remainder())
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .striped(true)
+        .header(20.0, |mut header| {
+            for heading in ["ID", "Name", "Source", "Executor", "", ""] {
+                header.col(|ui| {
+                    ui.heading(heading);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(30.0, pixel_map_ids.len(), |mut row| {
+                let id = pixel_map_ids[row.index()];
+                let Ok(pixel_map) = updatable_handler.pixel_map_mut(id) else {
+                    return;
+                };
+
+                row.col(|ui| {
+                    ui.label(pixel_map.id().to_string());
+                });
+
+                row.col(|ui| {
+                    ui.label(pixel_map.name());
+                });
+
+                row.col(|ui| {
+                    ui.label(pixel_map.source().to_string());
+                });
+
+                row.col(|ui| match pixel_map.executor() {
+                    Some(executor_id) => {
+                        ui.label(executor_id.to_string());
+                    }
+                    None => {
+                        if ui
+                            .selectable_label(pixel_map.is_running(), "Running")
+                            .clicked()
+                        {
+                            if pixel_map.is_running() {
+                                pixel_map.stop();
+                            } else {
+                                pixel_map.start();
+                            }
+                        }
+                    }
+                });
+
+                row.col(|ui| {
+                    if ui.button("Edit").clicked() {
+                        context
+                            .window_handler
+                            .add_window(DemexWindow::Edit(DemexEditWindow::EditPixelMap(id)));
+                    }
+                });
+
+                row.col(|ui| {
+                    if ui.button("Delete").clicked() {
+                        let _ = updatable_handler.delete_pixel_map(id, &mut fixture_handler);
+                    }
+                });
+            });
+        });
+}
@@ -34,7 +34,7 @@ impl<'a> SequenceEditorTab<'a> {
             egui_extras::TableBuilder::new(ui)
                 .columns(egui_extras::Column::auto().at_least(20.0), 2)
                 .column(egui_extras::Column::remainder().at_least(100.0))
-                .columns(egui_extras::Column::auto().at_least(20.0), 8)
+                .columns(egui_extras::Column::auto().at_least(20.0), 12)
                 .striped(true)
                 .header(20.0, |mut header| {
                     header.col(|ui| {
@@ -57,6 +57,18 @@ impl<'a> SequenceEditorTab<'a> {
                         ui.label("In Fade");
                     });
 
+                    header.col(|ui| {
+                        ui.label("Out Delay");
+                    });
+
+                    header.col(|ui| {
+                        ui.label("Out Fade");
+                    });
+
+                    header.col(|ui| {
+                        ui.label("Back Fade");
+                    });
+
                     header.col(|ui| {
                         ui.label("Snap %");
                     });
@@ -65,6 +77,10 @@ impl<'a> SequenceEditorTab<'a> {
                         ui.label("Timing");
                     });
 
+                    header.col(|ui| {
+                        ui.label("Feature Timing");
+                    });
+
                     header.col(|ui| {
                         ui.label("Trigger");
                     });
@@ -138,6 +154,24 @@ impl<'a> SequenceEditorTab<'a> {
                                     .show(ui);
                             });
 
+                            row.col(|ui| {
+                                egui_probe::Probe::new(cue.out_delay_mut())
+                                    .with_header("")
+                                    .show(ui);
+                            });
+
+                            row.col(|ui| {
+                                egui_probe::Probe::new(cue.out_fade_mut())
+                                    .with_header("")
+                                    .show(ui);
+                            });
+
+                            row.col(|ui| {
+                                egui_probe::Probe::new(cue.back_fade_mut())
+                                    .with_header("")
+                                    .show(ui);
+                            });
+
                             row.col(|ui| {
                                 egui_probe::Probe::new(cue.snap_percent_mut())
                                     .with_header("")
@@ -150,6 +184,21 @@ impl<'a> SequenceEditorTab<'a> {
                                     .show(ui);
                             });
 
+                            row.col(|ui| {
+                                ui.label(format!(
+                                    "{} overrides",
+                                    cue.feature_group_timings().len()
+                                ));
+                                if ui.button("Edit").clicked() {
+                                    self.context.window_handler.add_window(DemexWindow::Edit(
+                                        DemexEditWindow::EditSequenceCue(
+                                            sequence_id,
+                                            cue.cue_idx(),
+                                        ),
+                                    ));
+                                }
+                            });
+
                             row.col(|ui| {
                                 egui_probe::Probe::new(cue.trigger_mut())
                                     .with_header("")
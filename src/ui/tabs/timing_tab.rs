@@ -72,4 +72,84 @@ pub fn ui(ui: &mut eframe::egui::Ui, context: &mut super::DemexUiContext) {
                 });
             }
         });
+
+    ui.separator();
+    ui.heading("Timecodes");
+
+    // (timecode id, start)
+    let mut timecode_action: Option<(u32, bool)> = None;
+
+    egui_extras::TableBuilder::new(ui)
+        .id_salt("TimecodesTable")
+        .columns(egui_extras::Column::auto(), 4)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .header(20.0, |mut ui| {
+            ui.col(|ui| {
+                ui.strong("Id");
+            });
+
+            ui.col(|ui| {
+                ui.strong("Name");
+            });
+
+            ui.col(|ui| {
+                ui.strong("State");
+            });
+
+            ui.col(|ui| {
+                ui.strong("Control");
+            });
+        })
+        .body(|mut ui| {
+            for (timecode_id, timecode) in timing_handler
+                .timecodes()
+                .iter()
+                .sorted_by_key(|(id, _)| *id)
+            {
+                ui.row(20.0, |mut ui| {
+                    ui.col(|ui| {
+                        ui.label(timecode_id.to_string());
+                    });
+
+                    ui.col(|ui| {
+                        ui.label(timecode.name());
+                    });
+
+                    ui.col(|ui| {
+                        if timecode.state().is_running() {
+                            ui.colored_label(ecolor::Color32::GREEN, "Running");
+                        } else {
+                            ui.label("Stopped");
+                        }
+                    });
+
+                    ui.col(|ui| {
+                        if timecode.state().is_running() {
+                            if ui.button("Stop").clicked() {
+                                timecode_action = Some((*timecode_id, false));
+                            }
+                        } else if ui.button("Start").clicked() {
+                            timecode_action = Some((*timecode_id, true));
+                        }
+                    });
+                });
+            }
+        });
+
+    drop(timing_handler);
+
+    if let Some((timecode_id, start)) = timecode_action {
+        let mut fixture_handler = context.fixture_handler.write();
+        let mut timing_handler = context.timing_handler.write();
+
+        let result = if start {
+            timing_handler.start_timecode(timecode_id)
+        } else {
+            timing_handler.stop_timecode(timecode_id, &mut fixture_handler)
+        };
+
+        if let Err(err) = result {
+            log::warn!("Failed to control timecode {}: {}", timecode_id, err);
+        }
+    }
 }
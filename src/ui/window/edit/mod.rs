@@ -25,6 +25,7 @@ pub enum DemexEditWindow {
     EditSequence(u32),
     EditSequenceCue(u32, CueIdx),
     EditExecutor(u32),
+    EditPixelMap(u32),
     EditPreset(FixturePresetId),
 
     EditPreset2(FixturePresetId),
@@ -50,6 +51,7 @@ impl DemexEditWindow {
             }
 
             Self::EditExecutor(executor_id) => format!("Executor {}", executor_id),
+            Self::EditPixelMap(pixel_map_id) => format!("Pixel Map {}", pixel_map_id),
             Self::EditPreset(preset_id) | Self::EditPreset2(preset_id) => {
                 format!("Preset {}", preset_id)
             }
@@ -133,6 +135,16 @@ impl DemexEditWindow {
                 )
                 .show(ui);
             }
+            Self::EditPixelMap(pixel_map_id) => {
+                Probe::new(
+                    updatable_handler
+                        .pixel_map_mut(*pixel_map_id)
+                        .map_err(|_| {
+                            DemexUiError::RuntimeError("Pixel map not found".to_owned())
+                        })?,
+                )
+                .show(ui);
+            }
             Self::EditPreset(preset_id) => {
                 Probe::new(preset_handler.get_preset_mut(*preset_id)?).show(ui);
             }
@@ -154,9 +166,39 @@ impl DemexEditWindow {
                     Probe::new(patch.output_configs_mut())
                         .with_header("Outputs")
                         .show(ui);
+
+                    ui.separator();
+
+                    // routes reference outputs by their index in the list above
+                    Probe::new(patch.routing_mut().routes_mut())
+                        .with_header("Universe routing")
+                        .show(ui);
                 }
                 ConfigTypeActionData::Patch => {
                     ui.heading("Patch");
+
+                    for fixture in patch.fixtures_mut() {
+                        let header = format!(
+                            "Output modifiers of fixture {} ({})",
+                            fixture.id, fixture.name
+                        );
+
+                        ui.push_id(fixture.id, |ui| {
+                            Probe::new(&mut fixture.modifiers)
+                                .with_header(&header)
+                                .show(ui);
+                        });
+
+                        // modifiers are applied while generating the output,
+                        // so they take effect without a restart
+                        if let Some(patched_fixture) = fixture_handler.fixture(fixture.id) {
+                            if *patched_fixture.output_modifiers() != fixture.modifiers {
+                                patched_fixture.set_output_modifiers(fixture.modifiers.clone());
+                            }
+                        }
+
+                        ui.separator();
+                    }
                 }
             },
         };
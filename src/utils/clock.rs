@@ -0,0 +1,55 @@
+use std::{cell::Cell, time};
+
+// Start of the virtual clock and the virtual time elapsed since then
+type VirtualClock = (time::Instant, time::Duration);
+
+thread_local! {
+    static VIRTUAL_CLOCK: Cell<Option<VirtualClock>> = const { Cell::new(None) };
+}
+
+/// Returns the current instant. Every piece of time based show data (fades, effects, cue triggers, ..)
+/// uses this instead of `time::Instant::now()`, so shows can be rendered offline on a virtual clock.
+pub fn now() -> time::Instant {
+    VIRTUAL_CLOCK
+        .with(|clock| clock.get())
+        .map(|(started_at, elapsed)| started_at + elapsed)
+        .unwrap_or_else(time::Instant::now)
+}
+
+/// Returns the time elapsed since `instant`, according to `now`.
+pub fn elapsed(instant: time::Instant) -> time::Duration {
+    now().saturating_duration_since(instant)
+}
+
+/// Replaces the clock of the current thread with a virtual clock, that starts
+/// at zero and only advances using `set_virtual_time`.
+pub fn start_virtual_clock() {
+    VIRTUAL_CLOCK.with(|clock| clock.set(Some((time::Instant::now(), time::Duration::ZERO))));
+}
+
+/// Sets the time elapsed since the virtual clock of the current thread was started.
+/// Does nothing, if no virtual clock was started.
+pub fn set_virtual_time(elapsed: time::Duration) {
+    VIRTUAL_CLOCK.with(|clock| {
+        if let Some((started_at, _)) = clock.get() {
+            clock.set(Some((started_at, elapsed)));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time;
+
+    use super::{elapsed, now, set_virtual_time, start_virtual_clock};
+
+    #[test]
+    fn test_virtual_clock() {
+        start_virtual_clock();
+        let started_at = now();
+
+        set_virtual_time(time::Duration::from_millis(2500));
+        assert_eq!(elapsed(started_at), time::Duration::from_millis(2500));
+        assert_eq!(now(), now());
+    }
+}
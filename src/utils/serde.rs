@@ -1,5 +1,5 @@
 pub mod approx_instant {
-    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
     use std::time::{Instant, SystemTime};
 
     pub fn serialize<S>(instant: &Instant, serializer: S) -> Result<S::Ok, S::Error>
@@ -19,8 +19,9 @@ pub mod approx_instant {
         let de = SystemTime::deserialize(deserializer)?;
         let system_now = SystemTime::now();
         let instant_now = Instant::now();
-        let duration = system_now.duration_since(de).map_err(Error::custom)?;
-        let approx = instant_now - duration;
+        // if the sender's clock is ahead of ours, treat the instant as "now"
+        let duration = system_now.duration_since(de).unwrap_or_default();
+        let approx = instant_now.checked_sub(duration).unwrap_or(instant_now);
         Ok(approx)
     }
 }